@@ -0,0 +1,113 @@
+//! `--adaptive-concurrency`: fewer tests at once after resource exhaustion.
+//!
+//! Tests that open many sockets or files may fail when too many of them run
+//! at once, e.g. with `EMFILE`. Such failures are recognized by their
+//! message. The first one at a given limit halves the number of tests that
+//! run at once, and every test that failed at a higher limit than the
+//! current one is retried. A test that exhausts resources with a single task
+//! fails as usual.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::sync::Semaphore;
+
+use crate::report::Failure;
+
+/// Parts of failure messages that mean a resource ran out, with the reason
+/// that is reported for them. Compared in lowercase.
+const SIGNATURES: &[(&str, &str)] = &[
+    ("too many open files", "too many open files"),
+    ("os error 24", "too many open files"),
+    ("emfile", "too many open files"),
+    ("address already in use", "address in use"),
+    ("addrinuse", "address in use"),
+    ("os error 98", "address in use"),
+    ("cannot allocate memory", "out of memory"),
+    ("out of memory", "out of memory"),
+    ("os error 12", "out of memory"),
+];
+
+/// Returns why `failures` look like resource exhaustion, if they do.
+pub(crate) fn exhaustion(failures: &[Failure]) -> Option<&'static str> {
+    failures.iter().find_map(|failure| {
+        let message = failure.message.to_lowercase();
+        SIGNATURES
+            .iter()
+            .find(|(signature, _)| message.contains(signature))
+            .map(|&(_, reason)| reason)
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct Adaptive {
+    /// The semaphore of `--test-tasks`.
+    semaphore: Arc<Semaphore>,
+    /// The number of permits that are left of it.
+    limit: AtomicUsize,
+}
+
+impl Adaptive {
+    pub(crate) fn new(semaphore: Arc<Semaphore>, limit: usize) -> Self {
+        Self {
+            semaphore,
+            limit: AtomicUsize::new(limit),
+        }
+    }
+
+    /// The number of tests that may run at once.
+    pub(crate) fn limit(&self) -> usize {
+        self.limit.load(Ordering::Acquire)
+    }
+
+    /// Halves the limit if it is still `seen`, the limit a test failed at.
+    /// Returns the new limit if this call lowered it.
+    ///
+    /// Completes once the removed permits are released by the tests that
+    /// hold them. The caller keeps its own permit, which is never needed as
+    /// the limit stays at least 1.
+    pub(crate) async fn reduce(&self, seen: usize) -> Option<usize> {
+        let lowered = seen / 2;
+        if lowered == 0 {
+            return None;
+        }
+        self.limit
+            .compare_exchange(seen, lowered, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        self.semaphore
+            .acquire_many((seen - lowered) as u32)
+            .await
+            .unwrap()
+            .forget();
+        Some(lowered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(message: &str) -> Failure {
+        Failure {
+            message: message.to_owned(),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn recognizes_signatures() {
+        assert_eq!(
+            exhaustion(&[failure(
+                "called `Result::unwrap()` on an `Err` value: Os { code: 24, kind: Uncategorized, message: \"Too many open files\" }"
+            )]),
+            Some("too many open files")
+        );
+        assert_eq!(
+            exhaustion(&[failure("bind failed: Address already in use (os error 98)")]),
+            Some("address in use")
+        );
+        assert_eq!(exhaustion(&[failure("assertion failed: ok")]), None);
+    }
+}
@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use clap::{Parser, ValueEnum};
 
+use crate::Filterset;
+
 /// Command line arguments.
 ///
 /// This type represents everything the user can specify via CLI args. The main
@@ -69,6 +73,34 @@ pub struct Arguments {
     )]
     pub quiet: bool,
 
+    /// Run in a reproducible mode: a single thread, fixed seeds and no
+    /// timing-based reporting.
+    #[arg(
+        long = "deterministic",
+        conflicts_with = "test_threads",
+        help = "Run all tests on a single thread with fixed seeds and without slow-test \n\
+            detection, so that a failing run can be replayed step-for-step"
+    )]
+    pub deterministic: bool,
+
+    /// Skip the tests that require the network or another external service.
+    #[arg(
+        long = "offline",
+        help = "Skip the tests marked as requiring the network or another external service, \n\
+            without probing for it. They are counted as offline in the summary"
+    )]
+    pub offline: bool,
+
+    /// Print more detailed information, e.g. the fixtures each test requires
+    /// when listing tests.
+    #[arg(
+        short = 'v',
+        long = "verbose",
+        help = "Print more detailed output: the effective configuration at the start of a run \n\
+            and, with --list, the fixtures each test requires"
+    )]
+    pub verbose: bool,
+
     // ============== OPTIONS =================================================
     /// Number of threads used for parallel testing.
     #[arg(
@@ -85,6 +117,318 @@ pub struct Arguments {
     )]
     pub test_tasks: Option<usize>,
 
+    /// Multiplier for the default number of tasks.
+    #[arg(
+        long = "load-factor",
+        value_name = "FACTOR",
+        conflicts_with = "test_tasks",
+        help = "Run FACTOR times as many tasks as threads by default (default: 1). Tests \n\
+            tagged `heavy` run at a quarter of that"
+    )]
+    pub load_factor: Option<f64>,
+
+    /// Lower the number of tasks when tests run out of resources.
+    #[arg(
+        long = "adaptive-concurrency",
+        help = "Halve the number of tasks when a test fails because it ran out of file \n\
+            descriptors, ports or memory, and retry the tests that failed that way"
+    )]
+    pub adaptive_concurrency: bool,
+
+    /// Stack size of the runtime's worker threads, in bytes.
+    #[arg(
+        long = "thread-stack-size",
+        value_name = "BYTES",
+        help = "Stack size of the threads running tests, for tests that recurse deeply. \n\
+            Does not apply to the main thread, which runs all tests with --test-threads 1"
+    )]
+    pub thread_stack_size: Option<usize>,
+
+    /// Seconds that writing a single event may block before a warning is
+    /// printed. Defaults to 10, 0 disables the check.
+    #[arg(
+        long = "reporter-stall-timeout",
+        value_name = "SECONDS",
+        help = "Warn on the other output stream if writing test output blocks for longer than \n\
+            SECONDS (default: 10, 0 disables the warning)"
+    )]
+    pub reporter_stall_timeout: Option<u64>,
+
+    /// Minimum number of seconds between two `SLOW` lines. Tests that become
+    /// slow in between are reported together. Defaults to 15, 0 reports every
+    /// slow test individually.
+    #[arg(
+        long = "slow-report-interval",
+        value_name = "SECONDS",
+        help = "Report slow tests at most once every SECONDS, summarising tests that became \n\
+            slow in between (default: 15, 0 reports each one)"
+    )]
+    pub slow_report_interval: Option<u64>,
+
+    /// Maximum width of test names in status lines.
+    #[arg(
+        long = "name-width",
+        value_name = "COLUMNS",
+        value_parser = parse_name_width,
+        help = "Shorten test names in status lines to COLUMNS characters by eliding their \n\
+            middle, at least 3. The final summary and reports keep the full names"
+    )]
+    pub name_width: Option<usize>,
+
+    /// Lines of a captured screen shown in the failure.
+    #[arg(
+        long = "screen-lines",
+        value_name = "LINES",
+        help = "Show the first LINES lines of the screen that a failed test captured with \n\
+            Screen::capture (default: 20). The whole screen is saved to a file"
+    )]
+    pub screen_lines: Option<usize>,
+
+    /// Break the summary down by test tag.
+    #[arg(
+        long = "summary-by-tag",
+        help = "After the summary, print how many tests with each tag passed and failed"
+    )]
+    pub summary_by_tag: bool,
+
+    /// Print how the fixtures were used after the summary.
+    #[arg(
+        long = "report-fixtures",
+        help = "After the summary, print how many tests used each fixture, how long it took to \n\
+            set up and how many tests waited for it at once"
+    )]
+    pub report_fixtures: bool,
+
+    /// Print why tasks were idle after the summary.
+    #[arg(
+        long = "report-gaps",
+        help = "After the summary, print the top causes of idle tasks, like waiting on a fixture \n\
+            or a long tail of tests, with how to avoid them"
+    )]
+    pub report_gaps: bool,
+
+    /// Highlight durations over the warning and critical times.
+    #[arg(
+        long = "report-time",
+        help = "Highlight the durations of tests over the warning and critical times, set as \n\
+            WARN_MS,CRITICAL_MS in RUST_TEST_TIME_INTEGRATION (default 500,1000)"
+    )]
+    pub report_time: bool,
+
+    /// Fail tests that run for longer than the critical time.
+    #[arg(
+        long = "ensure-time",
+        help = "Like --report-time, and fail tests that run for longer than the critical time"
+    )]
+    pub ensure_time: bool,
+
+    /// Append every event to this file as it happens.
+    #[arg(
+        long = "run-log",
+        value_name = "PATH",
+        help = "Append every event, including the start of each test, to PATH as JSON lines \n\
+            while the run goes on. If the run is killed, the tests that started without \n\
+            finishing were running"
+    )]
+    pub run_log: Option<String>,
+
+    /// Log the start and end of the run and of every test to the system log.
+    #[arg(
+        long = "log-events",
+        value_enum,
+        value_name = "syslog|journald",
+        help = "Log the start and end of the run and of every test to syslog or journald, \n\
+            with the test name, status and duration as structured fields"
+    )]
+    pub log_events: Option<LogEventsSetting>,
+
+    /// The socket to send `--log-events` messages to.
+    #[arg(
+        long = "log-events-socket",
+        value_name = "PATH",
+        help = "Send the --log-events messages to the socket at PATH instead of /dev/log \n\
+            or /run/systemd/journal/socket"
+    )]
+    pub log_events_socket: Option<String>,
+
+    /// Post the summary of the run to this URL once it finishes.
+    #[cfg(feature = "notify-webhook")]
+    #[arg(
+        long = "notify-webhook",
+        value_name = "URL",
+        help = "Post the summary of the run and a digest of the failures as JSON to URL once \n\
+            it finishes, e.g. to an incoming webhook of a chat channel"
+    )]
+    pub notify_webhook: Option<String>,
+
+    /// Keep a snapshot of the JSON report at this path while the run goes on.
+    #[arg(
+        long = "checkpoint",
+        value_name = "PATH",
+        help = "Keep a snapshot of the JSON report so far at PATH, appended to every \n\
+            --checkpoint-every tests or --checkpoint-interval, and once the run finishes"
+    )]
+    pub checkpoint: Option<String>,
+
+    /// The number of finished tests between two checkpoints.
+    #[arg(
+        long = "checkpoint-every",
+        value_name = "N",
+        requires = "checkpoint",
+        help = "Update the --checkpoint snapshot after every N finished tests (default: 100)"
+    )]
+    pub checkpoint_every: Option<usize>,
+
+    /// The time between two checkpoints.
+    #[arg(
+        long = "checkpoint-interval",
+        value_name = "DURATION",
+        requires = "checkpoint",
+        value_parser = parse_duration,
+        help = "Update the --checkpoint snapshot when a test finishes DURATION after the last \n\
+            update, e.g. `30s` or `5m` (default: 60s)"
+    )]
+    pub checkpoint_interval: Option<Duration>,
+
+    /// Write the final counters of the run to this file.
+    #[arg(
+        long = "metrics-file",
+        value_name = "PATH",
+        help = "Write the counts and durations of the run to PATH in the Prometheus text \n\
+            format once it finishes"
+    )]
+    pub metrics_file: Option<String>,
+
+    /// Write an HTML page with the results to this file.
+    #[arg(
+        long = "html-report",
+        value_name = "PATH",
+        help = "Write a self-contained HTML page with the counts, a chart of durations, the \n\
+            failures and sortable tables of the tests to PATH once the run finishes"
+    )]
+    pub html_report: Option<String>,
+
+    /// Open the `--html-report` in the browser once the run finishes.
+    #[arg(
+        long = "open-report",
+        requires = "html_report",
+        help = "Open the --html-report in the default browser once the run finishes. Does \n\
+            nothing in CI"
+    )]
+    pub open_report: bool,
+
+    /// Write a timeline of the run in the Chrome trace event format to this
+    /// file.
+    #[arg(
+        long = "trace-timeline",
+        value_name = "PATH",
+        help = "Write a timeline of the tests and fixture setups, one lane per slot, to PATH \n\
+            once the run finishes, to open in chrome://tracing or Perfetto"
+    )]
+    pub trace_timeline: Option<String>,
+
+    /// Write a JUnit XML report to this file.
+    #[arg(
+        long = "junit-path",
+        value_name = "FILE",
+        help = "Write the results to FILE as a JUnit XML report once the run finishes, for CI \n\
+            systems that show test results"
+    )]
+    pub junit_path: Option<String>,
+
+    /// Merge the JSON report of the run into this combined report.
+    #[arg(
+        long = "merge-into",
+        value_name = "FILE",
+        help = "Merge the results into the combined JSON report FILE once the run finishes, \n\
+            replacing earlier results of this test binary, so that all binaries of a crate \n\
+            share one report"
+    )]
+    pub merge_into: Option<String>,
+
+    /// Print the stored results of a single test from a report instead of
+    /// running any tests.
+    #[arg(
+        long = "extract-output",
+        value_name = "TEST",
+        requires = "from_report",
+        help = "Print the stored output of TEST from the report given by --from-report \n\
+            instead of running tests"
+    )]
+    pub extract_output: Option<String>,
+
+    /// Print what is registered instead of running any tests.
+    #[arg(
+        long = "explain-registration",
+        help = "Print every registered `tests!` function and `setup!`, and whether it is \n\
+            collected, instead of running tests"
+    )]
+    pub explain_registration: bool,
+
+    /// Only collect what the crate of this binary registered.
+    #[arg(
+        long = "own-crate-only",
+        help = "Only collect the `tests!` functions and setups of the crate of this test \n\
+            binary, not those of libraries linked into it"
+    )]
+    pub own_crate_only: bool,
+
+    /// Print what the harness supports instead of running any tests.
+    #[arg(
+        long = "harness-info",
+        help = "Print the version of the harness, its formats and flags as JSON, instead of \n\
+            running tests"
+    )]
+    pub harness_info: bool,
+
+    /// The suite whose registrations are collected, see
+    /// [`test!`](crate::test).
+    #[arg(
+        long = "suite",
+        value_name = "NAME",
+        help = "Collect the tests and setups registered with `suite = \"NAME\"` instead of \n\
+            those registered without a suite"
+    )]
+    pub suite: Option<String>,
+
+    /// The report read by `--extract-output`.
+    #[arg(
+        long = "from-report",
+        value_name = "PATH",
+        requires = "extract_output",
+        help = "A report written with `--format json --logfile PATH`"
+    )]
+    pub from_report: Option<String>,
+
+    /// An earlier report to compare the results of this run with.
+    #[arg(
+        long = "compare-with",
+        value_name = "PATH",
+        help = "Print the tests that newly fail, pass or are slow, and the tests that were \n\
+            added or removed, compared to a report written with `--format json --logfile PATH`"
+    )]
+    pub compare_with: Option<String>,
+
+    /// Earlier reports to derive a slow period per test from.
+    #[arg(
+        long = "slow-history",
+        value_name = "PATH",
+        help = "Report a test as slow once it runs for --slow-factor times its median duration \n\
+            in PATH, a report written with `--format json --logfile PATH` or a --run-log. \n\
+            Tests that are not in PATH are slow after 15s"
+    )]
+    pub slow_history: Option<String>,
+
+    /// The multiple of the median duration after which a test is slow.
+    #[arg(
+        long = "slow-factor",
+        value_name = "K",
+        requires = "slow_history",
+        help = "With --slow-history, report a test as slow after K times its median duration \n\
+            (default: 3)"
+    )]
+    pub slow_factor: Option<f64>,
+
     /// Path of the logfile. If specified, everything will be written into the
     /// file instead of stdout.
     #[arg(
@@ -99,10 +443,29 @@ pub struct Arguments {
     #[arg(
         long = "skip",
         value_name = "FILTER",
-        help = "Skip tests whose names contain FILTER (this flag can be used multiple times)"
+        help = "Skip tests whose names contain FILTER (this flag can be used multiple times). \n\
+            `requires(Type)` skips tests that require a fixture of that type"
     )]
     pub skip: Vec<String>,
 
+    /// Only run tests with at least one of these tags.
+    #[arg(
+        long = "tag",
+        value_name = "TAG",
+        help = "Only run tests that have TAG. If used multiple times, tests need at least one \n\
+            of the tags"
+    )]
+    pub tag: Vec<String>,
+
+    /// Skip tests with any of these tags.
+    #[arg(
+        long = "exclude-tag",
+        value_name = "TAG",
+        help = "Skip tests that have TAG (this flag can be used multiple times). Takes \n\
+            precedence over --tag"
+    )]
+    pub exclude_tag: Vec<String>,
+
     /// Specifies whether or not to color the output.
     #[arg(
         long = "color",
@@ -119,21 +482,301 @@ pub struct Arguments {
     #[arg(
         long = "format",
         value_enum,
-        value_name = "pretty|terse|json",
+        value_name = "pretty|terse|json|json-pretty|summary-line|github",
         help = "Configure formatting of output: \n\
             - pretty = Print verbose output\n\
-            - terse = Display one character per test\n"
+            - terse = Display one character per test\n\
+            - json = Output a JSON object per event\n\
+            - json-pretty = Output an indented JSON object per event, for debugging\n\
+            - summary-line = Output a single line like `OK 3 passed, 0 failed, 1 skipped in \n\
+              1.2s`, for health checks\n\
+            - github = Print verbose output with groups and error annotations for \n\
+              GitHub Actions, the default when `GITHUB_ACTIONS` is set\n"
     )]
     pub format: Option<FormatSetting>,
 
+    /// Specifies how errors returned by tests are shown.
+    #[arg(
+        long = "error-format",
+        value_enum,
+        value_name = "display|debug|json",
+        help = "Configure how an `Err` returned by a test is shown, followed by its sources: \n\
+            - display = Print the error with `{:#}` (default)\n\
+            - debug = Print the error with `{:?}`\n\
+            - json = Print the error, its `Debug` and its sources as a JSON object\n"
+    )]
+    pub error_format: Option<ErrorFormatSetting>,
+
+    /// Randomly inject yields and small delays into the tests' futures.
+    #[arg(
+        long = "chaos",
+        value_name = "seed=N[,delay=MS]",
+        num_args = 0..=1,
+        default_missing_value = "",
+        value_parser = ChaosSetting::parse,
+        help = "Randomly inject yields and delays (up to MS milliseconds, default 5) at await \n\
+            points. The seed is printed so that a run can be reproduced."
+    )]
+    pub chaos: Option<ChaosSetting>,
+
+    /// Run only this many randomly picked tests.
+    #[arg(
+        long = "sample",
+        value_name = "N",
+        conflicts_with = "sample_percent",
+        help = "Run N randomly picked tests out of those selected by the filters. The seed is \n\
+            printed so that the sample can be reproduced with --sample-seed"
+    )]
+    pub sample: Option<usize>,
+
+    /// Run only this percentage of the tests, picked randomly.
+    #[arg(
+        long = "sample-percent",
+        value_name = "P",
+        help = "Run P percent of the tests selected by the filters, picked randomly"
+    )]
+    pub sample_percent: Option<f64>,
+
+    /// The seed that `--sample` and `--sample-percent` pick tests with.
+    #[arg(
+        long = "sample-seed",
+        value_name = "SEED",
+        help = "Pick the tests for --sample or --sample-percent with SEED (default: random)"
+    )]
+    pub sample_seed: Option<u64>,
+
+    /// Run only one of several disjoint parts of the tests.
+    #[arg(
+        long = "partition",
+        value_name = "count:N/M|hash:N/M",
+        value_parser = PartitionSetting::parse,
+        help = "Run only the Nth of M disjoint parts of the tests, to split a run across M \n\
+            machines. `count` deals the tests out in turn, `hash` assigns them by their name, \n\
+            so that adding a test does not move the others"
+    )]
+    pub partition: Option<PartitionSetting>,
+
+    /// Run the tests in a random order.
+    #[arg(
+        long = "shuffle",
+        help = "Start the tests in a random order. The seed is printed so that the order can \n\
+            be reproduced with --shuffle-seed"
+    )]
+    pub shuffle: bool,
+
+    /// The seed of the order of `--shuffle`.
+    #[arg(
+        long = "shuffle-seed",
+        value_name = "SEED",
+        help = "Like --shuffle, with the order of SEED"
+    )]
+    pub shuffle_seed: Option<u64>,
+
+    /// Run every selected test this many times.
+    #[arg(
+        long = "repeat",
+        value_name = "N",
+        conflicts_with = "repeat_until_failure",
+        help = "Run every selected test N times in a row, stopping a test at its first failure. \n\
+            Every iteration after the first sets up the fixtures of the test again"
+    )]
+    pub repeat: Option<usize>,
+
+    /// Repeat every selected test until it fails or this much time has
+    /// passed.
+    #[arg(
+        long = "repeat-until-failure",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Run every selected test again and again until it fails or has been repeated for \n\
+            DURATION, e.g. `90s`, `10m` or `1h`"
+    )]
+    pub repeat_until_failure: Option<Duration>,
+
+    /// Run every selected test this many times at once and compare the
+    /// outcomes.
+    #[arg(
+        long = "verify-deterministic",
+        value_name = "N",
+        conflicts_with_all = ["repeat", "repeat_until_failure"],
+        help = "Run every selected test N times concurrently, and fail tests whose runs do not \n\
+            all have the same outcome"
+    )]
+    pub verify_deterministic: Option<usize>,
+
+    /// Stop starting tests once the run has taken this long.
+    #[arg(
+        long = "time-budget",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Stop starting new tests once the run has taken DURATION, e.g. `10m`. Running \n\
+            tests finish, and the rest are reported as not run"
+    )]
+    pub time_budget: Option<Duration>,
+
+    /// Cancel the run once it has taken this long.
+    #[arg(
+        long = "run-timeout",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Cancel the run once it has taken DURATION, e.g. `30m`. Running tests are \n\
+            dropped and count as timed out, the rest are reported as not run, and the exit \n\
+            code is 124"
+    )]
+    pub run_timeout: Option<Duration>,
+
+    /// Stop starting tests once a test fails.
+    #[arg(
+        long = "fail-fast",
+        overrides_with = "no_fail_fast",
+        help = "Stop starting new tests once a test fails. Running tests finish, and the rest \n\
+            are reported as not run"
+    )]
+    pub fail_fast: bool,
+
+    /// Run failed tests again, up to this many times.
+    #[arg(
+        long = "retries",
+        value_name = "N",
+        help = "Run failed tests again, up to N more times, unless they set their own retries. \n\
+            Tests that pass on a retry count as passed, and as flaky in the summary"
+    )]
+    pub retries: Option<usize>,
+
+    /// Fail tests as timed out after this long.
+    #[arg(
+        long = "timeout",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Fail tests that run for longer than DURATION, e.g. `60s`, as timed out, unless \n\
+            they set their own timeout. The test is dropped at its next `.await`"
+    )]
+    pub timeout: Option<Duration>,
+
+    /// How long a timed out test that watches for its cancellation gets to
+    /// stop by itself.
+    #[arg(
+        long = "timeout-grace",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Give timed out tests that wait for Cancellation::requested DURATION to clean up \n\
+            before they are dropped (default: 5s)"
+    )]
+    pub timeout_grace: Option<Duration>,
+
+    /// Run all tests, even after a failure. The default.
+    #[arg(
+        long = "no-fail-fast",
+        overrides_with = "fail_fast",
+        help = "Run all tests, even after a failure (default)"
+    )]
+    pub no_fail_fast: bool,
+
+    /// Print an interim summary this often.
+    #[arg(
+        long = "summary-interval",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "Print the counts of the run so far every DURATION, e.g. `5m`, unless a progress \n\
+            bar shows them"
+    )]
+    pub summary_interval: Option<Duration>,
+
+    /// Whether fixtures reuse their cached artifacts.
+    #[arg(
+        long = "fixture-cache",
+        value_enum,
+        value_name = "use|rebuild|off",
+        help = "Configure the artifacts that fixtures cache between runs: \n\
+            - use = reuse cached artifacts that are up to date (default)\n\
+            - rebuild = build all artifacts again and cache them\n\
+            - off = build all artifacts without using the cache\n"
+    )]
+    pub fixture_cache: Option<FixtureCacheSetting>,
+
+    /// Do not use cached fixture artifacts or derived results.
+    #[arg(
+        long = "no-cache",
+        conflicts_with = "fixture_cache",
+        help = "Set up fixtures from scratch, without reading or writing the cache. Same as \n\
+            --fixture-cache off"
+    )]
+    pub no_cache: bool,
+
+    /// Values for the `TestArgs` fixture.
+    #[arg(
+        long = "test-arg",
+        value_name = "KEY=VALUE",
+        value_parser = parse_test_arg,
+        help = "Pass VALUE to the tests as KEY of the `TestArgs` fixture (this flag can be \n\
+            used multiple times)"
+    )]
+    pub test_arg: Vec<(String, String)>,
+
+    /// A dotenv-style file with environment variables for the tests.
+    #[arg(
+        long = "env-file",
+        value_name = "PATH",
+        help = "Set environment variables from the KEY=VALUE lines of PATH before collecting \n\
+            tests. Variables that are already set keep their value"
+    )]
+    pub env_file: Option<String>,
+
+    /// Limits for the values that tests record.
+    #[arg(
+        long = "thresholds",
+        value_name = "PATH",
+        help = "Fail tests whose values recorded with `TestMeta::record` break the limits in \n\
+            the [metrics] table of PATH, e.g. `p99_ms = { max = 50 }`"
+    )]
+    pub thresholds: Option<String>,
+
+    /// How long the per-test artifacts of earlier runs are kept.
+    #[arg(
+        long = "retention",
+        value_name = "PATH",
+        help = "Keep the screens of failing tests for as many runs or days as the [artifacts] \n\
+            table of PATH says, e.g. `retention = { runs = 5 }`, and remove older ones when a \n\
+            run starts (default: 10 runs)"
+    )]
+    pub retention: Option<String>,
+
+    /// What the environment must provide for the tests to run.
+    #[arg(
+        long = "env-requirements",
+        value_name = "PATH",
+        help = "Check the [environment] table of PATH before running any test, e.g. \n\
+            `binaries = [\"docker\"]`, and stop with what is missing instead of running tests \n\
+            that would fail. Also checks `min_open_files` and `env_vars`"
+    )]
+    pub env_requirements: Option<String>,
+
+    /// Expressions that select the tests to run.
+    #[arg(
+        short = 'E',
+        long = "filter-expr",
+        value_name = "EXPR",
+        value_parser = Filterset::parse,
+        help = "Only run tests that match EXPR, e.g. `test(db::) and not tag(slow)`. Supports \n\
+            test(), tag(), requires(), all(), none(), and, or, not and parentheses. If used \n\
+            multiple times, tests need to match one of the expressions, and the FILTERS too"
+    )]
+    pub filter_expr: Vec<Filterset>,
+
     // ============== POSITIONAL VALUES =======================================
     /// Filter string. Only tests which contain this string are run.
     #[arg(
         value_name = "FILTERS",
         help = "The FILTERS strings are tested against the name of all tests, and only those tests \
-                whose names contain the filter are run."
+                whose names contain the filter are run. `requires(Type)` selects the tests that \
+                require a fixture of that type."
     )]
     pub filter: Vec<String>,
+
+    /// Renames every test when it is collected, see
+    /// [`with_name_mapper`](Self::with_name_mapper).
+    #[arg(skip)]
+    pub name_mapper: Option<fn(&str) -> String>,
 }
 
 impl Arguments {
@@ -145,6 +788,20 @@ impl Arguments {
     pub fn from_args() -> Self {
         Parser::parse()
     }
+
+    /// Renames every test as it is collected, e.g. to strip a prefix that
+    /// all tests share. Filters, reports and the output all use the new
+    /// names.
+    ///
+    /// ```no_run
+    /// let args = async_test::Arguments::from_args()
+    ///     .with_name_mapper(|name| name.trim_start_matches("conformance/").to_owned());
+    /// async_test::run(&args).exit();
+    /// ```
+    pub fn with_name_mapper(mut self, mapper: fn(&str) -> String) -> Self {
+        self.name_mapper = Some(mapper);
+        self
+    }
 }
 
 impl<I> FromIterator<I> for Arguments
@@ -179,6 +836,186 @@ pub enum FormatSetting {
 
     /// One character per test. Usefull for test suites with many tests.
     Terse,
+
+    /// One JSON object per line, see the [`report`](crate::report) module.
+    Json,
+
+    /// The objects of `Json`, indented over several lines.
+    JsonPretty,
+
+    /// A single line with the outcome and counts of the run, and nothing
+    /// else, for health checks that feed alerting systems.
+    SummaryLine,
+
+    /// `Pretty`, with the output of failed tests in collapsible groups and
+    /// an error annotation at the location of every failure, for GitHub
+    /// Actions. The default when `GITHUB_ACTIONS` is set and there is no
+    /// `--logfile`.
+    Github,
+}
+
+/// Possible values for the `--log-events` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogEventsSetting {
+    /// RFC 5424 messages with structured data, sent to `/dev/log`.
+    Syslog,
+
+    /// The native protocol of systemd-journald, with `TEST_NAME`,
+    /// `TEST_STATUS` and `TEST_DURATION_SECS` fields.
+    Journald,
+}
+
+/// Possible values for the `--error-format` option, see
+/// [`Trial::fallible`](crate::Trial::fallible).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ErrorFormatSetting {
+    /// The alternate `Display` of the error, `{:#}`.
+    #[default]
+    Display,
+
+    /// The `Debug` of the error, `{:?}`.
+    Debug,
+
+    /// A JSON object with the error's `Display`, `Debug` and sources.
+    Json,
+}
+
+/// Possible values for the `--fixture-cache` option, see the
+/// [`cache`](crate::cache) module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum FixtureCacheSetting {
+    /// Reuse cached fixture artifacts that are up to date.
+    #[default]
+    Use,
+
+    /// Build all fixture artifacts again and cache the new ones.
+    Rebuild,
+
+    /// Build all fixture artifacts without touching the cache.
+    Off,
+}
+
+/// Parses a `--test-arg` value.
+fn parse_test_arg(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, found `{s}`")),
+    }
+}
+
+/// Parses a `--name-width` value. Narrower names would be little more than
+/// the `…`.
+fn parse_name_width(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(width) if width >= 3 => Ok(width),
+        Ok(_) => Err(format!("`{s}` is too narrow, expected at least 3")),
+        Err(e) => Err(format!("{e}")),
+    }
+}
+
+/// Parses a duration like `500ms`, `90s`, `10m` or `1h`. A bare number is
+/// in seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("expected a duration like `90s`, found `{s}`"))?;
+    let secs = |factor: u64| {
+        value
+            .checked_mul(factor)
+            .map(Duration::from_secs)
+            .ok_or_else(|| "duration too large".to_owned())
+    };
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => secs(60),
+        "h" => secs(3600),
+        _ => Err(format!(
+            "unknown unit `{unit}`, expected `ms`, `s`, `m` or `h`"
+        )),
+    }
+}
+
+/// Parsed value of the `--chaos` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaosSetting {
+    /// The seed to derive all injected yields and delays from. A random seed
+    /// is picked if this is `None`.
+    pub seed: Option<u64>,
+
+    /// The upper bound for a single injected delay.
+    pub max_delay: Duration,
+}
+
+impl Default for ChaosSetting {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            max_delay: Duration::from_millis(5),
+        }
+    }
+}
+
+impl ChaosSetting {
+    fn parse(s: &str) -> Result<Self, String> {
+        let mut setting = Self::default();
+        for pair in s.split(',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected `key=value`, found `{pair}`"))?;
+            match key {
+                "seed" => setting.seed = Some(value.parse().map_err(|e| format!("{e}"))?),
+                "delay" => {
+                    setting.max_delay =
+                        Duration::from_millis(value.parse().map_err(|e| format!("{e}"))?)
+                }
+                _ => return Err(format!("unknown key `{key}`, expected `seed` or `delay`")),
+            }
+        }
+        Ok(setting)
+    }
+}
+
+/// Parsed value of the `--partition` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionSetting {
+    /// The tests, sorted by name, are dealt out to the parts in turn.
+    Count { shard: usize, total: usize },
+    /// Each test goes to the part given by a hash of its name.
+    Hash { shard: usize, total: usize },
+}
+
+impl PartitionSetting {
+    fn parse(s: &str) -> Result<Self, String> {
+        let expected = || format!("expected `count:N/M` or `hash:N/M`, found `{s}`");
+        let (kind, part) = s.split_once(':').ok_or_else(expected)?;
+        let (shard, total) = part.split_once('/').ok_or_else(expected)?;
+        let shard: usize = shard.parse().map_err(|_| expected())?;
+        let total: usize = total.parse().map_err(|_| expected())?;
+        if shard == 0 || shard > total {
+            return Err(format!(
+                "the part must be between 1 and {total}, found {shard}"
+            ));
+        }
+        match kind {
+            "count" => Ok(Self::Count { shard, total }),
+            "hash" => Ok(Self::Hash { shard, total }),
+            _ => Err(format!(
+                "unknown partition `{kind}`, expected `count` or `hash`"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PartitionSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count { shard, total } => write!(f, "count:{shard}/{total}"),
+            Self::Hash { shard, total } => write!(f, "hash:{shard}/{total}"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -190,4 +1027,75 @@ mod tests {
         use clap::CommandFactory;
         Arguments::command().debug_assert();
     }
+
+    #[test]
+    fn parse_chaos() {
+        let args = Arguments::from_iter(["test", "--chaos", "seed=42,delay=10"]);
+        assert_eq!(
+            args.chaos,
+            Some(ChaosSetting {
+                seed: Some(42),
+                max_delay: Duration::from_millis(10),
+            })
+        );
+
+        let args = Arguments::from_iter(["test", "--chaos"]);
+        assert_eq!(args.chaos, Some(ChaosSetting::default()));
+
+        assert!(ChaosSetting::parse("seed").is_err());
+        assert!(ChaosSetting::parse("speed=1").is_err());
+    }
+
+    #[test]
+    fn parse_partition() {
+        let args = Arguments::from_iter(["test", "--partition", "count:1/3"]);
+        assert_eq!(
+            args.partition,
+            Some(PartitionSetting::Count { shard: 1, total: 3 })
+        );
+        assert_eq!(
+            PartitionSetting::parse("hash:2/2"),
+            Ok(PartitionSetting::Hash { shard: 2, total: 2 })
+        );
+        assert!(PartitionSetting::parse("count:0/3").is_err());
+        assert!(PartitionSetting::parse("count:4/3").is_err());
+        assert!(PartitionSetting::parse("slice:1/3").is_err());
+        assert!(PartitionSetting::parse("count:1").is_err());
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("1d").is_err());
+        assert!(parse_duration("s").is_err());
+        assert_eq!(
+            parse_duration("999999999999999999h"),
+            Err("duration too large".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_name_widths() {
+        assert_eq!(parse_name_width("20"), Ok(20));
+        assert_eq!(parse_name_width("3"), Ok(3));
+        assert!(parse_name_width("1").is_err());
+        assert!(parse_name_width("wide").is_err());
+    }
+
+    #[test]
+    fn parse_test_args() {
+        assert_eq!(
+            parse_test_arg("profile=ci=readonly"),
+            Ok(("profile".to_owned(), "ci=readonly".to_owned()))
+        );
+        assert_eq!(
+            parse_test_arg("empty="),
+            Ok(("empty".to_owned(), String::new()))
+        );
+        assert!(parse_test_arg("url").is_err());
+        assert!(parse_test_arg("=value").is_err());
+    }
 }
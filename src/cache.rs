@@ -0,0 +1,262 @@
+//! Artifacts that fixtures keep between runs.
+//!
+//! A [`setup!`](crate::setup) function that prepares something expensive,
+//! like a database dump, can keep the result in a [`CachedArtifact`]. If the
+//! artifact is warm, the setup reuses it. If it is cold, the setup builds it
+//! and calls [`CachedArtifact::finish`] so later runs can reuse it.
+//!
+//! Smaller results, like test inputs compiled from sources, can be kept with
+//! [`derived`], keyed by a hash of what they are derived from.
+//!
+//! Whether the cache is used is decided by `--fixture-cache`:
+//!
+//! - `use` (the default): reuse finished artifacts whose key matches,
+//! - `rebuild`: build every artifact again, replacing the cached ones,
+//! - `off`: build every artifact in a temporary directory that is removed
+//!   when the run ends, leaving the cache alone. `--no-cache` is the same.
+//!
+//! Artifacts live under `async-test/fixtures` in the cargo target directory,
+//! derived results under `async-test/cache`.
+
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{chaos::fnv1a, setup_env::SetupEnv, FixtureCacheSetting};
+
+/// A directory that a fixture builds once and reuses in later runs.
+#[derive(Debug)]
+pub struct CachedArtifact {
+    path: PathBuf,
+    /// Marks the artifact as finished. `None` if it is not cached.
+    marker: Option<PathBuf>,
+    warm: bool,
+}
+
+impl CachedArtifact {
+    /// Opens the artifact `name` of the current setup.
+    ///
+    /// `key` describes everything the artifact is built from, e.g. a hash of
+    /// a schema. An artifact built with a different key is stale and is
+    /// replaced when the new one is finished.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of a [`setup!`](crate::setup) function.
+    pub fn open(name: &str, key: &str) -> io::Result<Self> {
+        let env =
+            SetupEnv::current().expect("CachedArtifact::open called outside of a setup! function");
+        let setting = env.fixture_cache;
+        let hash = format!("{:016x}", fnv1a(key.as_bytes()));
+
+        if setting == FixtureCacheSetting::Off {
+            let path = env.scratch.0.join(format!("{name}-{hash}"));
+            reset_dir(&path)?;
+            return Ok(Self {
+                path,
+                marker: None,
+                warm: false,
+            });
+        }
+
+        let dir = artifacts_root().join("fixtures").join(name);
+        let path = dir.join(&hash);
+        let marker = dir.join(format!("{hash}.finished"));
+        let warm = setting == FixtureCacheSetting::Use && marker.exists();
+        if !warm {
+            // Whatever is there was left by a setup that did not finish, or
+            // is to be rebuilt.
+            remove_file(&marker)?;
+            reset_dir(&path)?;
+        }
+        Ok(Self {
+            path,
+            marker: Some(marker),
+            warm,
+        })
+    }
+
+    /// The directory of the artifact. Empty if the artifact is cold.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the artifact was finished by an earlier run and can be used
+    /// as is.
+    pub fn is_warm(&self) -> bool {
+        self.warm
+    }
+
+    /// Marks a cold artifact as built, so that later runs reuse it, and
+    /// removes stale artifacts of the same name.
+    pub fn finish(self) -> io::Result<()> {
+        let Some(marker) = &self.marker else {
+            return Ok(());
+        };
+        if self.warm {
+            return Ok(());
+        }
+        std::fs::write(marker, b"")?;
+        let Some(dir) = self.path.parent() else {
+            return Ok(());
+        };
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path == self.path || path == *marker {
+                continue;
+            }
+            match path.is_dir() {
+                true => std::fs::remove_dir_all(&path)?,
+                false => std::fs::remove_file(&path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The temporary directory of the artifacts of a run with `--fixture-cache
+/// off`, removed once the run and its setups no longer use it.
+#[derive(Debug)]
+pub(crate) struct ScratchDir(PathBuf);
+
+impl Default for ScratchDir {
+    fn default() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        Self(std::env::temp_dir().join(format!("async-test-{}-{id}", std::process::id())))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        // Only created once an artifact is opened.
+        _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The derived results that this process read or wrote, which are kept
+/// when a new result of the same name replaces the others.
+static USED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Tells apart the temporary files of concurrent calls to [`derived`].
+static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+/// Returns what `derive` makes of `input`, reusing the result of an earlier
+/// run that derived it from the same `input`.
+///
+/// Results are stored per `name`, keyed by a hash of `input`. Writing a new
+/// result removes the results of the same name that the test binary did not
+/// use, so stale ones don't pile up.
+///
+/// # Panics
+///
+/// If called outside of a [`setup!`](crate::setup) function.
+pub async fn derived<F, Fut>(name: &str, input: &[u8], derive: F) -> io::Result<Vec<u8>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Vec<u8>>,
+{
+    let setting = SetupEnv::current()
+        .expect("cache::derived called outside of a setup! function")
+        .fixture_cache;
+    if setting == FixtureCacheSetting::Off {
+        return Ok(derive().await);
+    }
+
+    let dir = artifacts_root().join("cache").join(name);
+    let key = format!("{:016x}-{}", fnv1a(input), input.len());
+    let path = dir.join(&key);
+    if setting == FixtureCacheSetting::Use {
+        match std::fs::read(&path) {
+            Ok(output) => {
+                USED.lock().unwrap().insert(path);
+                return Ok(output);
+            }
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            Err(_) => {}
+        }
+    }
+
+    let output = derive().await;
+    // Concurrent calls and runs may derive the same result, whichever is
+    // renamed last is kept.
+    std::fs::create_dir_all(&dir)?;
+    let tmp = dir.join(format!(
+        ".{key}-{}-{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, &output)?;
+    std::fs::rename(&tmp, &path)?;
+    USED.lock().unwrap().insert(path);
+    prune_derived(&dir)?;
+    Ok(output)
+}
+
+/// Removes the results in `dir` that this process did not use, leaving the
+/// temporary files of calls that are still writing.
+fn prune_derived(dir: &Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if !USED.lock().unwrap().contains(&path) {
+            remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The directory that the harness keeps its files in: `async-test` in the
+/// cargo target directory.
+pub(crate) fn artifacts_root() -> PathBuf {
+    if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return PathBuf::from(dir).join("async-test");
+    }
+    // Test binaries live in `<target>/<profile>/deps`.
+    let target = std::env::current_exe().ok().and_then(|exe| {
+        let deps = exe.parent()?;
+        (deps.file_name()? == "deps").then(|| deps.parent()?.parent().map(Path::to_owned))?
+    });
+    target
+        .unwrap_or_else(|| PathBuf::from("target"))
+        .join("async-test")
+}
+
+/// The name of the test binary, without the hash that cargo appends.
+pub(crate) fn binary_name() -> String {
+    let exe = std::env::current_exe().unwrap_or_default();
+    let stem = exe
+        .file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    match stem.rsplit_once('-') {
+        Some((name, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            name.to_owned()
+        }
+        _ => stem,
+    }
+}
+
+fn reset_dir(path: &Path) -> io::Result<()> {
+    match std::fs::remove_dir_all(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    std::fs::create_dir_all(path)
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,99 @@
+//! Asks a test that timed out to stop, see [`Cancellation`].
+//!
+//! Every attempt of a test runs with a token in a task-local, like failed
+//! checks. When the test times out, the token is set, and a test that looked
+//! at it gets `--timeout-grace` to finish its cleanup before its future is
+//! dropped. A test that never looked at it is dropped at once, since it
+//! cannot know that it should stop.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Notify;
+
+tokio::task_local! {
+    static TOKEN: Arc<Token>;
+}
+
+/// Whether the current test should stop, because it timed out.
+///
+/// Tests that hold resources which dropping their future does not clean up,
+/// e.g. a child process or a container, can wait for the cancellation next
+/// to their work, and clean up before the grace period of `--timeout-grace`
+/// (default 5s) ends. The failure of the test says whether it stopped by
+/// itself or was dropped.
+///
+/// ```no_run
+/// use async_test::Cancellation;
+///
+/// async_test::test!(
+///     async fn runs_server() {
+///         let mut server = tokio::process::Command::new("server").spawn().unwrap();
+///         tokio::select! {
+///             _ = server.wait() => {}
+///             () = Cancellation::requested() => server.kill().await.unwrap(),
+///         }
+///     }
+/// );
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Cancellation;
+
+impl Cancellation {
+    /// Completes once the current test is asked to stop.
+    ///
+    /// Only the task of the test itself is asked: outside of a test, e.g. in
+    /// a task it spawned, this never completes.
+    pub async fn requested() {
+        let Ok(token) = TOKEN.try_with(Arc::clone) else {
+            return std::future::pending().await;
+        };
+        token.observed.store(true, Ordering::Release);
+        // Created before checking, so that a request in between is not
+        // missed.
+        let notified = token.notify.notified();
+        if !token.requested.load(Ordering::Acquire) {
+            notified.await;
+        }
+    }
+
+    /// Returns whether the current test was asked to stop. Always `false`
+    /// outside of a test.
+    pub fn is_requested() -> bool {
+        TOKEN
+            .try_with(|token| {
+                token.observed.store(true, Ordering::Release);
+                token.requested.load(Ordering::Acquire)
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// The cancellation of an attempt of a test.
+#[derive(Debug, Default)]
+pub(crate) struct Token {
+    requested: AtomicBool,
+    /// Whether the test looked at the token, and so may stop by itself.
+    observed: AtomicBool,
+    notify: Notify,
+}
+
+impl Token {
+    /// Asks the test to stop, and returns whether it may do so by itself.
+    pub(crate) fn request(&self) -> bool {
+        self.requested.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+        self.observed.load(Ordering::Acquire)
+    }
+}
+
+/// Runs an attempt of a test that `token` can ask to stop.
+pub(crate) async fn scoped<F: Future>(token: Arc<Token>, attempt: F) -> F::Output {
+    TOKEN.scope(token, attempt).await
+}
@@ -0,0 +1,113 @@
+//! `Trial::with_requires_network` and `Trial::with_requires_docker`: tests
+//! that need an external service are skipped, with the reason, when it is
+//! not available, instead of failing with connection errors.
+//!
+//! Each service is probed at most once per run, and only if a test that
+//! would run requires it:
+//!
+//! - The network, by connecting to `ASYNC_TEST_NETWORK_PROBE` as
+//!   `host:port`, or to `1.1.1.1:443` without it.
+//! - Docker, by connecting to the socket in `DOCKER_HOST`, or to the default
+//!   socket of the platform without it.
+//!
+//! With `--offline`, these tests are skipped without probing anything.
+
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    sync::OnceLock,
+    time::Duration,
+};
+
+/// The variable with the address that the network probe connects to.
+const NETWORK_PROBE_VAR: &str = "ASYNC_TEST_NETWORK_PROBE";
+
+/// How long a probe waits for a connection.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// An external service that a test requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Capability {
+    Network,
+    Docker,
+}
+
+impl Capability {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Capability::Network => "network",
+            Capability::Docker => "docker",
+        }
+    }
+}
+
+/// The outcome of probing each service, with why it is unavailable.
+#[derive(Debug, Default)]
+pub(crate) struct Probes {
+    network: OnceLock<Result<(), String>>,
+    docker: OnceLock<Result<(), String>>,
+}
+
+impl Probes {
+    /// Returns why the first of `required` that is unavailable is, if one
+    /// is.
+    pub(crate) fn unavailable(&self, required: &[Capability]) -> Option<String> {
+        required.iter().find_map(|&capability| {
+            let probe = match capability {
+                Capability::Network => self.network.get_or_init(probe_network),
+                Capability::Docker => self.docker.get_or_init(probe_docker),
+            };
+            probe
+                .as_ref()
+                .err()
+                .map(|reason| format!("requires {}, but {reason}", capability.as_str()))
+        })
+    }
+}
+
+fn probe_network() -> Result<(), String> {
+    let target = std::env::var(NETWORK_PROBE_VAR).unwrap_or_else(|_| "1.1.1.1:443".to_owned());
+    connect_tcp(&target).map_err(|e| format!("{target} is unreachable: {e}"))
+}
+
+fn probe_docker() -> Result<(), String> {
+    let host = std::env::var("DOCKER_HOST").ok();
+    let host = host.as_deref().unwrap_or(DEFAULT_DOCKER_HOST);
+    let connected = match host.split_once("://") {
+        Some(("tcp", address)) => connect_tcp(address),
+        Some(("unix", path)) => connect_unix(path),
+        Some(("npipe", path)) => std::fs::metadata(path.replace('/', "\\")).map(drop),
+        _ => return Err(format!("DOCKER_HOST `{host}` is not supported")),
+    };
+    connected.map_err(|e| format!("{host} is unavailable: {e}"))
+}
+
+#[cfg(windows)]
+const DEFAULT_DOCKER_HOST: &str = "npipe:////./pipe/docker_engine";
+#[cfg(not(windows))]
+const DEFAULT_DOCKER_HOST: &str = "unix:///var/run/docker.sock";
+
+fn connect_tcp(address: &str) -> std::io::Result<()> {
+    let mut last_error = None;
+    for address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, PROBE_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "no addresses to connect to")
+    }))
+}
+
+#[cfg(unix)]
+fn connect_unix(path: &str) -> std::io::Result<()> {
+    std::os::unix::net::UnixStream::connect(path).map(drop)
+}
+
+#[cfg(not(unix))]
+fn connect_unix(_: &str) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "unix sockets are not supported on this platform",
+    ))
+}
@@ -0,0 +1,118 @@
+//! Latency injection for `--chaos`.
+//!
+//! Every test future is wrapped in a [`Chaos`] shim. Before polling the inner
+//! future, the shim may decide to yield back to the runtime (waking itself
+//! immediately) or to sleep for a short, random amount of time. Each injection
+//! consumes from a fixed budget so that a test can never be slowed down
+//! indefinitely.
+//!
+//! All decisions are derived from the run seed and the test name, so a failure
+//! can be reproduced by passing the printed seed back via `--chaos seed=N`.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use tokio::time::Sleep;
+
+use crate::{ChaosSetting, Fut};
+
+/// The maximum number of yields and delays injected into a single test.
+const BUDGET: u32 = 64;
+
+/// The resolved chaos configuration for a single run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChaosConfig {
+    seed: u64,
+    max_delay: Duration,
+}
+
+impl ChaosConfig {
+    /// Resolves the CLI setting, picking a random seed if none was given.
+    ///
+    /// In deterministic mode the fallback seed is fixed instead.
+    pub(crate) fn new(setting: &ChaosSetting, deterministic: bool) -> Self {
+        let fallback = || match deterministic {
+            true => 0,
+            false => fastrand::u64(..),
+        };
+        Self {
+            seed: setting.seed.unwrap_or_else(fallback),
+            max_delay: setting.max_delay,
+        }
+    }
+
+    /// The seed used for this run.
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Wraps the future of the test called `name`.
+    pub(crate) fn wrap(&self, name: &str, inner: Fut) -> Fut {
+        Box::pin(Chaos {
+            inner,
+            // Mixing in the name keeps the schedule of one test independent of
+            // which other tests were selected and in which order they started.
+            rng: fastrand::Rng::with_seed(self.seed ^ fnv1a(name.as_bytes())),
+            budget: BUDGET,
+            max_delay: self.max_delay,
+            delay: None,
+        })
+    }
+}
+
+struct Chaos {
+    inner: Fut,
+    rng: fastrand::Rng,
+    budget: u32,
+    max_delay: Duration,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl Future for Chaos {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+
+        if let Some(delay) = &mut this.delay {
+            if delay.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            this.delay = None;
+        } else if this.budget > 0 {
+            match this.rng.u8(..4) {
+                0 => {
+                    this.budget -= 1;
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                1 if !this.max_delay.is_zero() => {
+                    this.budget -= 1;
+                    let nanos = this.rng.u64(..=this.max_delay.as_nanos() as u64);
+                    let mut delay = Box::pin(tokio::time::sleep(Duration::from_nanos(nanos)));
+                    if delay.as_mut().poll(cx).is_pending() {
+                        this.delay = Some(delay);
+                        return Poll::Pending;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        this.inner.as_mut().poll(cx)
+    }
+}
+
+/// A small, stable hash so that seeds mean the same thing across Rust versions.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
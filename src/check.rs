@@ -0,0 +1,65 @@
+//! Soft assertions, see [`check!`](crate::check).
+//!
+//! Failed checks are recorded in a task-local list that lives as long as the
+//! test. When the test finishes, a non-empty list turns its outcome into a
+//! failure, with the panic after the checks if there was one. An error that
+//! the test returned is recorded last, like a check.
+
+use std::{cell::RefCell, error::Error, future::Future, panic::Location};
+
+use crate::{report::Failure, returned, ErrorFormatSetting, Outcome};
+
+tokio::task_local! {
+    static FAILED_CHECKS: RefCell<Vec<Failure>>;
+    static ERROR_FORMAT: ErrorFormatSetting;
+}
+
+/// Records a failed check of the current test.
+///
+/// Outside of a test there is nothing to fail at the end, so this panics
+/// like `assert!` would.
+#[track_caller]
+pub fn record(message: String) {
+    let location = Location::caller();
+    let recorded = FAILED_CHECKS.try_with(|checks| {
+        checks.borrow_mut().push(Failure {
+            message: format!("check failed at '{message}', {location}"),
+            location: Some(location.to_string()),
+        })
+    });
+    if recorded.is_err() {
+        panic!("{message}");
+    }
+}
+
+/// Records the error that the current test returned.
+pub(crate) fn record_error(error: Box<dyn Error + Send + Sync>) {
+    let recorded = FAILED_CHECKS.try_with(|checks| {
+        let format = ERROR_FORMAT.get();
+        checks.borrow_mut().push(returned::render(&*error, format))
+    });
+    if recorded.is_err() {
+        panic!("test returned an error: {error}");
+    }
+}
+
+/// Runs a test, failing it if any of its checks failed or it returned an
+/// error, which is rendered with `error_format`.
+pub(crate) async fn checked(
+    error_format: ErrorFormatSetting,
+    test: impl Future<Output = Outcome>,
+) -> Outcome {
+    let checked = FAILED_CHECKS.scope(RefCell::default(), async {
+        let outcome = test.await;
+        (outcome, FAILED_CHECKS.with(RefCell::take))
+    });
+    let (outcome, mut failures) = ERROR_FORMAT.scope(error_format, checked).await;
+    match outcome {
+        Outcome::Passed if failures.is_empty() => Outcome::Passed,
+        Outcome::Passed => Outcome::Failed(failures),
+        Outcome::Failed(panic) => {
+            failures.extend(panic);
+            Outcome::Failed(failures)
+        }
+    }
+}
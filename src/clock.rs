@@ -0,0 +1,122 @@
+//! The time source of a run.
+//!
+//! Slow-test ticks, test durations, the batching of slow reports and interim
+//! summaries all go through a [`Clock`]. A real run uses [`SystemClock`]. With a [`MockClock`],
+//! time only moves when [`MockClock::advance`] is called, so reporting of
+//! slow tests can be tested without waiting for the slow period.
+//!
+//! Delays injected by `--chaos`, the reporter stall warning, report
+//! checkpoints and the detection of a suspended process always use real time,
+//! as they are about the scheduling and I/O of the real system. Time that the
+//! process was suspended for is taken out of test durations, slow periods and
+//! timeouts, so a test is not slow just because the terminal was paused.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+/// A source of monotonic time.
+///
+/// Pass one to [`run_with_clock`](crate::run_with_clock).
+pub trait Clock: fmt::Debug + Send + Sync + 'static {
+    /// The current time.
+    fn now(&self) -> Instant;
+
+    /// Completes once `duration` has passed on this clock.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// The real time, as seen by the tokio runtime.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    now: Instant,
+    /// Every sleep that was polled since the last advance.
+    sleepers: Vec<Waker>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                now: Instant::now(),
+                sleepers: vec![],
+            })),
+        }
+    }
+
+    /// Moves the clock forward, waking up sleeps that are due.
+    pub fn advance(&self, by: Duration) {
+        let sleepers = {
+            let mut state = self.state.lock().unwrap();
+            state.now += by;
+            std::mem::take(&mut state.sleepers)
+        };
+        // Sleeps that are not due yet register themselves again.
+        for waker in sleepers {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.state.lock().unwrap().now
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(MockSleep {
+            deadline: self.now() + duration,
+            state: self.state.clone(),
+        })
+    }
+}
+
+struct MockSleep {
+    deadline: Instant,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.now >= self.deadline {
+            return Poll::Ready(());
+        }
+        state.sleepers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
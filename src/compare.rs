@@ -0,0 +1,117 @@
+//! `--compare-with`: reports what changed since an earlier run.
+//!
+//! The earlier run is read from a report written with
+//! `--format json --logfile PATH`. Results of the current run are fed in as
+//! they finish, and the differences are reported right before the summary.
+
+use std::{collections::HashMap, process, time::Duration};
+
+use serde_json::Value;
+
+use crate::report::{Comparison, TestChange};
+
+#[derive(Debug)]
+struct Previous {
+    passed: bool,
+    slow: bool,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+pub(crate) struct Compare {
+    /// Every test in the earlier report that has not been seen in this run
+    /// yet. `None` for tests that were skipped.
+    previous: HashMap<String, Option<Previous>>,
+    comparison: Comparison,
+}
+
+impl Compare {
+    /// Reads the report at `path`, exiting if it cannot be read.
+    pub(crate) fn load(path: &str) -> Self {
+        let report = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: failed to read report {path}: {e}");
+            process::exit(1)
+        });
+
+        let mut previous = HashMap::new();
+        // Lines that are not JSON objects are skipped, like in `--extract-output`.
+        for record in report
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        {
+            let Some(name) = record["name"].as_str() else {
+                continue;
+            };
+            match record["type"].as_str() {
+                Some("test-finished") => {
+                    let previous_result = Previous {
+                        passed: record["status"] == "passed",
+                        slow: record["slow"] == true,
+                        duration: Duration::from_secs_f64(
+                            record["duration_secs"].as_f64().unwrap_or(0.0).max(0.0),
+                        ),
+                    };
+                    previous.insert(name.to_owned(), Some(previous_result));
+                }
+                Some("test-skipped") => {
+                    previous.entry(name.to_owned()).or_insert(None);
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            previous,
+            comparison: Comparison {
+                baseline: path.to_owned(),
+                newly_failing: vec![],
+                newly_passing: vec![],
+                newly_slow: vec![],
+                added: vec![],
+                removed: vec![],
+            },
+        }
+    }
+
+    /// Records the result of a test that ran.
+    pub(crate) fn finished(&mut self, name: &str, passed: bool, slow: bool, duration: Duration) {
+        let previous = match self.previous.remove(name) {
+            Some(Some(previous)) => previous,
+            // It existed, but there is nothing to compare against.
+            Some(None) => return,
+            None => return self.comparison.added.push(name.to_owned()),
+        };
+
+        let change = || TestChange {
+            name: name.to_owned(),
+            duration_secs: duration.as_secs_f64(),
+            previous_duration_secs: previous.duration.as_secs_f64(),
+        };
+        match (previous.passed, passed) {
+            (true, false) => self.comparison.newly_failing.push(change()),
+            (false, true) => self.comparison.newly_passing.push(change()),
+            _ => {}
+        }
+        if slow && !previous.slow {
+            self.comparison.newly_slow.push(change());
+        }
+    }
+
+    /// Records a test that was collected but not run.
+    pub(crate) fn skipped(&mut self, name: &str) {
+        self.previous.remove(name);
+    }
+
+    /// The differences, with every list sorted by name.
+    pub(crate) fn finish(self) -> Comparison {
+        let mut comparison = self.comparison;
+        comparison.removed = self.previous.into_keys().collect();
+
+        comparison.newly_failing.sort_by(|a, b| a.name.cmp(&b.name));
+        comparison.newly_passing.sort_by(|a, b| a.name.cmp(&b.name));
+        comparison.newly_slow.sort_by(|a, b| a.name.cmp(&b.name));
+        comparison.added.sort();
+        comparison.removed.sort();
+        comparison
+    }
+}
@@ -0,0 +1,428 @@
+//! The effective configuration of a run.
+//!
+//! [`Arguments`] only holds what the user asked for. [`RunConfig`] is what the
+//! harness actually does after defaults and environment detection have been
+//! applied. It is printed at the start of the run with `--verbose`.
+
+use std::{fmt, num::NonZeroUsize, sync::Arc, time::Duration};
+
+use crate::{
+    chaos::ChaosConfig,
+    clock::{Clock, SystemClock},
+    repeat::Repeat,
+    report_time::TimeThresholds,
+    retention::Retention,
+    sample::SampleConfig,
+    shuffle::ShuffleConfig,
+    slow_history::{self, SlowHistory},
+    thresholds::Thresholds,
+    Arguments, ColorSetting, ErrorFormatSetting, FixtureCacheSetting, FormatSetting,
+    PartitionSetting, TestArgs,
+};
+
+/// Environment variables set by common CI providers.
+const CI_VARS: &[&str] = &[
+    "CI",
+    "CONTINUOUS_INTEGRATION",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "BUILDKITE",
+    "TF_BUILD",
+];
+
+/// Tests with this tag run with less concurrency by default.
+pub(crate) const HEAVY_TAG: &str = "heavy";
+
+/// The memory set aside for each concurrent task when picking a default for
+/// `--test-tasks`.
+const MEMORY_PER_TASK: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug)]
+pub(crate) struct RunConfig {
+    /// Number of runtime threads, including the main thread.
+    pub(crate) threads: NonZeroUsize,
+    /// Maximum number of concurrently running tests and setups.
+    pub(crate) tasks: NonZeroUsize,
+    /// Maximum number of concurrently running tests tagged [`HEAVY_TAG`].
+    pub(crate) heavy_tasks: NonZeroUsize,
+    /// Whether `tasks` is lowered when tests run out of resources.
+    pub(crate) adaptive_concurrency: bool,
+    /// Stack size of the runtime's worker threads, if not the default.
+    pub(crate) thread_stack_size: Option<usize>,
+    /// The period after which a test is reported as slow, if any.
+    pub(crate) slow_period: Option<Duration>,
+    /// Slow periods per test from `--slow-history`, in place of
+    /// `slow_period`.
+    pub(crate) slow_history: Option<SlowHistory>,
+    /// The minimum time between two reports of slow tests.
+    pub(crate) slow_report_interval: Duration,
+    /// Whether events are written as JSON.
+    pub(crate) json: bool,
+    /// Whether JSON events are indented.
+    pub(crate) json_pretty: bool,
+    /// Whether only the summary line is printed.
+    pub(crate) summary_line: bool,
+    /// Whether failures are annotated for GitHub Actions.
+    pub(crate) github: bool,
+    /// How errors returned by tests are rendered.
+    pub(crate) error_format: ErrorFormatSetting,
+    pub(crate) deterministic: bool,
+    pub(crate) chaos: Option<ChaosConfig>,
+    pub(crate) sample: Option<SampleConfig>,
+    pub(crate) shuffle: Option<ShuffleConfig>,
+    pub(crate) repeat: Option<Repeat>,
+    /// The number of concurrent runs of every test with
+    /// `--verify-deterministic`.
+    pub(crate) verify_deterministic: Option<NonZeroUsize>,
+    /// The time after which no more tests are started.
+    pub(crate) time_budget: Option<Duration>,
+    /// Whether no more tests are started after a failure.
+    pub(crate) fail_fast: bool,
+    /// The retries of tests that don't set their own.
+    pub(crate) retries: usize,
+    /// The timeout of tests that don't set their own.
+    pub(crate) timeout: Option<Duration>,
+    /// How long a timed out test that watches for its cancellation gets to
+    /// stop by itself.
+    pub(crate) timeout_grace: Duration,
+    /// The time after which running tests are dropped and no more start.
+    pub(crate) run_timeout: Option<Duration>,
+    /// Whether tests that require external services are skipped.
+    pub(crate) offline: bool,
+    /// How often an interim summary is printed.
+    pub(crate) summary_interval: Option<Duration>,
+    pub(crate) fixture_cache: FixtureCacheSetting,
+    /// The values of the `TestArgs` fixture.
+    pub(crate) test_args: Arc<TestArgs>,
+    /// The keys of the variables set by `--env-file`.
+    pub(crate) env_file_keys: Vec<String>,
+    /// The limits of recorded values from `--thresholds`.
+    pub(crate) thresholds: Thresholds,
+    /// How long per-test artifacts are kept, from `--retention`.
+    pub(crate) retention: Retention,
+    /// Whether a CI environment was detected.
+    pub(crate) ci: bool,
+    /// Whether the reporter output is colorized.
+    pub(crate) colorize: bool,
+    pub(crate) verbose: bool,
+    /// How long writing a single event may block before warning about it.
+    pub(crate) stall_timeout: Option<Duration>,
+    /// The maximum width of test names in status lines.
+    pub(crate) name_width: Option<usize>,
+    /// The lines of a captured screen shown in the failure.
+    pub(crate) screen_lines: usize,
+    /// Whether the summary is broken down by tag.
+    pub(crate) summary_by_tag: bool,
+    /// Whether fixture usage is printed after the summary.
+    pub(crate) report_fixtures: bool,
+    /// Whether the causes of idle tasks are printed after the summary.
+    pub(crate) report_gaps: bool,
+    /// The times of `--report-time` and `--ensure-time`.
+    pub(crate) report_time: Option<TimeThresholds>,
+    /// The time source for slow ticks and durations.
+    pub(crate) clock: Arc<dyn Clock>,
+    filters: Filters,
+}
+
+#[derive(Debug)]
+struct Filters {
+    filter: Vec<String>,
+    skip: Vec<String>,
+    expr: Vec<String>,
+    tag: Vec<String>,
+    exclude_tag: Vec<String>,
+    exact: bool,
+    ignored: &'static str,
+    partition: Option<PartitionSetting>,
+}
+
+impl RunConfig {
+    pub(crate) fn new(args: &Arguments) -> Self {
+        let threads = match args.test_threads.and_then(NonZeroUsize::new) {
+            // A single thread makes the order in which tasks are polled reproducible.
+            _ if args.deterministic => NonZeroUsize::new(1).unwrap(),
+            None => std::thread::available_parallelism().unwrap_or(NonZeroUsize::new(1).unwrap()),
+            Some(num_threads) => num_threads,
+        };
+
+        // An explicit `--test-tasks` applies to every test. Otherwise scale
+        // with the threads, leave enough memory for every task and run heavy
+        // tests at a quarter of that.
+        let (tasks, heavy_tasks) = match args.test_tasks.and_then(NonZeroUsize::new) {
+            Some(tasks) => (tasks, tasks),
+            None => {
+                let mut tasks = (threads.get() as f64 * args.load_factor.unwrap_or(1.0)) as usize;
+                if let Some(available) = available_memory() {
+                    tasks = tasks.min((available / MEMORY_PER_TASK) as usize);
+                }
+                let tasks = NonZeroUsize::new(tasks).unwrap_or(NonZeroUsize::new(1).unwrap());
+                let heavy_tasks =
+                    NonZeroUsize::new(tasks.get() / 4).unwrap_or(NonZeroUsize::new(1).unwrap());
+                (tasks, heavy_tasks)
+            }
+        };
+
+        let json = matches!(
+            args.format,
+            Some(FormatSetting::Json | FormatSetting::JsonPretty)
+        );
+
+        let colorize = match args.color.unwrap_or(ColorSetting::Auto) {
+            ColorSetting::Auto => {
+                args.logfile.is_none()
+                    && supports_color::on(supports_color::Stream::Stderr)
+                        .map_or(false, |x| x.has_basic)
+            }
+            ColorSetting::Always => true,
+            ColorSetting::Never => false,
+        };
+
+        Self {
+            threads,
+            tasks,
+            heavy_tasks,
+            thread_stack_size: args.thread_stack_size,
+            // Slow ticks depend on wall-clock time, so they are disabled when
+            // the run should be replayable.
+            slow_period: (!args.deterministic).then_some(Duration::from_secs(15)),
+            slow_history: args.slow_history.as_deref().map(|path| {
+                SlowHistory::load(
+                    path,
+                    args.slow_factor.unwrap_or(slow_history::DEFAULT_FACTOR),
+                )
+            }),
+            // The JSON stream reports every tick, consumers can aggregate them.
+            slow_report_interval: match json {
+                true => Duration::ZERO,
+                false => Duration::from_secs(args.slow_report_interval.unwrap_or(15)),
+            },
+            json,
+            json_pretty: args.format == Some(FormatSetting::JsonPretty),
+            summary_line: args.format == Some(FormatSetting::SummaryLine),
+            github: match args.format {
+                Some(format) => format == FormatSetting::Github,
+                // Like colors, annotations are only for the log of the job.
+                None => {
+                    args.logfile.is_none()
+                        && std::env::var_os("GITHUB_ACTIONS").map_or(false, |v| v == "true")
+                }
+            },
+            error_format: args.error_format.unwrap_or_default(),
+            deterministic: args.deterministic,
+            chaos: args
+                .chaos
+                .as_ref()
+                .map(|setting| ChaosConfig::new(setting, args.deterministic)),
+            sample: SampleConfig::new(args),
+            shuffle: ShuffleConfig::new(args),
+            repeat: Repeat::new(args),
+            verify_deterministic: args.verify_deterministic.and_then(NonZeroUsize::new),
+            time_budget: args.time_budget,
+            fail_fast: args.fail_fast && !args.no_fail_fast,
+            retries: args.retries.unwrap_or(0),
+            timeout: args.timeout.filter(|timeout| !timeout.is_zero()),
+            timeout_grace: args.timeout_grace.unwrap_or(Duration::from_secs(5)),
+            run_timeout: args.run_timeout,
+            offline: args.offline,
+            summary_interval: args.summary_interval.filter(|interval| !interval.is_zero()),
+            fixture_cache: match args.no_cache {
+                true => FixtureCacheSetting::Off,
+                false => args.fixture_cache.unwrap_or_default(),
+            },
+            test_args: Arc::new(TestArgs::new(args.test_arg.iter().cloned())),
+            env_file_keys: vec![],
+            thresholds: Thresholds::default(),
+            retention: Retention::default(),
+            ci: CI_VARS.iter().any(|var| std::env::var_os(var).is_some()),
+            colorize,
+            verbose: args.verbose,
+            stall_timeout: match args.reporter_stall_timeout {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => Some(Duration::from_secs(10)),
+            },
+            name_width: args.name_width,
+            screen_lines: args.screen_lines.unwrap_or(20),
+            summary_by_tag: args.summary_by_tag,
+            report_fixtures: args.report_fixtures,
+            report_gaps: args.report_gaps,
+            report_time: (args.report_time || args.ensure_time)
+                .then(|| TimeThresholds::from_env(args.ensure_time)),
+            adaptive_concurrency: args.adaptive_concurrency,
+            clock: Arc::new(SystemClock),
+            filters: Filters {
+                filter: args.filter.clone(),
+                skip: args.skip.clone(),
+                expr: args
+                    .filter_expr
+                    .iter()
+                    .map(|expr| expr.to_string())
+                    .collect(),
+                tag: args.tag.clone(),
+                exclude_tag: args.exclude_tag.clone(),
+                exact: args.exact,
+                ignored: match (args.ignored, args.include_ignored) {
+                    (true, _) => "only",
+                    (false, true) => "include",
+                    (false, false) => "exclude",
+                },
+                partition: args.partition,
+            },
+        }
+    }
+
+    /// The lines printed at the start of a verbose run.
+    pub(crate) fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        let slow = match self.slow_period {
+            Some(period) => format!("{}s", period.as_secs_f64()),
+            None => "off".to_owned(),
+        };
+        let slow_history = match &self.slow_history {
+            Some(history) => format!("{}x-median-of-{}-tests", history.factor(), history.len()),
+            None => "off".to_owned(),
+        };
+        let chaos = match &self.chaos {
+            Some(chaos) => format!("seed={}", chaos.seed()),
+            None => "off".to_owned(),
+        };
+        let sample = match &self.sample {
+            Some(sample) => format!("seed={}", sample.seed()),
+            None => "off".to_owned(),
+        };
+        let partition = match self.filters.partition {
+            Some(partition) => partition.to_string(),
+            None => "off".to_owned(),
+        };
+        let shuffle = match &self.shuffle {
+            Some(shuffle) => format!("seed={}", shuffle.seed()),
+            None => "off".to_owned(),
+        };
+        let repeat = match self.repeat {
+            Some(Repeat::Times(times)) => times.to_string(),
+            Some(Repeat::UntilFailure(duration)) => {
+                format!("until-failure-{}s", duration.as_secs_f64())
+            }
+            None => "off".to_owned(),
+        };
+        let verify = match self.verify_deterministic {
+            Some(runs) => runs.to_string(),
+            None => "off".to_owned(),
+        };
+        let budget = match self.time_budget {
+            Some(budget) => format!("{}s", budget.as_secs_f64()),
+            None => "off".to_owned(),
+        };
+        let timeout = match self.timeout {
+            Some(timeout) => format!("{}s", timeout.as_secs_f64()),
+            None => "off".to_owned(),
+        };
+        let run_timeout = match self.run_timeout {
+            Some(run_timeout) => format!("{}s", run_timeout.as_secs_f64()),
+            None => "off".to_owned(),
+        };
+        let fixture_cache = match self.fixture_cache {
+            FixtureCacheSetting::Use => "use",
+            FixtureCacheSetting::Rebuild => "rebuild",
+            FixtureCacheSetting::Off => "off",
+        };
+        let report_time = match self.report_time {
+            Some(times) => format!(
+                "warn-{}s-critical-{}s{}",
+                times.warn.as_secs_f64(),
+                times.critical.as_secs_f64(),
+                if times.ensure { "-ensured" } else { "" }
+            ),
+            None => "off".to_owned(),
+        };
+        let error_format = match self.error_format {
+            ErrorFormatSetting::Display => "display",
+            ErrorFormatSetting::Debug => "debug",
+            ErrorFormatSetting::Json => "json",
+        };
+        let f = &self.filters;
+
+        [
+            format!(
+                "threads={} tasks={} heavy-tasks={} adaptive-concurrency={} slow-period={slow} \
+                slow-history={slow_history} slow-report-interval={}s deterministic={} chaos={chaos} repeat={repeat} \
+                verify-deterministic={verify} time-budget={budget} run-timeout={run_timeout} fail-fast={} retries={} timeout={timeout} \
+                timeout-grace={}s fixture-cache={fixture_cache}",
+                self.threads,
+                self.tasks,
+                self.heavy_tasks,
+                self.adaptive_concurrency,
+                self.slow_report_interval.as_secs(),
+                self.deterministic,
+                self.fail_fast,
+                self.retries,
+                self.timeout_grace.as_secs_f64(),
+            ),
+            format!(
+                "filter={} skip={} expr={} tag={} exclude-tag={} exact={} ignored={} offline={} \
+                partition={partition} sample={sample} shuffle={shuffle}",
+                List(&f.filter),
+                List(&f.skip),
+                List(&f.expr),
+                List(&f.tag),
+                List(&f.exclude_tag),
+                f.exact,
+                f.ignored,
+                self.offline,
+            ),
+            format!(
+                "ci={} color={} reporter-stall-timeout={} thread-stack-size={} name-width={} \
+                screen-lines={} summary-by-tag={} summary-interval={} test-args={} env-file-keys={} \
+                error-format={error_format} report-fixtures={} report-gaps={} thresholds={} \
+                retention={} github={} report-time={report_time}",
+                self.ci,
+                self.colorize,
+                match self.stall_timeout {
+                    Some(timeout) => format!("{}s", timeout.as_secs()),
+                    None => "off".to_owned(),
+                },
+                match self.thread_stack_size {
+                    Some(size) => size.to_string(),
+                    None => "default".to_owned(),
+                },
+                match self.name_width {
+                    Some(width) => width.to_string(),
+                    None => "full".to_owned(),
+                },
+                self.screen_lines,
+                self.summary_by_tag,
+                match self.summary_interval {
+                    Some(interval) => format!("{}s", interval.as_secs_f64()),
+                    None => "off".to_owned(),
+                },
+                // Only the keys, the values may be secrets.
+                List(&self.test_args.keys().map(str::to_owned).collect::<Vec<_>>()),
+                List(&self.env_file_keys),
+                self.report_fixtures,
+                self.report_gaps,
+                List(&self.thresholds.keys()),
+                self.retention,
+                self.github,
+            ),
+        ]
+        .into_iter()
+    }
+}
+
+/// The memory available to new processes, in bytes, if it is known.
+fn available_memory() -> Option<u64> {
+    // Only Linux exposes this without platform-specific APIs.
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+struct List<'a>(&'a [String]);
+
+impl fmt::Display for List<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.0.join(","))
+    }
+}
@@ -0,0 +1,117 @@
+//! [`RunHandle`]: controls a run from outside of it.
+//!
+//! Tests check the handle right before they start, so pausing and canceling
+//! only hold back tests that have not started yet. Running tests always
+//! finish and are reported as usual.
+
+use std::future::Future;
+
+use tokio::sync::watch;
+
+/// The state requested through a [`RunHandle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Control {
+    Running,
+    Paused,
+    Canceled(String),
+}
+
+/// Pauses, resumes or cancels a run from another thread or task, e.g. from a
+/// TUI that wraps the harness.
+///
+/// Pass it to [`run_with_handle`](crate::run_with_handle). Clones control the
+/// same run. A canceled handle stays canceled: a later run with it starts no
+/// tests at all.
+#[derive(Debug, Clone)]
+pub struct RunHandle {
+    sender: watch::Sender<Control>,
+}
+
+impl RunHandle {
+    pub fn new() -> Self {
+        Self {
+            sender: watch::channel(Control::Running).0,
+        }
+    }
+
+    /// Cancels the run: no more tests are started, and the run finishes once
+    /// the running ones have. `reason` is included in the output.
+    pub fn cancel(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        self.sender.send_if_modified(|control| match control {
+            Control::Canceled(_) => false,
+            _ => {
+                *control = Control::Canceled(reason);
+                true
+            }
+        });
+    }
+
+    /// Stops starting tests until [`resume`](Self::resume) is called.
+    pub fn pause(&self) {
+        self.set(Control::Running, Control::Paused)
+    }
+
+    /// Starts tests again after [`pause`](Self::pause).
+    pub fn resume(&self) {
+        self.set(Control::Paused, Control::Running)
+    }
+
+    /// Returns whether the run was canceled.
+    pub fn is_canceled(&self) -> bool {
+        matches!(*self.sender.borrow(), Control::Canceled(_))
+    }
+
+    fn set(&self, from: Control, to: Control) {
+        self.sender.send_if_modified(|control| {
+            let modified = *control == from;
+            if modified {
+                *control = to;
+            }
+            modified
+        });
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Control> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for RunHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Waits until tests may start. Returns `false` if the run was canceled.
+pub(crate) async fn wait_runnable(control: &mut watch::Receiver<Control>) -> bool {
+    loop {
+        match &*control.borrow_and_update() {
+            Control::Running => return true,
+            Control::Canceled(_) => return false,
+            Control::Paused => {}
+        }
+        // The run borrows the handle, so it is not dropped while paused.
+        if control.changed().await.is_err() {
+            return true;
+        }
+    }
+}
+
+/// Calls `f` with every change of `control`, starting with the current state
+/// if it is not [`Control::Running`]. Never completes.
+pub(crate) fn watch(
+    mut control: watch::Receiver<Control>,
+    mut f: impl FnMut(Control),
+) -> impl Future<Output = ()> {
+    async move {
+        let initial = control.borrow_and_update().clone();
+        if initial != Control::Running {
+            f(initial);
+        }
+        while control.changed().await.is_ok() {
+            f(control.borrow_and_update().clone());
+        }
+        std::future::pending().await
+    }
+}
@@ -0,0 +1,90 @@
+//! The distribution of test durations, printed with `--verbose` and included
+//! in the summary of reports.
+
+use std::time::Duration;
+
+use crate::report::{DurationSummary, HistogramBucket};
+
+/// The upper bounds of the histogram buckets, in seconds, each ten times or
+/// so the previous one. The last bucket has no upper bound.
+const BUCKETS: [f64; 5] = [0.01, 0.1, 1.0, 10.0, 60.0];
+
+/// Summarizes the durations of the finished tests, or returns `None` if
+/// there are none.
+pub(crate) fn summarize(mut durations: Vec<Duration>) -> Option<DurationSummary> {
+    durations.sort_unstable();
+    let max = *durations.last()?;
+    // The nearest-rank percentile: the shortest duration that at least `p`
+    // percent of the tests took no longer than.
+    let percentile = |p: usize| {
+        let rank = ((durations.len() * p + 99) / 100).max(1);
+        durations[rank - 1].as_secs_f64()
+    };
+
+    let mut histogram: Vec<_> = BUCKETS
+        .iter()
+        .map(|&bound| Some(bound))
+        .chain([None])
+        .map(|below_secs| HistogramBucket {
+            below_secs,
+            count: 0,
+        })
+        .collect();
+    for duration in &durations {
+        let bucket = BUCKETS
+            .iter()
+            .position(|&bound| duration.as_secs_f64() < bound)
+            .unwrap_or(BUCKETS.len());
+        histogram[bucket].count += 1;
+    }
+
+    Some(DurationSummary {
+        p50_secs: percentile(50),
+        p90_secs: percentile(90),
+        p99_secs: percentile(99),
+        max_secs: max.as_secs_f64(),
+        histogram,
+    })
+}
+
+/// A short label for a histogram bucket, like `< 100ms`.
+pub(crate) fn bucket_label(bucket: &HistogramBucket) -> String {
+    let format = |secs: f64| match secs {
+        secs if secs >= 60.0 => format!("{:.0}m", secs / 60.0),
+        secs if secs >= 1.0 => format!("{secs:.0}s"),
+        secs => format!("{:.0}ms", secs * 1000.0),
+    };
+    match bucket.below_secs {
+        Some(below) => format!("< {}", format(below)),
+        None => format!(">= {}", format(BUCKETS[BUCKETS.len() - 1])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_and_buckets() {
+        assert_eq!(summarize(vec![]), None);
+
+        let durations = (1..=100).map(Duration::from_millis).collect();
+        let summary = summarize(durations).unwrap();
+        assert_eq!(summary.p50_secs, 0.05);
+        assert_eq!(summary.p90_secs, 0.09);
+        assert_eq!(summary.p99_secs, 0.099);
+        assert_eq!(summary.max_secs, 0.1);
+        let counts: Vec<_> = summary.histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [9, 90, 1, 0, 0, 0]);
+
+        let summary = summarize(vec![Duration::from_secs(90)]).unwrap();
+        assert_eq!(summary.p50_secs, 90.0);
+        assert_eq!(summary.histogram.last().unwrap().count, 1);
+
+        let labels: Vec<_> = summary.histogram.iter().map(bucket_label).collect();
+        assert_eq!(
+            labels,
+            ["< 10ms", "< 100ms", "< 1s", "< 10s", "< 1m", ">= 1m"]
+        );
+    }
+}
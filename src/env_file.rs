@@ -0,0 +1,95 @@
+//! `--env-file`: sets environment variables from a dotenv-style file.
+//!
+//! The file is read before tests are collected, so `tests!` functions,
+//! setups and tests all see the variables. Every line is `KEY=VALUE`,
+//! optionally preceded by `export`. Values may be wrapped in single or double
+//! quotes. Empty lines and lines starting with `#` are ignored.
+//!
+//! Like other dotenv loaders, variables that are already set are left alone,
+//! so a value from the environment of the run wins over the file.
+
+use std::process;
+
+/// Sets the variables from the file at `path` and returns their keys, in the
+/// order of the file. Exits if the file cannot be read or parsed.
+pub(crate) fn load(path: &str) -> Vec<String> {
+    let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("error: failed to read env file {path}: {e}");
+        process::exit(1)
+    });
+
+    let vars = parse(&contents).unwrap_or_else(|(line, message)| {
+        eprintln!("error: {path}:{line}: {message}");
+        process::exit(1)
+    });
+
+    let mut keys = vec![];
+    for (key, value) in vars {
+        if std::env::var_os(key).is_none() {
+            std::env::set_var(key, value);
+        }
+        keys.push(key.to_owned());
+    }
+    keys
+}
+
+/// Parses the variables, or returns the line number and a description of the
+/// first error. Keys and values are checked so that setting them cannot
+/// panic.
+fn parse(contents: &str) -> Result<Vec<(&str, &str)>, (usize, String)> {
+    let mut vars = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let error = |message: &str| (i + 1, message.to_owned());
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected KEY=VALUE"))?;
+        let key = key.trim();
+        let value = unquote(value.trim());
+        if key.is_empty() {
+            return Err(error("expected a key before `=`"));
+        }
+        if key.contains('\0') || value.contains('\0') {
+            return Err(error("keys and values cannot contain NUL"));
+        }
+        vars.push((key, value));
+    }
+    Ok(vars)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("A=1\n=2").unwrap_err(),
+            (2, "expected a key before `=`".to_owned())
+        );
+        assert_eq!(
+            parse("# comment\nA").unwrap_err(),
+            (2, "expected KEY=VALUE".to_owned())
+        );
+        assert_eq!(
+            parse("A==1\nexport B='2'").unwrap(),
+            [("A", "=1"), ("B", "2")]
+        );
+    }
+}
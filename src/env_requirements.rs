@@ -0,0 +1,203 @@
+//! `--env-requirements`: what the environment must provide for the suite to
+//! run, checked before any test starts.
+//!
+//! The file uses a small subset of TOML, like `--thresholds`, and may be the
+//! same file. Every entry of the `[environment]` table is optional:
+//!
+//! ```toml
+//! [environment]
+//! min_open_files = 4096
+//! env_vars = ["DATABASE_URL", "AWS_REGION"]
+//! binaries = ["docker", "psql"]
+//! ```
+//!
+//! If anything is missing, the run stops with a list of what it is and how
+//! to provide it, instead of with hundreds of failing tests. The limit of
+//! open files is only checked on Unix.
+
+use std::process;
+
+use crate::toml_subset::{self, split_unquoted, Entry};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct EnvRequirements {
+    min_open_files: Option<u64>,
+    env_vars: Vec<String>,
+    binaries: Vec<String>,
+}
+
+impl EnvRequirements {
+    /// Reads the requirements from the file at `path`. Exits if the file
+    /// cannot be read or parsed.
+    pub(crate) fn load(path: &str) -> Self {
+        let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: failed to read env requirements {path}: {e}");
+            process::exit(1)
+        });
+        Self::parse(&contents).unwrap_or_else(|(line, message)| {
+            eprintln!("error: {path}:{line}: {message}");
+            process::exit(1)
+        })
+    }
+
+    /// Parses the requirements, or returns the line number and a description
+    /// of the first error.
+    fn parse(contents: &str) -> Result<Self, (usize, String)> {
+        let mut requirements = Self::default();
+        for Entry { line, key, value } in toml_subset::entries(contents, "environment")? {
+            let error = |message: &str| (line, message.to_owned());
+            match key {
+                "min_open_files" => {
+                    let number = value
+                        .parse()
+                        .map_err(|_| error(&format!("`{value}` is not a number")))?;
+                    requirements.min_open_files = Some(number);
+                }
+                "env_vars" => requirements.env_vars = parse_strings(value).map_err(error)?,
+                "binaries" => requirements.binaries = parse_strings(value).map_err(error)?,
+                key => return Err(error(&format!("unknown requirement `{key}`"))),
+            }
+        }
+        Ok(requirements)
+    }
+
+    /// Exits with a description of every requirement that the environment
+    /// does not meet.
+    pub(crate) fn enforce(&self, path: &str) {
+        let unmet = self.unmet();
+        if unmet.is_empty() {
+            return;
+        }
+        eprintln!("error: the environment does not meet the requirements in {path}:");
+        for message in unmet {
+            eprintln!("  - {message}");
+        }
+        process::exit(1)
+    }
+
+    /// Describes every requirement that the environment does not meet, with
+    /// how to meet it.
+    fn unmet(&self) -> Vec<String> {
+        let mut unmet = vec![];
+        if let Some(min) = self.min_open_files {
+            if let Some(limit) = open_files_limit().filter(|&limit| limit < min) {
+                unmet.push(format!(
+                    "the limit of open files is {limit}, but {min} are required, \
+                    raise it with `ulimit -n {min}`"
+                ));
+            }
+        }
+        for var in &self.env_vars {
+            if std::env::var_os(var).is_none() {
+                unmet.push(format!(
+                    "{var} is not set, set it or add it to the file of --env-file"
+                ));
+            }
+        }
+        for binary in &self.binaries {
+            if !on_path(binary) {
+                unmet.push(format!(
+                    "`{binary}` is not on PATH, install it or add its directory to PATH"
+                ));
+            }
+        }
+        unmet
+    }
+}
+
+/// Parses an array of strings, like `["a", "b"]`.
+fn parse_strings(value: &str) -> Result<Vec<String>, &'static str> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .ok_or("expected an array like [\"A\", \"B\"]")?;
+    split_unquoted(inner, ',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.strip_prefix('"')
+                .and_then(|item| item.strip_suffix('"'))
+                .map(str::to_owned)
+                .ok_or("expected a quoted string")
+        })
+        .collect()
+}
+
+/// The soft limit of open files, or `None` if it is unlimited or cannot be
+/// found out.
+#[cfg(unix)]
+fn open_files_limit() -> Option<u64> {
+    // A child process inherits the limits, and this avoids depending on libc.
+    let output = process::Command::new("sh")
+        .args(["-c", "ulimit -n"])
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+#[cfg(not(unix))]
+fn open_files_limit() -> Option<u64> {
+    None
+}
+
+fn on_path(binary: &str) -> bool {
+    let paths = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&paths).any(|dir| {
+        let path = dir.join(binary);
+        path.is_file() || (cfg!(windows) && path.with_extension("exe").is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let requirements = EnvRequirements::parse(
+            r#"
+            [metrics]
+            p99_ms = { max = 50 }
+
+            [environment]
+            min_open_files = 4096 # for the connection pool
+            env_vars = ["DATABASE_URL", "AWS_REGION"]
+            binaries = []
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            requirements,
+            EnvRequirements {
+                min_open_files: Some(4096),
+                env_vars: vec!["DATABASE_URL".to_owned(), "AWS_REGION".to_owned()],
+                binaries: vec![],
+            }
+        );
+
+        let error = EnvRequirements::parse("[environment]\nbinaries = docker\n").unwrap_err();
+        assert_eq!(
+            error,
+            (2, "expected an array like [\"A\", \"B\"]".to_owned())
+        );
+        let error = EnvRequirements::parse("[environment]\nmin_memory = 1\n").unwrap_err();
+        assert_eq!(error, (2, "unknown requirement `min_memory`".to_owned()));
+    }
+
+    #[test]
+    fn unmet() {
+        let requirements = EnvRequirements {
+            min_open_files: None,
+            env_vars: vec!["PATH".to_owned(), "ASYNC_TEST_NEVER_SET".to_owned()],
+            binaries: vec!["async-test-no-such-binary".to_owned()],
+        };
+        assert_eq!(
+            requirements.unmet(),
+            [
+                "ASYNC_TEST_NEVER_SET is not set, set it or add it to the file of --env-file",
+                "`async-test-no-such-binary` is not on PATH, install it or add its directory \
+                to PATH",
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! `-E`/`--filter-expr`: selects tests with an expression instead of a list
+//! of names, e.g. `test(db::) and not (tag(slow) or requires(Docker))`.
+//!
+//! The predicates are:
+//!
+//! - `test(NAME)`: the name contains `NAME`, or is `NAME` with `test(=NAME)`.
+//! - `tag(TAG)`: the test has the tag.
+//! - `requires(Type)`: the test requires a fixture of that type, where the
+//!   last segment of the type path is enough unless it is `requires(=Type)`.
+//! - `all()` and `none()`.
+//!
+//! They combine with `not` (or `!`), `and` (or `&`) and `or` (or `|`), which
+//! bind in that order, and with parentheses.
+
+use std::fmt;
+
+use crate::Trial;
+
+/// A parsed `--filter-expr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filterset {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Test(Matcher),
+    Tag(String),
+    Requires(Matcher),
+    All,
+    None,
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Matcher {
+    Exact(String),
+    Contains(String),
+}
+
+impl Filterset {
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        let mut parser = Parser { input: s, pos: 0 };
+        let expr = parser.or()?;
+        parser.skip_whitespace();
+        if let Some(rest) = parser.rest() {
+            return Err(format!("unexpected `{rest}` at {}", parser.pos + 1));
+        }
+        Ok(Self {
+            source: s.to_owned(),
+            expr,
+        })
+    }
+
+    /// Returns whether the expression selects `test`.
+    pub(crate) fn matches(&self, test: &Trial) -> bool {
+        self.expr.matches(test)
+    }
+}
+
+impl fmt::Display for Filterset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Expr {
+    fn matches(&self, test: &Trial) -> bool {
+        match self {
+            Expr::Test(Matcher::Exact(name)) => test.info.name == *name,
+            Expr::Test(Matcher::Contains(part)) => test.info.name.contains(part.as_str()),
+            Expr::Tag(tag) => test.info.tags.contains(tag),
+            Expr::Requires(Matcher::Exact(ty)) => test.requires.iter().any(|(name, _)| name == ty),
+            Expr::Requires(Matcher::Contains(ty)) => test
+                .requires
+                .iter()
+                .any(|(name, _)| name == ty || name.ends_with(&format!("::{ty}"))),
+            Expr::All => true,
+            Expr::None => false,
+            Expr::Not(expr) => !expr.matches(test),
+            Expr::And(a, b) => a.matches(test) && b.matches(test),
+            Expr::Or(a, b) => a.matches(test) || b.matches(test),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.symbol('|') || self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+        while self.symbol('&') || self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.symbol('!') || self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.symbol('(') {
+            let expr = self.or()?;
+            self.expect(')')?;
+            return Ok(expr);
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Expr, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.word();
+        if name.is_empty() {
+            return Err(match self.rest() {
+                Some(rest) => format!("expected an expression, found `{rest}` at {}", start + 1),
+                None => "expected an expression at the end".to_owned(),
+            });
+        }
+        self.expect('(')?;
+        let end = self.input[self.pos..]
+            .find(')')
+            .map(|end| self.pos + end)
+            .ok_or_else(|| format!("expected `)` after `{name}(`"))?;
+        let argument = self.input[self.pos..end].trim();
+        self.pos = end + 1;
+
+        let matcher = || match argument.strip_prefix('=') {
+            Some(exact) => Matcher::Exact(exact.to_owned()),
+            None => Matcher::Contains(argument.to_owned()),
+        };
+        let no_argument = |expr| match argument {
+            "" => Ok(expr),
+            _ => Err(format!("`{name}()` takes no argument")),
+        };
+        match name {
+            "test" => Ok(Expr::Test(matcher())),
+            "tag" => Ok(Expr::Tag(argument.to_owned())),
+            "requires" => Ok(Expr::Requires(matcher())),
+            "all" => no_argument(Expr::All),
+            "none" => no_argument(Expr::None),
+            _ => Err(format!(
+                "unknown predicate `{name}`, expected `test`, `tag`, `requires`, `all` or `none`"
+            )),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> Option<&'a str> {
+        Some(&self.input[self.pos..]).filter(|rest| !rest.is_empty())
+    }
+
+    /// Consumes the next word of letters, digits and underscores.
+    fn word(&mut self) -> &'a str {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consumes `symbol` if it is next.
+    fn symbol(&mut self, symbol: char) -> bool {
+        self.skip_whitespace();
+        let found = self.input[self.pos..].starts_with(symbol);
+        if found {
+            self.pos += symbol.len_utf8();
+        }
+        found
+    }
+
+    /// Consumes `keyword` if it is the next word.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let start = self.pos;
+        if self.word() == keyword {
+            return true;
+        }
+        self.pos = start;
+        false
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        match self.symbol(symbol) {
+            true => Ok(()),
+            false => Err(match self.rest() {
+                Some(rest) => format!("expected `{symbol}`, found `{rest}` at {}", self.pos + 1),
+                None => format!("expected `{symbol}` at the end"),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let contains = |name: &str| Box::new(Expr::Test(Matcher::Contains(name.to_owned())));
+        assert_eq!(
+            Filterset::parse("test(a) or test(=b) and not tag(slow)")
+                .unwrap()
+                .expr,
+            Expr::Or(
+                contains("a"),
+                Box::new(Expr::And(
+                    Box::new(Expr::Test(Matcher::Exact("b".to_owned()))),
+                    Box::new(Expr::Not(Box::new(Expr::Tag("slow".to_owned())))),
+                )),
+            )
+        );
+        assert_eq!(
+            Filterset::parse("!(test(a)|test(b))&all()").unwrap().expr,
+            Expr::And(
+                Box::new(Expr::Not(Box::new(Expr::Or(contains("a"), contains("b"))))),
+                Box::new(Expr::All),
+            )
+        );
+        // Keywords are whole words.
+        assert_eq!(
+            Filterset::parse("test(x) or_else").unwrap_err(),
+            "unexpected `or_else` at 9"
+        );
+
+        for (expr, error) in [
+            ("", "expected an expression at the end"),
+            ("test(a) and", "expected an expression at the end"),
+            ("(test(a)", "expected `)` at the end"),
+            ("test(a", "expected `)` after `test(`"),
+            (
+                "name(a)",
+                "unknown predicate `name`, expected `test`, `tag`, `requires`, `all` or `none`",
+            ),
+            ("all(a)", "`all()` takes no argument"),
+            ("test(a) test(b)", "unexpected `test(b)` at 9"),
+        ] {
+            assert_eq!(Filterset::parse(expr).unwrap_err(), error, "{expr}");
+        }
+    }
+}
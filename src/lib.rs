@@ -60,8 +60,12 @@
 //!
 //! - Output capture and `--nocapture`: simply not supported. The official
 //!   `libtest` uses internal `std` functions to temporarily redirect output.
-//!   `async-test` cannot use those.
-//! - `--format=json|junit`
+//!   `async-test` cannot use those. [`out::println!`] at least keeps the
+//!   lines of concurrent tests apart, prefixed with their names.
+//! - `--format=junit`: use `--junit-path FILE` instead, which writes the
+//!   JUnit report next to the regular output. `--format=json` is supported,
+//!   but prints the schema described in [`report`] instead of the unstable
+//!   libtest one.
 
 #![forbid(unsafe_code)]
 #![allow(clippy::all, unused_variables, dead_code)]
@@ -72,29 +76,115 @@ use std::{
     any::TypeId,
     backtrace::{Backtrace, BacktraceStatus},
     cell::Cell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     future::Future,
-    num::NonZeroUsize,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
     pin::Pin,
     process,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc, Mutex, OnceLock,
+    },
     task::Poll,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
+mod adaptive;
 mod args;
+pub mod cache;
+mod cancel;
+mod capability;
+mod chaos;
+mod check;
+pub mod clock;
+mod compare;
+mod config;
+mod control;
+mod durations;
+mod env_file;
+mod env_requirements;
+mod filterset;
+mod merge;
+mod meta;
+pub mod out;
+mod partition;
+mod payload;
 mod printer;
-
+mod registration;
+mod repeat;
+mod replay;
+pub mod report;
+mod report_time;
+mod retention;
+mod returned;
+mod sample;
+mod screen;
+mod setup_env;
+mod shuffle;
+mod signal;
+// Public for `tests/simulation.rs` and forks only, see the module docs.
+#[doc(hidden)]
+pub mod sim;
+mod slow_history;
+mod suspend;
+mod system_log;
+mod template;
+mod test_args;
+mod thresholds;
+mod toml_subset;
+mod verify;
+#[cfg(feature = "notify-webhook")]
+mod webhook;
+use adaptive::Adaptive;
+use cache::ScratchDir;
+use capability::{Capability, Probes};
+
+use clock::{Clock, SystemClock};
+use compare::Compare;
+use config::{RunConfig, HEAVY_TAG};
+use control::Control;
+use env_requirements::EnvRequirements;
 use nextest::{
-    reporter::{ReporterOutput, TestEvent, TestReporterBuilder},
+    reporter::{
+        open_html_report, CancelReason, Checkpoint, HtmlReport, MergeInto, Metrics, ReporterOutput,
+        SlowBatcher, TestEvent, TestReporterBuilder, Timeline,
+    },
     ExecuteStatus, MismatchReason, RunStats, TestInstance, TestList,
 };
+use registration::{Kind, Scope};
+use report::{CollectionError, Diagnostic, DiagnosticLevel};
+use retention::Retention;
+use setup_env::SetupEnv;
+use signal::Signals;
+use suspend::SuspendDetector;
+use system_log::SystemLog;
+use thresholds::Thresholds;
 use tokio::sync::Semaphore;
 
-pub use crate::args::{Arguments, ColorSetting, FormatSetting};
+pub use crate::{
+    args::{
+        Arguments, ChaosSetting, ColorSetting, ErrorFormatSetting, FixtureCacheSetting,
+        FormatSetting, LogEventsSetting, PartitionSetting,
+    },
+    cancel::Cancellation,
+    control::RunHandle,
+    filterset::Filterset,
+    merge::{merge_reports, CombinedReport},
+    meta::TestMeta,
+    payload::register_payload_formatter,
+    report::Reporter,
+    returned::TestOutput,
+    screen::Screen,
+    signal::Signal,
+    template::TrialTemplate,
+    test_args::TestArgs,
+};
 
 type Fut = Pin<Box<dyn 'static + Send + Future<Output = ()>>>;
-type Fun = Box<dyn 'static + Send + FnOnce(&'static Context) -> Fut>;
+type Fun = Box<dyn 'static + Send + Fn(&'static Context) -> Fut>;
 /// A single test.
 ///
 /// The original `libtest` often calls benchmarks "tests", which is a bit
@@ -103,9 +193,8 @@ type Fun = Box<dyn 'static + Send + FnOnce(&'static Context) -> Fut>;
 /// A trial is created via [`Trial::test`]. The trial's
 /// `name` is printed and used for filtering. The `runner` is called when the
 /// test/benchmark is executed to determine its outcome. If `runner` panics,
-/// the trial is considered "failed". If you need the behavior of
-/// `#[should_panic]` you need to catch the panic yourself. You likely want to
-/// compare the panic payload to an expected value anyway.
+/// the trial is considered "failed", unless it is marked with
+/// [`with_should_panic`](Trial::with_should_panic).
 pub struct Trial {
     runner: Option<Fun>,
     requires: Vec<(&'static str, TypeId)>,
@@ -113,6 +202,9 @@ pub struct Trial {
 }
 
 pub trait TestFn<T>: Clone + Send + Sized + 'static {
+    /// What the runner's future completes with.
+    type Output: TestOutput;
+
     fn call(self, context: &'static Context) -> Fut;
     fn requires(&self) -> Vec<(&'static str, TypeId)>;
 }
@@ -120,11 +212,16 @@ pub trait TestFn<T>: Clone + Send + Sized + 'static {
 impl<F, Fut2> TestFn<((),)> for F
 where
     F: FnOnce() -> Fut2 + Clone + Send + 'static,
-    Fut2: Future<Output = ()> + Send + 'static,
+    Fut2: Future + Send + 'static,
+    Fut2::Output: TestOutput,
 {
+    type Output = Fut2::Output;
+
     fn call(self, context: &'static Context) -> Fut {
         Box::pin(async move {
-            self().await;
+            if let Some(error) = self().await.into_error() {
+                check::record_error(error);
+            }
         })
     }
     fn requires(&self) -> Vec<(&'static str, TypeId)> {
@@ -140,16 +237,21 @@ macro_rules! impl_handler {
         impl<F, Fut2, $($ty,)*> TestFn<($($ty,)* ())> for F
         where
             F: FnOnce($(&'static $ty),*) -> Fut2 + Clone + Send + 'static,
-            Fut2: Future<Output = ()> + Send + 'static,
+            Fut2: Future + Send + 'static,
+            Fut2::Output: TestOutput,
             $($ty: 'static + Sync + Send,)*
         {
+            type Output = Fut2::Output;
+
             fn call(self, context: &'static Context) -> Fut {
                 Box::pin(async move {
                     $(
                         let $ty: &'static $ty = context.get().await.unwrap();
                     )*
 
-                    self($($ty),*).await;
+                    if let Some(error) = self($($ty),*).await.into_error() {
+                        check::record_error(error);
+                    }
                 })
             }
             fn requires(&self) -> Vec<(&'static str, TypeId)> {
@@ -178,23 +280,51 @@ impl_handler!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15]
 impl Trial {
     /// Creates a (non-benchmark) test with the given name and runner.
     ///
-    /// The runner returning `Ok(())` is interpreted as the test passing. If the
-    /// runner returns `Err(_)`, the test is considered failed.
+    /// The runner's future completing is interpreted as the test passing. If
+    /// it panics, the test is considered failed. For a runner that returns a
+    /// `Result`, use [`Trial::fallible`].
     pub fn test<T, F>(name: impl Into<String>, runner: F) -> Self
+    where
+        T: 'static,
+        F: TestFn<T, Output = ()>,
+    {
+        Self::new(name, runner)
+    }
+
+    /// Like [`Trial::test`], but the runner's future completes with a
+    /// `Result`. An `Err` fails the test, and is shown with its sources in the
+    /// format picked with `--error-format`.
+    ///
+    /// Any error that converts into `Box<dyn Error + Send + Sync>` works,
+    /// which includes `String`, `anyhow::Error` and all `Send + Sync` error
+    /// types.
+    pub fn fallible<T, E, F>(name: impl Into<String>, runner: F) -> Self
+    where
+        T: 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+        F: TestFn<T, Output = Result<(), E>>,
+    {
+        Self::new(name, runner)
+    }
+
+    fn new<T, F>(name: impl Into<String>, runner: F) -> Self
     where
         T: 'static,
         F: TestFn<T>,
     {
         Self {
             requires: runner.requires(),
-            runner: Some(Box::new(move |ctx| Box::pin(runner.call(ctx)))),
-            info: TestInfo {
-                name: name.into(),
-                is_ignored: false,
-            },
+            runner: Some(Box::new(move |ctx| Box::pin(runner.clone().call(ctx)))),
+            info: TestInfo::new(name.into()),
         }
     }
 
+    /// Creates a template for trials that share their configuration, see
+    /// [`TrialTemplate`].
+    pub fn template() -> TrialTemplate {
+        TrialTemplate::new()
+    }
+
     /// Sets whether or not this test is considered "ignored". (Default: `false`)
     ///
     /// With the built-in test suite, you can annotate `#[ignore]` on tests to
@@ -211,15 +341,177 @@ impl Trial {
         }
     }
 
+    /// Marks this test as ignored, and says why, e.g. "needs a GPU".
+    ///
+    /// The reason is shown by `--list --verbose` and included in
+    /// `--list --format json`.
+    pub fn with_ignore_reason(mut self, reason: impl Into<String>) -> Self {
+        self.info.is_ignored = true;
+        self.info.ignore_reason = Some(reason.into());
+        self
+    }
+
+    /// Adds tags to this trial. (Default: no tags)
+    ///
+    /// Tags can be used to select tests from the command line with `--tag`
+    /// and `--exclude-tag`.
+    ///
+    /// Tests tagged `heavy` run at a quarter of the usual concurrency, unless
+    /// `--test-tasks` is given.
+    pub fn with_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.info.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Marks this trial as only applicable where `enabled` is `true`.
+    ///
+    /// This is meant to be used with [`cfg!`], for example
+    /// `.with_required_cfg(cfg!(unix), "unix")`. Where the condition does not
+    /// hold, the trial is still registered but reported as skipped, with `cfg`
+    /// as the reason. That keeps test counts comparable across platforms.
+    pub fn with_required_cfg(mut self, enabled: bool, cfg: impl Into<String>) -> Self {
+        if !enabled && self.info.unmet_cfg.is_none() {
+            self.info.unmet_cfg = Some(cfg.into());
+        }
+        self
+    }
+
+    /// Marks this trial as requiring network access.
+    ///
+    /// Before the first such trial runs, the harness checks that it can
+    /// connect to `1.1.1.1:443`, or to the `host:port` in the
+    /// `ASYNC_TEST_NETWORK_PROBE` environment variable. If it cannot, the
+    /// trial is reported as skipped with the reason, rather than failing with
+    /// a connection error. With `--offline`, it is skipped without checking.
+    pub fn with_requires_network(mut self) -> Self {
+        self.info.require(Capability::Network);
+        self
+    }
+
+    /// Marks this trial as requiring a Docker daemon.
+    ///
+    /// Before the first such trial runs, the harness checks that it can
+    /// connect to the socket in `DOCKER_HOST`, or to the platform's default
+    /// socket. If it cannot, the trial is reported as skipped with the
+    /// reason, like [`Trial::with_requires_network`].
+    pub fn with_requires_docker(mut self) -> Self {
+        self.info.require(Capability::Docker);
+        self
+    }
+
+    /// Sets whether this test must run on the thread that called [`run`].
+    /// (Default: `false`)
+    ///
+    /// This is for tests of APIs that only work on the main thread, like some
+    /// GUI frameworks. Such tests run one at a time, independent of
+    /// `--test-threads`, while the other tests keep running on the runtime's
+    /// worker threads.
+    pub fn with_main_thread(mut self, main_thread: bool) -> Self {
+        self.info.main_thread = main_thread;
+        self
+    }
+
+    /// Describes what this test checks, e.g. "verifies RFC 1234 §5 corner
+    /// case". The description is printed under the failure header of the
+    /// test and included in reports.
+    ///
+    /// `test!` uses the doc comment of the function as the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.info.description = Some(description.into());
+        self
+    }
+
+    /// Attaches a link to this test, e.g. `.with_link("issue", url)` for the
+    /// issue tracking a known problem.
+    ///
+    /// Links are printed with the failure output of the test, as clickable
+    /// hyperlinks if the output is colorized, and included in reports and in
+    /// `--list --verbose`.
+    pub fn with_link(mut self, label: impl Into<String>, url: impl Into<String>) -> Self {
+        self.info.links.push(report::Link {
+            label: label.into(),
+            url: url.into(),
+        });
+        self
+    }
+
+    /// Marks this test as known to fail, e.g. because of a bug that is not
+    /// fixed yet.
+    ///
+    /// If the test fails, it is reported as `XFAIL` and counts as passed. If
+    /// it passes, it is reported as `XPASS` and fails the run, as a reminder
+    /// to remove the marking.
+    pub fn with_expected_failure(mut self, reason: impl Into<String>) -> Self {
+        self.info.expected_failure = Some(reason.into());
+        self
+    }
+
+    /// Makes the trial pass if it panics, and fail with "test did not panic
+    /// as expected" if it completes, like `#[should_panic]`.
+    pub fn with_should_panic(mut self) -> Self {
+        self.info.should_panic = ShouldPanic::Yes;
+        self
+    }
+
+    /// Like [`with_should_panic`](Self::with_should_panic), but the trial
+    /// only passes if the panic message contains `expected`.
+    pub fn with_should_panic_expected(mut self, expected: impl Into<String>) -> Self {
+        self.info.should_panic = ShouldPanic::YesWithMessage(expected.into());
+        self
+    }
+
+    /// Runs the trial again, up to `retries` more times, if it fails. See
+    /// [`TrialHandle::retries`].
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.info.retries = Some(retries);
+        self
+    }
+
+    /// Fails the trial as timed out if it runs for longer than `timeout`. See
+    /// [`TrialHandle::timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.info.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long the trial is expected to take, without failing it if
+    /// it takes longer.
+    ///
+    /// The trial is reported as SLOW once it runs for longer than `expected`
+    /// instead of after the usual slow period, and the summary lists the
+    /// trials that took longer than they were expected to.
+    pub fn with_expected_duration(mut self, expected: Duration) -> Self {
+        self.info.expected_duration = Some(expected);
+        self
+    }
+
     /// Returns the name of this trial.
     pub fn name(&self) -> &str {
         &self.info.name
     }
 
+    /// Returns the tags of this trial.
+    pub fn tags(&self) -> &[String] {
+        &self.info.tags
+    }
+
     /// Returns whether this trial has been marked as *ignored*.
     pub fn has_ignored_flag(&self) -> bool {
         self.info.is_ignored
     }
+
+    /// `#[ignore_if(env = "VAR")]` of [`test!`].
+    #[doc(hidden)]
+    pub fn __ignore_if_env(self, var: &str) -> Self {
+        match std::env::var_os(var) {
+            Some(_) => self.with_ignore_reason(format!("{var} is set")),
+            None => self,
+        }
+    }
 }
 
 // struct Config {}
@@ -236,6 +528,12 @@ struct Setup {
     setup: fn() -> tokio::task::JoinHandle<AnySharedVal>,
     // init: AtomicUsize,
     value: tokio::sync::OnceCell<AnySharedVal>,
+    /// The number of tests in the run that require it.
+    tests: AtomicUsize,
+    /// The tests waiting for it to be set up right now, and the most so far.
+    waiting: AtomicUsize,
+    peak_waiting: AtomicUsize,
+    init_duration: OnceLock<Duration>,
 }
 
 impl Context {
@@ -246,9 +544,69 @@ impl Context {
             None => None,
         }
     }
+
+    /// A context that sets up the fixtures `ids` again, for an iteration of
+    /// `--repeat`. It is leaked like the context of the run, since tests hold
+    /// `&'static` references to their fixtures.
+    fn fresh(&self, ids: &[TypeId]) -> &'static Context {
+        let values = ids
+            .iter()
+            .filter_map(|id| {
+                let s = self.values.get(id)?;
+                Some((*id, Arc::new(Setup::new(s.module, s.function, s.setup))))
+            })
+            .collect();
+        Box::leak(Box::new(Context { values }))
+    }
+
+    /// Sets up every fixture of the context, one after another.
+    async fn set_up(&'static self, env: SetupEnv) {
+        for s in self.values.values() {
+            let env = env.clone();
+            s.value
+                .get_or_init(|| async move { env.scope(s.setup).await.unwrap() })
+                .await;
+        }
+    }
+
+    /// How the fixtures that any test required were used, sorted by name.
+    fn fixture_usage(&self) -> Vec<report::FixtureUsage> {
+        let mut fixtures: Vec<_> = self
+            .values
+            .values()
+            .filter(|s| s.tests.load(Ordering::Acquire) > 0)
+            .map(|s| report::FixtureUsage {
+                name: s.function.to_owned(),
+                tests: s.tests.load(Ordering::Acquire),
+                init_secs: s.init_duration.get().map(Duration::as_secs_f64),
+                peak_waiters: s.peak_waiting.load(Ordering::Acquire),
+            })
+            .collect();
+        fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+        fixtures
+    }
 }
 
 impl Setup {
+    fn new(
+        module: &'static str,
+        function: &'static str,
+        setup: fn() -> tokio::task::JoinHandle<AnySharedVal>,
+    ) -> Self {
+        Self {
+            module,
+            function,
+            // file: setup.file,
+            // line: setup.line,
+            setup,
+            value: tokio::sync::OnceCell::new(),
+            tests: AtomicUsize::new(0),
+            waiting: AtomicUsize::new(0),
+            peak_waiting: AtomicUsize::new(0),
+            init_duration: OnceLock::new(),
+        }
+    }
+
     async fn get<T: 'static>(&'static self) -> &'static T {
         // &** is necessary... trust me
         // get_or_init returns &Arc<T>
@@ -277,7 +635,22 @@ pub struct Tester {
 }
 
 impl Tester {
-    pub fn add(&self, trial: Trial) {
+    /// Adds a trial to the run.
+    ///
+    /// The returned handle configures how the trial runs, which is handy
+    /// when that depends on what was found while collecting it:
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use async_test::{Tester, Trial};
+    /// # fn collect(tester: Tester, path: std::path::PathBuf, size: u64) {
+    /// let handle = tester.add(Trial::test(path.display().to_string(), || async {}));
+    /// if size > 1 << 20 {
+    ///     handle.timeout(Duration::from_secs(120)).group("large-files");
+    /// }
+    /// # }
+    /// ```
+    pub fn add(&self, trial: Trial) -> TrialHandle {
         let mut missing = vec![];
         for (ty, id) in &trial.requires {
             if !self.context.values.contains_key(id) {
@@ -298,18 +671,93 @@ impl Tester {
             );
         }
 
-        self.inner.lock().unwrap().tasks.push(trial)
+        let mut inner = self.inner.lock().unwrap();
+        inner.tasks.push(trial);
+        TrialHandle {
+            inner: self.inner.clone(),
+            index: inner.tasks.len() - 1,
+        }
+    }
+
+    /// Records a warning about collecting tests, e.g. a fixture directory that
+    /// could not be read. Warnings are printed before the run starts.
+    pub fn warn(&self, message: impl Into<String>) {
+        self.diagnose(DiagnosticLevel::Warning, message.into())
+    }
+
+    /// Records a note about collecting tests. Notes are printed before the run
+    /// starts.
+    pub fn note(&self, message: impl Into<String>) {
+        self.diagnose(DiagnosticLevel::Note, message.into())
+    }
+
+    fn diagnose(&self, level: DiagnosticLevel, message: String) {
+        self.inner
+            .lock()
+            .unwrap()
+            .diagnostics
+            .push((level, message))
+    }
+}
+
+/// A trial that was added with [`Tester::add`].
+pub struct TrialHandle {
+    inner: Arc<Mutex<TesterInner>>,
+    index: usize,
+}
+
+impl TrialHandle {
+    /// Fails the trial as timed out if it runs for longer than `timeout`.
+    /// (Default: `--timeout`, or no timeout)
+    ///
+    /// The test's future is dropped once the timeout passes, so it stops at
+    /// its next `.await`. With [`retries`](Self::retries), every attempt gets
+    /// the full timeout.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.update(|info| info.timeout = Some(timeout))
+    }
+
+    /// Runs the trial again, up to `retries` more times, if it fails.
+    /// (Default: `--retries`, or `0`)
+    ///
+    /// A trial that passes on a retry counts as passed, and as flaky in the
+    /// summary. Every failed attempt is reported as `TRY n FAIL`.
+    pub fn retries(self, retries: usize) -> Self {
+        self.update(|info| info.retries = Some(retries))
+    }
+
+    /// Puts the trial in a group. Trials of the same group run one at a
+    /// time, e.g. because they share a resource like a port or a database.
+    pub fn group(self, group: impl Into<String>) -> Self {
+        let group = group.into();
+        self.update(|info| info.group = Some(group))
+    }
+
+    fn update(self, f: impl FnOnce(&mut TestInfo)) -> Self {
+        f(&mut self.inner.lock().unwrap().tasks[self.index].info);
+        self
+    }
+}
+
+impl fmt::Debug for TrialHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        f.debug_struct("TrialHandle")
+            .field("name", &inner.tasks[self.index].info.name)
+            .finish_non_exhaustive()
     }
 }
 
 struct TesterInner {
     tasks: Vec<Trial>,
+    /// Diagnostics of the builder that is currently running.
+    diagnostics: Vec<(DiagnosticLevel, String)>,
 }
 
 mod builder {
     use std::{any::TypeId, marker::PhantomData};
 
-    use crate::{AnySharedVal, Tester};
+    use crate::{AnySharedVal, Fut, Tester};
 
     pub trait TestRequirementHasSetupFnFor<T> {}
 
@@ -319,49 +767,168 @@ mod builder {
         pub type_id: fn() -> TypeId,
         pub module: &'static str,
         pub function: &'static str,
+        pub suite: Option<&'static str>,
         // file: &'static str,
         // line: u32,
         pub setup: fn() -> tokio::task::JoinHandle<AnySharedVal>,
     }
     inventory::collect!(SetupInit);
 
-    pub struct TestBuilder(pub fn(tester: Tester));
+    pub struct TestBuilder {
+        pub module: &'static str,
+        pub function: &'static str,
+        pub suite: Option<&'static str>,
+        pub build: Build,
+    }
+
+    pub enum Build {
+        Sync(fn(tester: Tester)),
+        /// Runs on the harness runtime before any test is dispatched.
+        Async(fn(tester: Tester) -> Fut),
+    }
     inventory::collect!(TestBuilder);
 }
 
-fn setup_tests() -> (Vec<Trial>, &'static Context) {
+/// Everything gathered by running the [`tests!`] functions.
+struct Collected {
+    tests: Vec<Trial>,
+    context: &'static Context,
+    errors: Vec<CollectionError>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Runs the `tests!` functions, only those of `krate` if it is set.
+fn setup_tests(runtime: &tokio::runtime::Runtime, scope: &Scope) -> Collected {
     let mut context = Context {
         values: HashMap::new(),
     };
-    for setup in inventory::iter::<builder::SetupInit>() {
+    for setup in inventory::iter::<builder::SetupInit>().filter(|setup| {
+        scope
+            .excludes(Kind::Setup, setup.module, setup.suite)
+            .is_none()
+    }) {
         context.values.insert(
             (setup.type_id)(),
-            Arc::new(Setup {
-                module: setup.module,
-                function: setup.function,
-                // file: setup.file,
-                // line: setup.line,
-                setup: setup.setup,
-                value: tokio::sync::OnceCell::new(),
-            }),
+            Arc::new(Setup::new(setup.module, setup.function, setup.setup)),
         );
     }
     let context: &'static Context = Box::leak(Box::new(context));
     let tester = Tester {
         context,
-        inner: Arc::new(Mutex::new(TesterInner { tasks: vec![] })),
+        inner: Arc::new(Mutex::new(TesterInner {
+            tasks: vec![],
+            diagnostics: vec![],
+        })),
     };
-    for builder in inventory::iter::<builder::TestBuilder>() {
-        (builder.0)(tester.clone())
+    let mut errors = vec![];
+    let mut diagnostics = vec![];
+    for builder in inventory::iter::<builder::TestBuilder>().filter(|builder| {
+        scope
+            .excludes(Kind::TestBuilder, builder.module, builder.suite)
+            .is_none()
+    }) {
+        let path = format!("{}::{}", builder.module, builder.function);
+
+        // Tests added before the panic are kept, they are still valid.
+        let res = match builder.build {
+            builder::Build::Sync(build) => catch_unwind(AssertUnwindSafe(|| build(tester.clone())))
+                .map_err(|payload| {
+                    let msg = payload::message(&*payload).unwrap_or("Box<dyn Any>".into());
+                    describe_panic(&msg, "async_test::setup_tests").message
+                }),
+            builder::Build::Async(build) => {
+                match runtime.block_on(CatchUnwind(build(tester.clone()), ShouldPanic::No)) {
+                    Outcome::Passed => Ok(()),
+                    Outcome::Failed(failures) => Err(failure_output(&failures)),
+                }
+            }
+        };
+
+        let emitted = std::mem::take(&mut tester.inner.lock().unwrap().diagnostics);
+        diagnostics.extend(emitted.into_iter().map(|(level, message)| Diagnostic {
+            level,
+            builder: path.clone(),
+            message,
+        }));
+
+        if let Err(message) = res {
+            errors.push(CollectionError {
+                builder: path,
+                message,
+            });
+        }
+    }
+    let tests = std::mem::take(&mut tester.inner.lock().unwrap().tasks);
+    Collected {
+        tests,
+        context,
+        errors,
+        diagnostics,
     }
-    let tasks = std::mem::take(&mut tester.inner.lock().unwrap().tasks);
-    (tasks, context)
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct TestInfo {
     name: String,
     is_ignored: bool,
+    tags: Vec<String>,
+    /// The first `cfg` passed to `with_required_cfg` that does not hold.
+    unmet_cfg: Option<String>,
+    /// The services the test requires, see [`Trial::with_requires_network`].
+    capabilities: Vec<Capability>,
+    main_thread: bool,
+    description: Option<String>,
+    links: Vec<report::Link>,
+    expected_failure: Option<String>,
+    ignore_reason: Option<String>,
+    /// See [`TrialHandle::timeout`]. `None` uses `--timeout`.
+    timeout: Option<Duration>,
+    /// See [`Trial::with_expected_duration`].
+    expected_duration: Option<Duration>,
+    /// See [`TrialHandle::retries`]. `None` uses `--retries`.
+    retries: Option<usize>,
+    /// See [`TrialHandle::group`].
+    group: Option<String>,
+    /// See [`Trial::with_should_panic`].
+    should_panic: ShouldPanic,
+}
+
+/// Whether a test passes by panicking, like `#[should_panic]` of libtest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum ShouldPanic {
+    #[default]
+    No,
+    Yes,
+    /// The panic message must contain the string.
+    YesWithMessage(String),
+}
+
+impl TestInfo {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            is_ignored: false,
+            tags: vec![],
+            unmet_cfg: None,
+            capabilities: vec![],
+            main_thread: false,
+            description: None,
+            links: vec![],
+            expected_failure: None,
+            ignore_reason: None,
+            timeout: None,
+            expected_duration: None,
+            retries: None,
+            group: None,
+            should_panic: ShouldPanic::No,
+        }
+    }
+
+    fn require(&mut self, capability: Capability) {
+        if !self.capabilities.contains(&capability) {
+            self.capabilities.push(capability);
+        }
+    }
 }
 
 /// The outcome of performing a test/benchmark.
@@ -370,8 +937,8 @@ enum Outcome {
     /// The test passed.
     Passed,
 
-    /// The test failed.
-    Failed(String),
+    /// The test failed, for every reason in the order they happened.
+    Failed(Vec<report::Failure>),
 }
 
 /// Contains information about the entire test run. Is returned by [`run`].
@@ -392,27 +959,66 @@ pub struct Conclusion {
 
     /// Number of failed tests and benchmarks.
     pub num_failed: usize,
+
+    /// Number of tests that failed at first and passed on a retry. Included
+    /// in `num_passed`, so that CI can gate on them separately from
+    /// failures.
+    pub num_flaky: usize,
+
+    /// Number of [`tests!`] functions that panicked while collecting tests.
+    pub num_collection_errors: usize,
+
+    /// Whether `--run-timeout` passed before all tests finished. The tests
+    /// that were running count as failed.
+    pub run_timed_out: bool,
+
+    /// Whether the run was canceled through a [`RunHandle`] before all tests
+    /// started. The run counts as failed, since not every test ran.
+    pub canceled: bool,
+
+    /// The signal, e.g. Ctrl-C, that canceled the run before all tests
+    /// finished. The run counts as failed, and [`exit()`][Conclusion::exit]
+    /// exits with the code a shell reports for a process the signal ended.
+    pub signal: Option<Signal>,
 }
 
 impl Conclusion {
     /// Exits the application with an appropriate error code (0 if all tests
-    /// have passed, 101 if there have been failures).
+    /// have passed, 101 if there have been failures or the run was canceled,
+    /// 124 if the run timed out, 128 plus the number of the signal that
+    /// canceled the run, e.g. 130 on Ctrl-C).
     pub fn exit(&self) -> ! {
         self.exit_if_failed();
         process::exit(0);
     }
 
-    /// Exits the application with error code 101 if there were any failures.
-    /// Otherwise, returns normally.
+    /// Exits the application with error code 101 if there were any failures
+    /// or the run was canceled, 124 if the run timed out, or the code of the
+    /// signal that canceled the run. Otherwise, returns normally.
     pub fn exit_if_failed(&self) {
+        // Like the signal ended the process, only once the reports are
+        // written.
+        if let Some(signal) = self.signal {
+            process::exit(signal.exit_code())
+        }
+        // The code of `timeout(1)`, so that CI can tell a run that ran out of
+        // time from one with failures.
+        if self.run_timed_out {
+            process::exit(124)
+        }
         if self.has_failed() {
             process::exit(101)
         }
     }
 
-    /// Returns whether there have been any failures.
+    /// Returns whether there have been any failures, or the run ended
+    /// before all tests ran.
     pub fn has_failed(&self) -> bool {
         self.num_failed > 0
+            || self.num_collection_errors > 0
+            || self.run_timed_out
+            || self.canceled
+            || self.signal.is_some()
     }
 
     fn empty() -> Self {
@@ -420,6 +1026,11 @@ impl Conclusion {
             num_filtered_out: 0,
             num_passed: 0,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     }
 }
@@ -430,29 +1041,59 @@ impl Arguments {
         test.info.is_ignored && !self.ignored && !self.include_ignored
     }
 
-    fn is_filtered_out(&self, test: &Trial) -> Option<MismatchReason> {
-        let test_name = &test.info.name;
+    /// Returns `true` if the given filter (or skip filter) selects this test.
+    ///
+    /// `requires(Type)` selects tests that require a fixture of that type.
+    /// Without `--exact`, the last segment of the type path is enough.
+    fn matches(&self, test: &Trial, filter: &str) -> bool {
+        if let Some(ty) = filter
+            .strip_prefix("requires(")
+            .and_then(|f| f.strip_suffix(')'))
+        {
+            return test.requires.iter().any(|(name, _)| match self.exact {
+                true => *name == ty,
+                false => *name == ty || name.ends_with(&format!("::{ty}")),
+            });
+        }
 
+        match self.exact {
+            true => test.info.name == filter,
+            false => test.info.name.contains(filter),
+        }
+    }
+
+    fn is_filtered_out(&self, test: &Trial) -> Option<MismatchReason> {
         // If a filter was specified, apply this
-        let matches_filter = self.filter.iter().any(|filter| match self.exact {
-            true if test_name == filter => true,
-            false if test_name.contains(filter) => true,
-            _ => false,
-        });
+        let matches_filter = self.filter.iter().any(|filter| self.matches(test, filter));
         if !self.filter.is_empty() && !matches_filter {
             return Some(MismatchReason::String);
         }
 
+        // Both the filters and one of the expressions need to match.
+        let matches_expr = self.filter_expr.iter().any(|expr| expr.matches(test));
+        if !self.filter_expr.is_empty() && !matches_expr {
+            return Some(MismatchReason::Expression);
+        }
+
         // If any skip pattern were specified, test for all patterns.
-        let matches_skip = self.skip.iter().any(|skip_filter| match self.exact {
-            true if test_name == skip_filter => true,
-            false if test_name.contains(skip_filter) => true,
-            _ => false,
-        });
+        let matches_skip = self
+            .skip
+            .iter()
+            .any(|skip_filter| self.matches(test, skip_filter));
         if matches_skip {
             return Some(MismatchReason::String);
         }
 
+        // A test needs at least one of the `--tag` tags (if any are given) and
+        // none of the `--exclude-tag` tags.
+        let tags = &test.info.tags;
+        if !self.tag.is_empty() && !self.tag.iter().any(|tag| tags.contains(tag)) {
+            return Some(MismatchReason::Tag);
+        }
+        if self.exclude_tag.iter().any(|tag| tags.contains(tag)) {
+            return Some(MismatchReason::Tag);
+        }
+
         if self.ignored ^ test.info.is_ignored {
             return Some(MismatchReason::Ignored);
         }
@@ -480,22 +1121,209 @@ pub fn main() {
 /// [`Conclusion`] for more information. If `--list` was specified, a list is
 /// printed and a dummy `Conclusion` is returned.
 pub fn run(args: &Arguments) -> Conclusion {
-    let start_instant = SystemTime::now();
+    run_with_clock(args, SystemClock)
+}
+
+/// Runs all given tests, measuring time with `clock`.
+///
+/// Like [`run`], but slow-test ticks and durations use the given clock. This
+/// is mostly useful with a [`MockClock`](clock::MockClock) to test how slow
+/// tests are reported.
+pub fn run_with_clock(args: &Arguments, clock: impl Clock) -> Conclusion {
+    run_controlled(args, clock, &RunHandle::new())
+}
+
+/// Runs all given tests, like [`run`], while `handle` can pause, resume or
+/// cancel the run from another thread.
+///
+/// A canceled run reports the tests that were held back as not run, and
+/// counts as failed, see [`Conclusion::canceled`].
+pub fn run_with_handle(args: &Arguments, handle: &RunHandle) -> Conclusion {
+    run_controlled(args, SystemClock, handle)
+}
+
+/// Runs all given tests, like [`run`], passing every event to `reporter`
+/// instead of writing the regular output.
+///
+/// The events are those of `--format json`, see the [`report`] module.
+/// Everything that is written next to the output, like `--junit-path` and
+/// `--run-log`, is still written.
+///
+/// ```no_run
+/// use async_test::report::Event;
+///
+/// let args = async_test::Arguments::from_args();
+/// async_test::run_with_reporter(&args, |event: &Event| {
+///     if let Event::TestFinished(result) = event {
+///         println!("{}: {:?}", result.name, result.status);
+///     }
+/// })
+/// .exit();
+/// ```
+pub fn run_with_reporter(args: &Arguments, reporter: impl Reporter) -> Conclusion {
+    let listener = Listener::Reporter(Box::new(reporter));
+    run_reported(args, SystemClock, &RunHandle::new(), Some(listener))
+}
+
+/// Runs all given tests, like [`run`], and also passes every event to
+/// `observer`, e.g. to show the progress of the run in a GUI.
+///
+/// Unlike [`run_with_reporter`], the regular output is still written. The
+/// observer is called on the thread that writes it, so it should hand the
+/// events off instead of blocking, e.g. through a channel.
+///
+/// ```no_run
+/// use async_test::report::Event;
+///
+/// let args = async_test::Arguments::from_args();
+/// let (tx, rx) = std::sync::mpsc::channel::<Event>();
+/// std::thread::spawn(move || {
+///     for event in rx {
+///         // Update the GUI.
+///     }
+/// });
+/// async_test::run_with_observer(&args, move |event: &Event| {
+///     _ = tx.send(event.clone());
+/// })
+/// .exit();
+/// ```
+pub fn run_with_observer(args: &Arguments, observer: impl Reporter) -> Conclusion {
+    let listener = Listener::Observer(Box::new(observer));
+    run_reported(args, SystemClock, &RunHandle::new(), Some(listener))
+}
+
+/// Who gets the events of a run besides the regular output.
+enum Listener<'a> {
+    /// Gets them instead of the regular output.
+    Reporter(Box<dyn Reporter + 'a>),
+    /// Gets them next to the regular output.
+    Observer(Box<dyn Reporter + 'a>),
+}
+
+fn run_controlled(args: &Arguments, clock: impl Clock, handle: &RunHandle) -> Conclusion {
+    run_reported(args, clock, handle, None)
+}
 
-    let (mut tests, context) = setup_tests();
+fn run_reported(
+    args: &Arguments,
+    clock: impl Clock,
+    handle: &RunHandle,
+    listener: Option<Listener<'_>>,
+) -> Conclusion {
+    // The wall clock is only used for report timestamps. It can step
+    // backwards, so all durations are measured on the monotonic `clock`.
+    let start_time = SystemTime::now();
+    let run_start = clock.now();
+
+    if let Some(name) = &args.extract_output {
+        return replay::extract_output(args, name);
+    }
+    if args.harness_info {
+        printer::Printer::new(args).print_harness_info();
+        return Conclusion::empty();
+    }
+
+    // Loaded first, so that detecting CI and colors sees its variables.
+    let env_file_keys = args.env_file.as_deref().map(env_file::load);
+    let mut config = RunConfig::new(args);
+    config.clock = Arc::new(clock);
+    config.env_file_keys = env_file_keys.unwrap_or_default();
+    if let Some(path) = &args.thresholds {
+        config.thresholds = Thresholds::load(path);
+    }
+    if let Some(path) = &args.retention {
+        config.retention = Retention::load(path);
+    }
+    // Listing tests runs none of them.
+    if let (Some(path), false) = (&args.env_requirements, args.list) {
+        EnvRequirements::load(path).enforce(path);
+    }
+    let scope = Scope {
+        krate: args.own_crate_only.then(registration::own_crate),
+        suite: args.suite.clone(),
+    };
+    if args.explain_registration {
+        let registrations = registration::registrations(&scope);
+        printer::Printer::new(args).print_registrations(&registrations);
+        return Conclusion::empty();
+    }
+    // Created before collecting so that `async_tests!` functions can use it.
+    let runtime = build_runtime(&config);
+
+    let mut collected = with_recorded_panics(|| setup_tests(&runtime, &scope));
+    if let Some(mapper) = args.name_mapper {
+        for test in &mut collected.tests {
+            test.info.name = mapper(&test.info.name);
+        }
+    }
 
     // If `--list` is specified, just print the list and return.
     if args.list {
-        if !args.filter.is_empty() || !args.skip.is_empty() || args.ignored {
+        let Collected {
+            mut tests,
+            context,
+            errors,
+            ..
+        } = collected;
+
+        if !args.filter.is_empty()
+            || !args.skip.is_empty()
+            || !args.filter_expr.is_empty()
+            || !args.tag.is_empty()
+            || !args.exclude_tag.is_empty()
+            || args.ignored
+        {
             tests.retain(|test| args.is_filtered_out(test).is_none());
         }
 
         let mut printer = printer::Printer::new(args);
-        printer.print_list(&tests, args.ignored);
-        return Conclusion::empty();
+        printer.print_list(&tests, context, &errors, args.ignored, args.verbose);
+        return Conclusion {
+            num_collection_errors: errors.len(),
+            ..Conclusion::empty()
+        };
+    }
+
+    let runs = cache::artifacts_root()
+        .join("runs")
+        .join(cache::binary_name());
+    config.retention.prune(&runs, &screen::dir(), start_time);
+
+    with_recorded_panics(|| {
+        run_nextest(
+            args,
+            (start_time, run_start),
+            config,
+            runtime,
+            collected,
+            handle,
+            listener,
+        )
+    })
+}
+
+fn build_runtime(config: &RunConfig) -> tokio::runtime::Runtime {
+    let mut runtime;
+
+    match config.threads.get() {
+        1 => runtime = tokio::runtime::Builder::new_current_thread(),
+        num_threads => {
+            runtime = tokio::runtime::Builder::new_multi_thread();
+            runtime.worker_threads(num_threads - 1);
+        }
+    };
+
+    // Named so that they can be told apart in crash reports and profilers.
+    let next_worker = AtomicUsize::new(0);
+    runtime.thread_name_fn(move || {
+        let id = next_worker.fetch_add(1, Ordering::Relaxed);
+        format!("async-test-worker-{id}")
+    });
+    if let Some(size) = config.thread_stack_size {
+        runtime.thread_stack_size(size);
     }
 
-    run_nextest(args, start_instant, &mut tests, context)
+    runtime.enable_all().build().unwrap()
 }
 
 struct Location {
@@ -508,56 +1336,114 @@ thread_local! {
     static BT: Cell<(Backtrace, Option<Location>)> = Cell::new((Backtrace::disabled(), None));
 }
 
+/// Runs `f` with a panic hook that records panics in `BT` instead of
+/// printing them. They are reported through [`describe_panic`].
+fn with_recorded_panics<R>(f: impl FnOnce() -> R) -> R {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let bt = std::backtrace::Backtrace::capture();
+        let location = info.location().map(|loc| Location {
+            file: loc.file().to_owned(),
+            line: loc.line(),
+            column: loc.column(),
+        });
+        BT.with(|x| x.set((bt, location)));
+    }));
+
+    let res = f();
+
+    std::panic::set_hook(hook);
+    res
+}
+
+/// How long the tests that are running at Ctrl-C, SIGTERM or SIGHUP get to
+/// finish.
+const SIGNAL_GRACE: Duration = Duration::from_secs(2);
+
+/// Runs the collected tests.
 fn run_nextest(
     args: &Arguments,
-    start_instant: SystemTime,
-    tests: &mut [Trial],
-    context: &'static Context,
+    (start_time, run_start): (SystemTime, Instant),
+    config: RunConfig,
+    runtime: tokio::runtime::Runtime,
+    collected: Collected,
+    handle: &RunHandle,
+    listener: Option<Listener<'_>>,
 ) -> Conclusion {
+    let Collected {
+        mut tests,
+        context,
+        errors,
+        diagnostics,
+    } = collected;
+
     let mut test_list = TestList {
         tests: vec![],
         skip_count: 0,
+        filtered_out: 0,
+        ignored: 0,
+        offline: 0,
     };
 
     let conclusion = Conclusion::empty();
 
-    let threads = match args.test_threads.and_then(NonZeroUsize::new) {
-        None => std::thread::available_parallelism().unwrap_or(NonZeroUsize::new(1).unwrap()),
-        Some(num_threads) => num_threads,
-    };
-
-    let mut runtime;
-
-    match threads.get() {
-        1 => runtime = tokio::runtime::Builder::new_current_thread(),
-        num_threads => {
-            runtime = tokio::runtime::Builder::new_multi_thread();
-            runtime.worker_threads(num_threads - 1);
-        }
-    };
-
-    let runtime = runtime.enable_all().build().unwrap();
-
-    let tasks = match args.test_tasks.and_then(NonZeroUsize::new) {
-        Some(tasks) => tasks,
-        None => threads,
-    };
-
     #[derive(Debug)]
     enum TestState {
         Skipped {
             name: String,
             reason: MismatchReason,
+            message: Option<String>,
+        },
+        Start {
+            info: TestInfo,
+        },
+        NotRun {
+            info: TestInfo,
+            reason: MismatchReason,
+        },
+        /// `--run-timeout` passed.
+        RunTimedOut {
+            after: Duration,
+        },
+        /// Ctrl-C was pressed, or SIGTERM or SIGHUP received.
+        Signaled(Signal),
+        /// The tests that were still running after the signal took too long,
+        /// and are reported as not run with the tests that did not start.
+        Abandoned,
+        /// An attempt ran out of time, and is dropped.
+        Terminating {
+            info: TestInfo,
+            elapsed: Duration,
+        },
+        /// An attempt failed, and the test runs again.
+        Retry {
+            info: TestInfo,
+            attempt: usize,
+            duration: Duration,
+            failures: Vec<report::Failure>,
+            timed_out: bool,
         },
-        Start {},
         StartSetup {},
         DoneSetup {
             name: String,
             start: SystemTime,
+            duration: Duration,
         },
         Done {
             start: SystemTime,
+            duration: Duration,
+            /// The time the process was suspended for while the test ran.
+            suspended: Duration,
+            /// The time the test waited for a task permit.
+            queued: Duration,
+            /// The time the test waited for its fixtures to be set up.
+            setup_wait: Duration,
+            meta: BTreeMap<String, report::MetaValue>,
             outcome: Outcome,
+            timed_out: bool,
+            attempts: usize,
+            iterations: Option<report::Iterations>,
+            determinism: Option<report::Determinism>,
             info: TestInfo,
             slow: bool,
         },
@@ -565,31 +1451,159 @@ fn run_nextest(
             elapsed: Duration,
             info: TestInfo,
         },
+        ConcurrencyReduced(report::ConcurrencyReduction),
+        Control(Control),
     }
 
-    let slow_period = Duration::from_secs(15);
-
-    let semaphore = Arc::new(Semaphore::new(tasks.get()));
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let slow_period = config.slow_period;
+    let suspend = SuspendDetector::start();
+    // Set once a test fails with `--fail-fast`, after which no more tests
+    // start.
+    let failed_fast = Arc::new(AtomicBool::new(false));
+    // Set once `--run-timeout` passes, after which running tests are dropped
+    // and no more tests start.
+    let run_timed_out = Arc::new(AtomicBool::new(false));
+    // Set on Ctrl-C, SIGTERM or SIGHUP, after which no more tests start.
+    let interrupted = Arc::new(AtomicBool::new(false));
+    // Installed before any test starts, so that the default handlers never
+    // end the process halfway through a line of output.
+    let mut signals = Signals::install();
+    let mut received = None;
+
+    let semaphore = Arc::new(Semaphore::new(config.tasks.get()));
+    let adaptive = config
+        .adaptive_concurrency
+        .then(|| Arc::new(Adaptive::new(semaphore.clone(), config.tasks.get())));
+    let heavy_semaphore = Arc::new(Semaphore::new(config.heavy_tasks.get()));
+    // Removed once the setups that built artifacts in it are gone.
+    let scratch = Arc::new(ScratchDir::default());
+    let mut group_semaphores = HashMap::<String, Arc<Semaphore>>::new();
+    // Events are written by a dedicated reporter thread, so a slow terminal or
+    // logfile never holds up the runtime that is polling the tests.
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut handles = vec![];
+    let mut main_thread_tests: Vec<Pin<Box<dyn Future<Output = ()>>>> = vec![];
 
     let mut stats = RunStats::default();
+    // The durations of the finished tests, summarized at the end.
+    let mut durations = vec![];
 
-    // don't log panics, catch and record them instead
-    let hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |info| {
-        let bt = std::backtrace::Backtrace::capture();
-        let location = info.location().map(|loc| Location {
-            file: loc.file().to_owned(),
-            line: loc.line(),
-            column: loc.column(),
-        });
-        BT.with(|x| x.set((bt, location)));
-    }));
+    stats.collection_errors = errors.len();
+
+    let mut compare = args.compare_with.as_deref().map(Compare::load);
 
-    for test in tests.iter_mut() {
+    if let Some(shuffle) = &config.shuffle {
+        shuffle.shuffle(&mut tests);
+    }
+
+    // The parts are dealt from the tests that pass the filters, so that the
+    // parts of every machine in a sharded run add up to the whole run.
+    let mut in_partition = vec![true; tests.len()];
+    if let Some(partition) = args.partition {
+        let candidates: Vec<usize> = (0..tests.len())
+            .filter(|&i| args.is_filtered_out(&tests[i]).is_none())
+            .collect();
+        let names: Vec<&str> = candidates
+            .iter()
+            .map(|&i| tests[i].info.name.as_str())
+            .collect();
+        for (&i, selected) in candidates.iter().zip(partition::select(partition, &names)) {
+            in_partition[i] = selected;
+        }
+    }
+
+    // The sample is picked from the tests that would run without it.
+    let mut sampled = vec![true; tests.len()];
+    if let Some(sample) = &config.sample {
+        let candidates: Vec<usize> = (0..tests.len())
+            .filter(|&i| {
+                args.is_filtered_out(&tests[i]).is_none()
+                    && in_partition[i]
+                    && tests[i].info.unmet_cfg.is_none()
+                    && !(config.offline && !tests[i].info.capabilities.is_empty())
+            })
+            .collect();
+        let names: Vec<&str> = candidates
+            .iter()
+            .map(|&i| tests[i].info.name.as_str())
+            .collect();
+        for (&i, selected) in candidates.iter().zip(sample.select(&names)) {
+            sampled[i] = selected;
+        }
+    }
+
+    let probes = Probes::default();
+    for (test, (sampled, in_partition)) in
+        tests.iter_mut().zip(sampled.into_iter().zip(in_partition))
+    {
         if let Some(reason) = args.is_filtered_out(&test) {
             stats.skipped += 1;
             test_list.skip_count += 1;
+            // With `--ignored`, the tests that are not ignored are the ones
+            // filtered out.
+            if reason == MismatchReason::Ignored && test.info.is_ignored {
+                stats.ignored += 1;
+                test_list.ignored += 1;
+            } else {
+                stats.filtered_out += 1;
+                test_list.filtered_out += 1;
+            }
+            // Filtered tests are not reported, but they still exist.
+            if let Some(compare) = &mut compare {
+                compare.skipped(&test.info.name);
+            }
+        } else if !in_partition {
+            stats.skipped += 1;
+            stats.filtered_out += 1;
+            test_list.skip_count += 1;
+            test_list.filtered_out += 1;
+            tx.send(TestState::Skipped {
+                name: test.info.name.clone(),
+                reason: MismatchReason::Partition,
+                message: args
+                    .partition
+                    .map(|partition| format!("not in partition {partition}")),
+            })
+            .unwrap();
+        } else if !sampled {
+            stats.skipped += 1;
+            stats.filtered_out += 1;
+            stats.sampled_out += 1;
+            test_list.skip_count += 1;
+            test_list.filtered_out += 1;
+            if let Some(compare) = &mut compare {
+                compare.skipped(&test.info.name);
+            }
+        } else if let Some(cfg) = &test.info.unmet_cfg {
+            stats.skipped += 1;
+            test_list.skip_count += 1;
+            tx.send(TestState::Skipped {
+                name: test.info.name.clone(),
+                reason: MismatchReason::Cfg,
+                message: Some(format!("requires cfg({cfg})")),
+            })
+            .unwrap();
+        } else if config.offline && !test.info.capabilities.is_empty() {
+            stats.skipped += 1;
+            stats.offline += 1;
+            test_list.skip_count += 1;
+            test_list.offline += 1;
+            let services: Vec<_> = test.info.capabilities.iter().map(|c| c.as_str()).collect();
+            tx.send(TestState::Skipped {
+                name: test.info.name.clone(),
+                reason: MismatchReason::Offline,
+                message: Some(format!("offline, requires {}", services.join(" and "))),
+            })
+            .unwrap();
+        } else if let Some(message) = probes.unavailable(&test.info.capabilities) {
+            stats.skipped += 1;
+            test_list.skip_count += 1;
+            tx.send(TestState::Skipped {
+                name: test.info.name.clone(),
+                reason: MismatchReason::Unavailable,
+                message: Some(message),
+            })
+            .unwrap();
         } else {
             stats.initial_run_count += 1;
             test_list.tests.push(test.info.clone());
@@ -599,57 +1613,373 @@ fn run_nextest(
 
             for (requirement, id) in &test.requires {
                 if let Some(s) = context.values.get(&id) {
+                    s.tests.fetch_add(1, Ordering::AcqRel);
                     let tx = tx.clone();
+                    let clock = config.clock.clone();
+                    let env = SetupEnv {
+                        fixture_cache: config.fixture_cache,
+                        test_args: config.test_args.clone(),
+                        scratch: scratch.clone(),
+                    };
                     let permit = semaphore.clone().acquire_owned();
                     let wg_permit = wg.clone().try_acquire_owned().unwrap();
-                    runtime.spawn(async move {
+                    handles.push(runtime.spawn(async move {
                         let _wg_permit = wg_permit;
+                        let waiting = s.waiting.fetch_add(1, Ordering::AcqRel) + 1;
+                        s.peak_waiting.fetch_max(waiting, Ordering::AcqRel);
                         s.value
                             .get_or_init(move || async move {
                                 let _permit = permit.await.unwrap();
-                                let start = SystemTime::now();
+                                let start = clock.now();
+                                let start_time = SystemTime::now();
 
                                 tx.send(TestState::StartSetup {}).unwrap();
-                                let res = (s.setup)().await.unwrap();
+                                let res = env.scope(s.setup).await.unwrap();
+                                let duration = clock.now() - start;
+                                _ = s.init_duration.set(duration);
                                 tx.send(TestState::DoneSetup {
                                     name: s.function.to_owned(),
-                                    start,
+                                    start: start_time,
+                                    duration,
                                 })
                                 .unwrap();
                                 res
                             })
                             .await;
-                    });
+                        s.waiting.fetch_sub(1, Ordering::AcqRel);
+                    }));
                 }
             }
 
             let tx = tx.clone();
+            let heavy_permit = (test.info.tags.iter().any(|tag| tag == HEAVY_TAG))
+                .then(|| heavy_semaphore.clone().acquire_owned());
+            let group_permit = test.info.group.clone().map(|group| {
+                group_semaphores
+                    .entry(group)
+                    .or_insert_with(|| Arc::new(Semaphore::new(1)))
+                    .clone()
+                    .acquire_owned()
+            });
             let permit = semaphore.clone().acquire_owned();
             let runner = test.runner.take().unwrap();
-            let task = runner(context);
+            let chaos = config.chaos;
+            let error_format = config.error_format;
+            let screen_lines = config.screen_lines;
+            let name = test.info.name.clone();
+            let should_panic = test.info.should_panic.clone();
+            // Every iteration of `--repeat` after the first sets up the
+            // fixtures of the test again.
+            let fresh_fixtures =
+                (config.repeat.is_some() && !test.requires.is_empty()).then(|| {
+                    let ids: Vec<_> = test.requires.iter().map(|(_, id)| *id).collect();
+                    let env = SetupEnv {
+                        fixture_cache: config.fixture_cache,
+                        test_args: config.test_args.clone(),
+                        scratch: scratch.clone(),
+                    };
+                    (ids, env)
+                });
+            let mut first = true;
+            let iteration = move || {
+                let mut task: Fut = match (&fresh_fixtures, first) {
+                    (Some((ids, env)), false) => {
+                        let context = context.fresh(ids);
+                        let (env, task) = (env.clone(), runner(context));
+                        Box::pin(async move {
+                            context.set_up(env).await;
+                            task.await
+                        })
+                    }
+                    _ => runner(context),
+                };
+                first = false;
+                if let Some(chaos) = &chaos {
+                    task = chaos.wrap(&name, task);
+                }
+                task = Box::pin(out::scoped(name.clone(), task));
+                let outcome = check::checked(error_format, CatchUnwind(task, should_panic.clone()));
+                screen::captured(name.clone(), screen_lines, outcome)
+            };
+            // Tests with an expected duration or a history are slow relative
+            // to it, but only when slow tests are reported at all.
+            let slow_period = slow_period.map(|period| {
+                match (test.info.expected_duration, config.slow_history.as_ref()) {
+                    (Some(expected), _) => expected,
+                    (None, Some(history)) => history.period(&test.info.name).unwrap_or(period),
+                    (None, None) => period,
+                }
+            });
+            let repeat = config.repeat;
+            let verify_runs = config.verify_deterministic;
+            let time_budget = config.time_budget;
+            let fail_fast = config.fail_fast;
+            let retries = config.retries;
+            let default_timeout = config.timeout;
+            let timeout_grace = config.timeout_grace;
+            let run_timeout = config.run_timeout;
+            let failed_fast = failed_fast.clone();
+            let run_timed_out = run_timed_out.clone();
+            let interrupted = interrupted.clone();
+            let mut control = handle.subscribe();
             let info = test.info.clone();
+            let clock = config.clock.clone();
+            let suspend = suspend.clone();
+            let adaptive = adaptive.clone();
             let test_task = async move {
+                let setup_wait_at = clock.now();
                 let _wg_permit = wg.acquire_many_owned(req_len).await.unwrap();
+                let setup_wait = clock.now() - setup_wait_at;
+                // Taken first, so that waiting heavy tests don't block the
+                // other tests.
+                let _heavy_permit = match heavy_permit {
+                    Some(permit) => Some(permit.await.unwrap()),
+                    None => None,
+                };
+                let _group_permit = match group_permit {
+                    Some(permit) => Some(permit.await.unwrap()),
+                    None => None,
+                };
+                let queued_at = clock.now();
                 let _permit = permit.await.unwrap();
+                let queued = clock.now() - queued_at;
+                // Only the first test to notice tells the reporter.
+                let notify_run_timed_out = |after| {
+                    if !run_timed_out.swap(true, Ordering::AcqRel) {
+                        tx.send(TestState::RunTimedOut { after }).unwrap();
+                    }
+                };
+                if let Some(after) =
+                    run_timeout.filter(|&run_timeout| clock.now() - run_start >= run_timeout)
+                {
+                    notify_run_timed_out(after);
+                    tx.send(TestState::NotRun {
+                        info,
+                        reason: MismatchReason::RunTimeout,
+                    })
+                    .unwrap();
+                    return;
+                }
+                // Tests that are already running finish, but none start once
+                // the budget is used up.
+                if time_budget.is_some_and(|budget| clock.now() - run_start >= budget) {
+                    tx.send(TestState::NotRun {
+                        info,
+                        reason: MismatchReason::Budget,
+                    })
+                    .unwrap();
+                    return;
+                }
+                if failed_fast.load(Ordering::Acquire) {
+                    tx.send(TestState::NotRun {
+                        info,
+                        reason: MismatchReason::FailFast,
+                    })
+                    .unwrap();
+                    return;
+                }
+                if interrupted.load(Ordering::Acquire) {
+                    tx.send(TestState::NotRun {
+                        info,
+                        reason: MismatchReason::Interrupted,
+                    })
+                    .unwrap();
+                    return;
+                }
+                if !control::wait_runnable(&mut control).await {
+                    tx.send(TestState::NotRun {
+                        info,
+                        reason: MismatchReason::Canceled,
+                    })
+                    .unwrap();
+                    return;
+                }
                 let start = SystemTime::now();
+                // Time that the process was suspended for, e.g. by the
+                // terminal, counts neither towards the slow period nor
+                // towards the timeout or the duration.
+                let started = (clock.now(), suspend.total());
+
+                let timeout = info.timeout.or(default_timeout);
+                let retries = info.retries.unwrap_or(retries);
+                let expected_failure = info.expected_failure.is_some();
+                let test_name = info.name.clone();
+                let attempt_info = info.clone();
+                let mut test_task = std::pin::pin!(meta::collected(async {
+                    let mut iteration = iteration;
+                    let mut attempts = 0;
+                    // Attempts that ran out of resources at a higher limit
+                    // than the current one, which don't count as retries.
+                    let mut exhausted = 0;
+                    loop {
+                        attempts += 1;
+                        let limit = adaptive.as_ref().map(|adaptive| adaptive.limit());
+                        let attempt_started = (clock.now(), suspend.total());
+                        let token = Arc::new(cancel::Token::default());
+                        let attempt = cancel::scoped(token.clone(), async {
+                            match verify_runs {
+                                Some(runs) => {
+                                    let (outcome, determinism) =
+                                        verify::verified(runs, &mut iteration).await;
+                                    (outcome, None, Some(determinism))
+                                }
+                                None => {
+                                    let (outcome, iterations) =
+                                        repeat::repeated(repeat, &*clock, &mut iteration).await;
+                                    (outcome, iterations, None)
+                                }
+                            }
+                        });
+                        let mut attempt = std::pin::pin!(attempt);
+                        let test_deadline = async {
+                            match timeout {
+                                Some(timeout) => {
+                                    suspend
+                                        .sleep_active(&*clock, attempt_started, timeout)
+                                        .await;
+                                    timeout
+                                }
+                                None => std::future::pending().await,
+                            }
+                        };
+                        // Unlike the timeout of the test, the deadline of the
+                        // run is on the wall clock, like a CI job's.
+                        let run_deadline = async {
+                            match run_timeout {
+                                Some(run_timeout) => {
+                                    let left = (run_start + run_timeout)
+                                        .saturating_duration_since(clock.now());
+                                    clock.sleep(left).await;
+                                    run_timeout
+                                }
+                                None => std::future::pending().await,
+                            }
+                        };
+                        let terminate = |message| {
+                            tx.send(TestState::Terminating {
+                                info: attempt_info.clone(),
+                                elapsed: suspend.active_since(&*clock, started),
+                            })
+                            .unwrap();
+                            let failure = report::Failure {
+                                message,
+                                location: None,
+                            };
+                            (Outcome::Failed(vec![failure]), None, None, true)
+                        };
+                        let (outcome, iterations, determinism, timed_out) = tokio::select! {
+                            biased;
+                            (outcome, iterations, determinism) = &mut attempt => {
+                                (outcome, iterations, determinism, false)
+                            }
+                            timeout = test_deadline => {
+                                // A test that never looked at the token would
+                                // not stop by itself.
+                                let stopped = token.request() && tokio::select! {
+                                    biased;
+                                    _ = &mut attempt => true,
+                                    () = clock.sleep(timeout_grace) => false,
+                                };
+                                terminate(match stopped {
+                                    true => format!(
+                                        "test timed out after {timeout:?}, and stopped by itself \
+                                        within the grace period of {timeout_grace:?}"
+                                    ),
+                                    false => format!(
+                                        "test timed out after {timeout:?}, and was dropped"
+                                    ),
+                                })
+                            }
+                            run_timeout = run_deadline => {
+                                notify_run_timed_out(run_timeout);
+                                terminate(format!("the run timed out after {run_timeout:?}"))
+                            }
+                        };
+                        // A test that is expected to fail is not retried, its
+                        // failure is the point.
+                        if let (Some(adaptive), Some(limit), Outcome::Failed(failures), false) =
+                            (&adaptive, limit, &outcome, expected_failure)
+                        {
+                            if let Some(reason) = adaptive::exhaustion(failures) {
+                                if let Some(to) = adaptive.reduce(limit).await {
+                                    tx.send(TestState::ConcurrencyReduced(
+                                        report::ConcurrencyReduction {
+                                            test: test_name.clone(),
+                                            reason: reason.to_owned(),
+                                            from: limit,
+                                            to,
+                                        },
+                                    ))
+                                    .unwrap();
+                                }
+                                if adaptive.limit() < limit {
+                                    exhausted += 1;
+                                    continue;
+                                }
+                            }
+                        }
+                        if matches!(outcome, Outcome::Passed)
+                            || expected_failure
+                            || attempts - exhausted > retries
+                            || run_timed_out.load(Ordering::Acquire)
+                        {
+                            break (outcome, iterations, determinism, timed_out, attempts);
+                        }
+                        if let Outcome::Failed(failures) = outcome {
+                            tx.send(TestState::Retry {
+                                info: attempt_info.clone(),
+                                attempt: attempts,
+                                duration: suspend.active_since(&*clock, attempt_started),
+                                failures,
+                                timed_out,
+                            })
+                            .unwrap();
+                        }
+                    }
+                }));
 
-                let mut test_task = std::pin::pin!(CatchUnwind(task));
-
-                tx.send(TestState::Start {}).unwrap();
+                tx.send(TestState::Start { info: info.clone() }).unwrap();
                 for i in 1.. {
-                    let res = tokio::time::timeout(slow_period, test_task.as_mut()).await;
+                    let res = match slow_period {
+                        Some(period) => tokio::select! {
+                            // A test that finishes just as the period ends is
+                            // not slow.
+                            biased;
+                            result = test_task.as_mut() => Ok(result),
+                            () = suspend.sleep_active(&*clock, started, i * period) => Err(period),
+                        },
+                        None => Ok(test_task.as_mut().await),
+                    };
                     match res {
-                        Err(_) => {
+                        Err(period) => {
                             tx.send(TestState::Tick {
-                                elapsed: i * slow_period,
+                                elapsed: i * period,
                                 info: info.clone(),
                             })
                             .unwrap();
                         }
-                        Ok(outcome) => {
+                        Ok(((outcome, iterations, determinism, timed_out, attempts), meta)) => {
+                            // Set before the permit is released, so that the
+                            // next test in line doesn't start. The reporter
+                            // also sets it for failures that only it sees,
+                            // e.g. `--thresholds`.
+                            if fail_fast
+                                && matches!(outcome, Outcome::Failed(_)) != expected_failure
+                            {
+                                failed_fast.store(true, Ordering::Release);
+                            }
                             tx.send(TestState::Done {
                                 start,
+                                duration: suspend.active_since(&*clock, started),
+                                suspended: suspend.total().saturating_sub(started.1),
+                                queued,
+                                setup_wait,
+                                meta,
                                 outcome,
+                                timed_out,
+                                attempts,
+                                iterations,
+                                determinism,
                                 info,
                                 slow: i > 1,
                             })
@@ -660,218 +1990,822 @@ fn run_nextest(
                     }
                 }
             };
-            runtime.spawn(test_task);
+            if test.info.main_thread {
+                main_thread_tests.push(Box::pin(test_task));
+            } else {
+                handles.push(runtime.spawn(test_task));
+            }
         }
     }
 
+    // Forwards the requests of the handle to the reporter until the tests are
+    // done.
+    let control_tx = tx.clone();
+    let watch_control = control::watch(handle.subscribe(), move |control| {
+        _ = control_tx.send(TestState::Control(control));
+    });
+    // Completes once the tests that were running at a signal had a moment to
+    // finish, or at a second signal. Never completes without a signal.
+    let interrupt_tx = tx.clone();
+    let interrupt = {
+        let interrupted = interrupted.clone();
+        let (signals, received) = (&mut signals, &mut received);
+        async move {
+            let signal = signals.recv().await;
+            *received = Some(signal);
+            interrupted.store(true, Ordering::Release);
+            _ = interrupt_tx.send(TestState::Signaled(signal));
+            tokio::select! {
+                () = tokio::time::sleep(SIGNAL_GRACE) => {}
+                _ = signals.recv() => {}
+            }
+            _ = interrupt_tx.send(TestState::Abandoned);
+        }
+    };
     drop(tx);
 
     let mut output = args
         .logfile
         .as_deref()
         .map(|f| std::fs::File::create(f).unwrap());
-    let report_output = match &mut output {
-        Some(file) => ReporterOutput::Buffer(file),
-        None => ReporterOutput::Stderr,
+    let (custom, observer) = match listener {
+        Some(Listener::Reporter(reporter)) => (Some(reporter), None),
+        Some(Listener::Observer(observer)) => (None, Some(observer)),
+        None => (None, None),
+    };
+    let report_output = match (custom, &mut output) {
+        (Some(custom), _) => ReporterOutput::Custom(custom),
+        (None, Some(file)) => ReporterOutput::Buffer(file),
+        (None, None) => ReporterOutput::Stderr,
     };
 
     let mut reporter = TestReporterBuilder::default()
         .set_imitate_cargo(args.exact)
+        .set_stall_timeout(config.stall_timeout)
+        .set_json(config.json)
+        .set_json_pretty(config.json_pretty)
+        .set_summary_line(config.summary_line)
+        .set_name_width(config.name_width)
+        .set_junit_path(args.junit_path.as_ref().map(Into::into))
         .build(&test_list, report_output);
 
-    match args.color.unwrap_or(ColorSetting::Auto) {
-        ColorSetting::Auto => match args.logfile.is_some() {
-            true => {}
-            false => {
-                if supports_color::on(supports_color::Stream::Stderr).map_or(false, |x| x.has_basic)
-                {
-                    reporter.colorize();
-                }
-            }
-        },
-        ColorSetting::Always => reporter.colorize(),
-        ColorSetting::Never => {}
+    if config.colorize {
+        reporter.colorize();
+    }
+    if let Some(observer) = observer {
+        reporter.add_observer("the observer", observer);
+    }
+    if let Some(setting) = args.log_events {
+        let system_log = SystemLog::connect(setting, args.log_events_socket.as_deref());
+        reporter.add_observer("the system log", Box::new(system_log));
+    }
+    #[cfg(feature = "notify-webhook")]
+    if let Some(url) = &args.notify_webhook {
+        let webhook = webhook::Webhook::new(url.clone());
+        reporter.add_observer("the webhook", Box::new(webhook));
+    }
+    if let Some(path) = &args.run_log {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap_or_else(|e| {
+                eprintln!("error: failed to open run log {path}: {e}");
+                process::exit(1)
+            });
+        reporter.set_run_log(file);
+    }
+    if let Some(path) = &args.checkpoint {
+        reporter.set_checkpoint(Checkpoint::new(
+            path.into(),
+            args.checkpoint_every.unwrap_or(100),
+            args.checkpoint_interval.unwrap_or(Duration::from_secs(60)),
+        ));
+    }
+    if let Some(path) = &args.metrics_file {
+        reporter.set_metrics(Metrics::new(path.into()));
+    }
+    if let Some(path) = &args.html_report {
+        reporter.set_html_report(HtmlReport::new(path.into()));
+    }
+    if let Some(path) = &args.trace_timeline {
+        reporter.set_timeline(Timeline::new(path.into()));
+    }
+    if let Some(path) = &args.merge_into {
+        reporter.set_merge_into(MergeInto::new(path.into()));
     }
 
-    reporter
-        .report_event(TestEvent::RunStarted {
-            test_list: &test_list,
-        })
-        .unwrap();
-
-    let mut running = 0;
-    runtime.block_on(async {
-        loop {
-            let msg = rx.recv().await;
+    let (test_list, config, diagnostics) = (&test_list, &config, &*diagnostics);
+    let stats = std::thread::scope(|scope| {
+        let reporter_thread = std::thread::Builder::new()
+            .name("async-test-reporter".to_owned())
+            .spawn_scoped(scope, move || {
+                reporter
+                    .report_event(TestEvent::RunStarted {
+                        test_list,
+                        config,
+                        diagnostics,
+                    })
+                    .unwrap();
 
-            match msg {
-                Some(TestState::Skipped { name, reason }) => {
+                for CollectionError { builder, message } in errors {
                     reporter
-                        .report_event(TestEvent::TestSkipped {
-                            test_instance: TestInstance { name },
-                            reason,
-                        })
+                        .report_event(TestEvent::CollectionError { builder, message })
                         .unwrap();
                 }
-                Some(TestState::StartSetup {}) => {}
-                Some(TestState::DoneSetup { name, start }) => {
-                    reporter
-                        .report_event(TestEvent::SetupFinished {
-                            test_instance: TestInstance { name },
-                            duration: start.elapsed().unwrap(),
-                            current_stats: stats,
-                            running,
-                        })
-                        .unwrap();
-                }
-                Some(TestState::Start {}) => {
-                    running += 1;
-                    reporter
-                        .report_event(TestEvent::TestStarted {
-                            current_stats: stats,
-                            running,
-                            cancel_state: None,
-                        })
-                        .unwrap()
-                }
-                Some(TestState::Tick { elapsed, info }) => reporter
-                    .report_event(TestEvent::TestSlow {
-                        test_instance: TestInstance { name: info.name },
-                        elapsed,
-                        will_terminate: false,
-                    })
-                    .unwrap(),
-                Some(TestState::Done {
-                    start,
-                    outcome,
-                    info,
-                    slow,
-                }) => {
-                    running -= 1;
-                    let status = match outcome {
-                        Outcome::Passed => {
-                            stats.passed += 1;
-                            stats.passed_slow += slow as usize;
-                            stats.finished_count += 1;
-                            ExecuteStatus {
-                                output: None,
-                                result: nextest::ExecutionResult::Pass,
-                                start_time: start,
-                                time_taken: start.elapsed().unwrap(),
-                                is_slow: slow,
-                                delay_before_start: Duration::ZERO,
+
+                let mut running = 0;
+                let mut cancel_state = None;
+                // The signal that interrupted the run, if one did.
+                let mut signal = None;
+                // The tests that finished or were not run, so that those that
+                // were abandoned after a signal can be told apart.
+                let mut accounted = HashSet::new();
+                let mut slow_tests =
+                    SlowBatcher::new(config.slow_report_interval, config.clock.clone());
+                let mut next_summary = config.summary_interval.map(|interval| run_start + interval);
+                loop {
+                    let now = config.clock.now();
+                    if next_summary.is_some_and(|summary| summary <= now) {
+                        reporter
+                            .report_event(TestEvent::RunProgress {
+                                elapsed: now - run_start,
+                                run_stats: stats,
+                            })
+                            .unwrap();
+                        next_summary = config.summary_interval.map(|interval| now + interval);
+                    }
+
+                    // Wake up for pending slow tests and interim summaries
+                    // even when nothing else is happening.
+                    let deadline = match (slow_tests.deadline(), next_summary) {
+                        (Some(slow), Some(summary)) => Some(slow.min(summary)),
+                        (slow, summary) => slow.or(summary),
+                    };
+                    let msg = match deadline {
+                        Some(deadline) => {
+                            match rx.recv_timeout(
+                                deadline.saturating_duration_since(config.clock.now()),
+                            ) {
+                                Ok(msg) => Some(msg),
+                                Err(RecvTimeoutError::Timeout) => None,
+                                Err(RecvTimeoutError::Disconnected) => break,
+                            }
+                        }
+                        None => match rx.recv() {
+                            Ok(msg) => Some(msg),
+                            Err(_) => break,
+                        },
+                    };
+                    if let Some(event) = slow_tests.poll() {
+                        reporter.report_event(event).unwrap();
+                    }
+                    let Some(msg) = msg else { continue };
+
+                    match msg {
+                        TestState::Skipped {
+                            name,
+                            reason,
+                            message,
+                        } => {
+                            if let Some(compare) = &mut compare {
+                                compare.skipped(&name);
+                            }
+                            reporter
+                                .report_event(TestEvent::TestSkipped {
+                                    test_instance: TestInstance {
+                                        name,
+                                        description: None,
+                                        links: vec![],
+                                        expected_failure: None,
+                                    },
+                                    reason,
+                                    message,
+                                })
+                                .unwrap();
+                        }
+                        TestState::StartSetup {} => {}
+                        TestState::ConcurrencyReduced(reduction) => reporter
+                            .report_event(TestEvent::ConcurrencyReduced { reduction })
+                            .unwrap(),
+                        TestState::DoneSetup {
+                            name,
+                            start,
+                            duration,
+                        } => {
+                            reporter
+                                .report_event(TestEvent::SetupFinished {
+                                    test_instance: TestInstance {
+                                        name,
+                                        description: None,
+                                        links: vec![],
+                                        expected_failure: None,
+                                    },
+                                    start_time: start,
+                                    duration,
+                                    current_stats: stats,
+                                    running,
+                                })
+                                .unwrap();
+                        }
+                        TestState::Start { info } => {
+                            running += 1;
+                            reporter
+                                .report_event(TestEvent::TestStarted {
+                                    test_instance: TestInstance {
+                                        name: info.name,
+                                        description: info.description,
+                                        links: info.links,
+                                        expected_failure: info.expected_failure,
+                                    },
+                                    current_stats: stats,
+                                    running,
+                                    cancel_state,
+                                })
+                                .unwrap()
+                        }
+                        TestState::NotRun { info, reason } => {
+                            stats.not_run += 1;
+                            // The test may notice the signal before the
+                            // reporter does.
+                            stats.interrupted |= reason == MismatchReason::Interrupted;
+                            accounted.insert(info.name.clone());
+                            if let Some(compare) = &mut compare {
+                                compare.skipped(&info.name);
                             }
+                            reporter
+                                .report_event(TestEvent::TestSkipped {
+                                    test_instance: TestInstance {
+                                        name: info.name,
+                                        description: info.description,
+                                        links: info.links,
+                                        expected_failure: info.expected_failure,
+                                    },
+                                    reason,
+                                    message: Some(not_run_message(reason, signal)),
+                                })
+                                .unwrap();
+                        }
+                        TestState::Retry {
+                            info,
+                            attempt,
+                            duration,
+                            failures,
+                            timed_out,
+                        } => reporter
+                            .report_event(TestEvent::TestAttemptFailedWillRetry {
+                                test_instance: TestInstance {
+                                    name: info.name,
+                                    description: info.description,
+                                    links: info.links,
+                                    expected_failure: info.expected_failure,
+                                },
+                                run_status: ExecuteStatus {
+                                    output: Some(failure_output(&failures)),
+                                    failures,
+                                    result: match timed_out {
+                                        true => nextest::ExecutionResult::Timeout,
+                                        false => nextest::ExecutionResult::Fail,
+                                    },
+                                    start_time: SystemTime::now() - duration,
+                                    time_taken: duration,
+                                    is_slow: false,
+                                    delay_before_start: Duration::ZERO,
+                                    iterations: None,
+                                    determinism: None,
+                                    attempts: attempt,
+                                    suspended: None,
+                                    queued: Duration::ZERO,
+                                    setup_wait: Duration::ZERO,
+                                    expected_duration: None,
+                                    meta: BTreeMap::new(),
+                                },
+                            })
+                            .unwrap(),
+                        TestState::Terminating { info, elapsed } => reporter
+                            .report_event(TestEvent::TestSlow {
+                                test_instance: TestInstance {
+                                    name: info.name,
+                                    description: info.description,
+                                    links: info.links,
+                                    expected_failure: info.expected_failure,
+                                },
+                                elapsed,
+                                will_terminate: true,
+                            })
+                            .unwrap(),
+                        TestState::Tick { elapsed, info } => slow_tests.tick(info.name, elapsed),
+                        TestState::Control(Control::Paused) => reporter
+                            .report_event(TestEvent::RunPaused {
+                                running,
+                                reason: CancelReason::Requested,
+                            })
+                            .unwrap(),
+                        TestState::Control(Control::Running) => reporter
+                            .report_event(TestEvent::RunContinued {
+                                running,
+                                reason: CancelReason::Requested,
+                            })
+                            .unwrap(),
+                        TestState::RunTimedOut { after } => {
+                            stats.run_timed_out = true;
+                            cancel_state = Some(CancelReason::RunTimeout);
+                            reporter
+                                .report_event(TestEvent::RunBeginCancel {
+                                    running,
+                                    reason: CancelReason::RunTimeout,
+                                    message: Some(format!("the run timed out after {after:?}")),
+                                })
+                                .unwrap()
+                        }
+                        TestState::Signaled(received) => {
+                            let reason = received.cancel_reason();
+                            signal = Some(received);
+                            stats.interrupted = true;
+                            cancel_state = Some(reason);
+                            reporter
+                                .report_event(TestEvent::RunBeginCancel {
+                                    running,
+                                    reason,
+                                    message: match received {
+                                        Signal::Interrupt => None,
+                                        signal => Some(signal.name().to_owned()),
+                                    },
+                                })
+                                .unwrap()
+                        }
+                        // Stuck tests would keep the channel open forever.
+                        TestState::Abandoned => break,
+                        TestState::Control(Control::Canceled(message)) => {
+                            stats.canceled = true;
+                            cancel_state = Some(CancelReason::Requested);
+                            reporter
+                                .report_event(TestEvent::RunBeginCancel {
+                                    running,
+                                    reason: CancelReason::Requested,
+                                    message: Some(message),
+                                })
+                                .unwrap()
                         }
-                        Outcome::Failed(failed) => {
-                            stats.failed += 1;
-                            stats.failed_slow += slow as usize;
+                        TestState::Done {
+                            start,
+                            duration,
+                            suspended,
+                            queued,
+                            setup_wait,
+                            meta,
+                            outcome,
+                            timed_out,
+                            attempts,
+                            iterations,
+                            determinism,
+                            info,
+                            slow,
+                        } => {
+                            running -= 1;
+                            accounted.insert(info.name.clone());
+                            slow_tests.finish(&info.name);
+                            let mut breaches = config.thresholds.check(&meta);
+                            breaches.extend(
+                                config
+                                    .report_time
+                                    .and_then(|report_time| report_time.check(duration)),
+                            );
+                            let outcome = match outcome {
+                                _ if breaches.is_empty() => outcome,
+                                Outcome::Passed => Outcome::Failed(breaches),
+                                Outcome::Failed(mut failures) => {
+                                    failures.extend(breaches);
+                                    Outcome::Failed(failures)
+                                }
+                            };
+                            let (result, failures) = match (outcome, &info.expected_failure) {
+                                (Outcome::Failed(failures), _) if timed_out => {
+                                    (nextest::ExecutionResult::Timeout, failures)
+                                }
+                                (Outcome::Passed, None) => (nextest::ExecutionResult::Pass, vec![]),
+                                (Outcome::Failed(failures), None) => {
+                                    (nextest::ExecutionResult::Fail, failures)
+                                }
+                                // The failure is kept for reports, even
+                                // though it is not shown.
+                                (Outcome::Failed(failures), Some(_)) => {
+                                    (nextest::ExecutionResult::ExpectedFail, failures)
+                                }
+                                (Outcome::Passed, Some(reason)) => (
+                                    nextest::ExecutionResult::UnexpectedPass,
+                                    vec![report::Failure {
+                                        message: format!(
+                                            "test passed, but was expected to fail: {reason}"
+                                        ),
+                                        location: None,
+                                    }],
+                                ),
+                            };
                             stats.finished_count += 1;
-                            ExecuteStatus {
-                                output: Some(failed),
-                                result: nextest::ExecutionResult::Fail,
+                            stats.queue_wait += queued;
+                            durations.push(duration);
+                            if result.is_success() {
+                                stats.passed += 1;
+                                stats.passed_slow += slow as usize;
+                                stats.expected_failures +=
+                                    (result == nextest::ExecutionResult::ExpectedFail) as usize;
+                                stats.flaky += (attempts > 1) as usize;
+                            } else if result == nextest::ExecutionResult::Timeout {
+                                stats.timed_out += 1;
+                            } else {
+                                stats.failed += 1;
+                                stats.failed_slow += slow as usize;
+                            }
+                            let status = ExecuteStatus {
+                                output: (!failures.is_empty()).then(|| failure_output(&failures)),
+                                failures,
+                                result,
                                 start_time: start,
-                                time_taken: start.elapsed().unwrap(),
+                                time_taken: duration,
                                 is_slow: slow,
                                 delay_before_start: Duration::ZERO,
+                                iterations,
+                                determinism,
+                                attempts,
+                                suspended: (!suspended.is_zero()).then_some(suspended),
+                                queued,
+                                setup_wait,
+                                expected_duration: info.expected_duration,
+                                meta,
+                            };
+                            // Only the first failure cancels the run.
+                            let fail_fast = (config.fail_fast
+                                && !status.result.is_success()
+                                && cancel_state.is_none())
+                            .then(|| info.name.clone());
+                            if let Some(compare) = &mut compare {
+                                compare.finished(
+                                    &info.name,
+                                    status.result.is_success(),
+                                    slow,
+                                    status.time_taken,
+                                );
+                            }
+                            reporter
+                                .report_event(TestEvent::TestFinished {
+                                    test_instance: TestInstance {
+                                        name: info.name,
+                                        description: info.description,
+                                        links: info.links,
+                                        expected_failure: info.expected_failure,
+                                    },
+                                    success_output: nextest::reporter::TestOutputDisplay::Never,
+                                    failure_output: nextest::reporter::TestOutputDisplay::Immediate,
+                                    junit_store_success_output: false,
+                                    junit_store_failure_output: false,
+                                    run_status: status,
+                                    current_stats: stats,
+                                    running,
+                                    cancel_state,
+                                })
+                                .unwrap();
+                            if let Some(name) = fail_fast {
+                                failed_fast.store(true, Ordering::Release);
+                                stats.failed_fast = true;
+                                cancel_state = Some(CancelReason::TestFailure);
+                                reporter
+                                    .report_event(TestEvent::RunBeginCancel {
+                                        running,
+                                        reason: CancelReason::TestFailure,
+                                        message: Some(format!("{name} failed")),
+                                    })
+                                    .unwrap();
                             }
                         }
-                    };
+                    }
+                }
+
+                // Tests that were still running or waiting when the run was
+                // abandoned after a signal are not run, like those that
+                // noticed the signal before they started.
+                let abandoned = test_list
+                    .tests
+                    .iter()
+                    .filter(|info| stats.interrupted && !accounted.contains(&info.name));
+                for info in abandoned {
+                    stats.not_run += 1;
+                    if let Some(compare) = &mut compare {
+                        compare.skipped(&info.name);
+                    }
                     reporter
-                        .report_event(TestEvent::TestFinished {
-                            test_instance: TestInstance { name: info.name },
-                            success_output: nextest::reporter::TestOutputDisplay::Never,
-                            failure_output: nextest::reporter::TestOutputDisplay::Immediate,
-                            junit_store_success_output: false,
-                            junit_store_failure_output: false,
-                            run_status: status,
-                            current_stats: stats,
-                            running,
-                            cancel_state: None,
+                        .report_event(TestEvent::TestSkipped {
+                            test_instance: TestInstance {
+                                name: info.name.clone(),
+                                description: info.description.clone(),
+                                links: info.links.clone(),
+                                expected_failure: info.expected_failure.clone(),
+                            },
+                            reason: MismatchReason::Interrupted,
+                            message: Some(not_run_message(MismatchReason::Interrupted, signal)),
                         })
                         .unwrap();
                 }
-                None => break,
+
+                if let Some(compare) = compare {
+                    reporter
+                        .report_event(TestEvent::RunCompared {
+                            comparison: compare.finish(),
+                        })
+                        .unwrap();
+                }
+
+                let fixtures = context.fixture_usage();
+                if !fixtures.is_empty() {
+                    reporter
+                        .report_event(TestEvent::FixturesUsed { fixtures })
+                        .unwrap();
+                }
+
+                reporter
+                    .report_event(TestEvent::RunFinished {
+                        start_time,
+                        elapsed: config.clock.now() - run_start,
+                        run_stats: stats,
+                        durations: durations::summarize(durations),
+                    })
+                    .unwrap();
+
+                stats
+            })
+            .expect("failed to spawn reporter thread");
+
+        // The reporter is done once every sender is dropped, which happens
+        // when all setups and tests have finished.
+        // Futures that are not spawned run on the thread calling `block_on`,
+        // so main-thread tests are driven here, one at a time.
+        runtime.block_on(async {
+            let main_thread = async {
+                for test in main_thread_tests {
+                    test.await;
+                }
+            };
+            let spawned = async {
+                for handle in handles {
+                    _ = handle.await;
+                }
+            };
+            // The handle is polled first, so that a request made by the last
+            // test is still forwarded.
+            tokio::select! {
+                biased;
+                () = watch_control => unreachable!("watching the handle never completes"),
+                () = interrupt => {}
+                _ = async { tokio::join!(main_thread, spawned) } => {}
             }
-        }
-    });
+        });
 
-    std::panic::set_hook(hook);
+        reporter_thread.join().unwrap()
+    });
+    if received.is_some() {
+        // Tests that are stuck would block dropping the runtime.
+        runtime.shutdown_background();
+    }
 
-    reporter
-        .report_event(TestEvent::RunFinished {
-            start_time: start_instant,
-            elapsed: start_instant.elapsed().unwrap(),
-            run_stats: stats,
-        })
-        .unwrap();
+    // Nobody is there to look at the report in CI.
+    if let (Some(path), true, false) = (&args.html_report, args.open_report, config.ci) {
+        open_html_report(Path::new(path));
+    }
 
     Conclusion {
         num_filtered_out: stats.skipped,
         num_passed: stats.passed,
-        num_failed: stats.failed,
+        num_failed: stats.failed + stats.timed_out,
+        num_flaky: stats.flaky,
+        num_collection_errors: stats.collection_errors,
+        run_timed_out: stats.run_timed_out,
+        canceled: stats.canceled,
+        signal: received,
     }
 }
 
-struct CatchUnwind(Pin<Box<dyn Future<Output = ()> + Send>>);
+/// Turns a panic of the future into a failure, or, if it should panic, the
+/// lack of one.
+struct CatchUnwind(Pin<Box<dyn Future<Output = ()> + Send>>, ShouldPanic);
 impl Future for CatchUnwind {
     type Output = Outcome;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
-        use std::panic::{catch_unwind, AssertUnwindSafe};
-
         let res = catch_unwind(AssertUnwindSafe(|| self.0.as_mut().poll(cx)));
 
-        match res {
-            Err(e) => {
-                // The `panic` information is just an `Any` object representing the
-                // value the panic was invoked with. For most panics (which use
-                // `panic!` like `println!`), this is either `&str` or `String`.
-                let payload = e
-                    .downcast_ref::<String>()
-                    .map(|s| s.as_str())
-                    .or(e.downcast_ref::<&str>().copied());
-
-                let msg = payload.unwrap_or("test panicked");
-
-                let (bt, location) = BT.with(|x| x.replace((Backtrace::disabled(), None)));
-                // dbg!(location);
-
-                let mut final_msg = format!("thread 'main' panicked at '{msg}'");
-                if let Some(Location { file, line, column }) = location {
-                    final_msg += &format!(", {file}:{line}:{column}");
-                }
-                if bt.status() == BacktraceStatus::Captured {
-                    let bt = bt.to_string();
-                    if let Some(unwind) = bt.find("rust_begin_unwind") {
-                        if let Some(catch) = bt[unwind..].find("async_test::CatchUnwind") {
-                            let unwind_start = bt[..unwind].rfind('\n').unwrap_or(0);
-                            let catch_start = bt[..unwind + catch].rfind('\n').unwrap();
-                            final_msg += &format!(
-                                "\nstack backtrace:\n{}",
-                                bt[unwind_start..catch_start].trim_start_matches('\n')
-                            );
-                        }
+        match (res, &self.1) {
+            (Err(e), should_panic) => {
+                let msg = payload::message(&*e).unwrap_or("test panicked".into());
+                let failure = describe_panic(&msg, "async_test::CatchUnwind");
+                Poll::Ready(match should_panic {
+                    ShouldPanic::No => Outcome::Failed(vec![failure]),
+                    ShouldPanic::Yes => Outcome::Passed,
+                    ShouldPanic::YesWithMessage(expected) if msg.contains(expected.as_str()) => {
+                        Outcome::Passed
                     }
-                }
+                    ShouldPanic::YesWithMessage(expected) => {
+                        Outcome::Failed(vec![report::Failure {
+                            message: format!(
+                                "panic did not contain expected string\n      panic message: \
+                            `{msg:?}`,\n expected substring: `{expected:?}`"
+                            ),
+                            location: failure.location,
+                        }])
+                    }
+                })
+            }
+            (Ok(Poll::Ready(())), ShouldPanic::No) => Poll::Ready(Outcome::Passed),
+            (Ok(Poll::Ready(())), _) => Poll::Ready(Outcome::Failed(vec![report::Failure {
+                message: "test did not panic as expected".to_owned(),
+                location: None,
+            }])),
+            (Ok(Poll::Pending), _) => Poll::Pending,
+        }
+    }
+}
 
-                Poll::Ready(Outcome::Failed(final_msg))
+/// Formats the panic that was last recorded on this thread.
+///
+/// The backtrace is cut off at `catch_frame`, the function that caught the
+/// panic, so that only frames belonging to the panicking code are shown.
+fn describe_panic(msg: &str, catch_frame: &str) -> report::Failure {
+    let (bt, location) = BT.with(|x| x.replace((Backtrace::disabled(), None)));
+    let location =
+        location.map(|Location { file, line, column }| format!("{file}:{line}:{column}"));
+
+    let mut final_msg = format!("thread 'main' panicked at '{msg}'");
+    if let Some(location) = &location {
+        final_msg += &format!(", {location}");
+    }
+    if bt.status() == BacktraceStatus::Captured {
+        let bt = bt.to_string();
+        if let Some(unwind) = bt.find("rust_begin_unwind") {
+            if let Some(catch) = bt[unwind..].find(catch_frame) {
+                let unwind_start = bt[..unwind].rfind('\n').unwrap_or(0);
+                let catch_start = bt[..unwind + catch].rfind('\n').unwrap();
+                final_msg += &format!(
+                    "\nstack backtrace:\n{}",
+                    bt[unwind_start..catch_start].trim_start_matches('\n')
+                );
             }
-            Ok(Poll::Ready(())) => Poll::Ready(Outcome::Passed),
-            Ok(Poll::Pending) => Poll::Pending,
         }
     }
+    report::Failure {
+        message: final_msg,
+        location,
+    }
+}
+
+/// Why a test that did not start, for `reason`, was not run.
+fn not_run_message(reason: MismatchReason, signal: Option<Signal>) -> String {
+    match (reason, signal) {
+        (MismatchReason::FailFast, _) => "a test failed with --fail-fast".to_owned(),
+        (MismatchReason::RunTimeout, _) => "the run timed out".to_owned(),
+        (MismatchReason::Canceled, _) => "the run was canceled".to_owned(),
+        (MismatchReason::Interrupted, None | Some(Signal::Interrupt)) => {
+            "the run was interrupted".to_owned()
+        }
+        (MismatchReason::Interrupted, Some(signal)) => {
+            format!("the run was interrupted by {}", signal.name())
+        }
+        (MismatchReason::Budget, _) => "time budget exceeded".to_owned(),
+        // Decided before a test is started, never sent as `NotRun`.
+        (
+            MismatchReason::Ignored
+            | MismatchReason::String
+            | MismatchReason::Expression
+            | MismatchReason::Tag
+            | MismatchReason::Cfg
+            | MismatchReason::Unavailable
+            | MismatchReason::Offline
+            | MismatchReason::Partition,
+            _,
+        ) => unreachable!("{reason:?} is not a reason to stop a run"),
+    }
 }
 
+/// The failure output of a test, with every failure numbered if there are
+/// several.
+fn failure_output(failures: &[report::Failure]) -> String {
+    match failures {
+        [failure] => failure.message.clone(),
+        failures => failures
+            .iter()
+            .enumerate()
+            .map(|(i, failure)| format!("[{}/{}] {}", i + 1, failures.len(), failure.message))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// Registers an async function as a test.
+///
+/// The function may take references to values created by [`setup!`]
+/// functions. The following attributes are understood in addition to the
+/// usual ones:
+///
+/// - `#[tags(a, b)]`: see [`Trial::with_tags`]
+/// - `#[required_cfg(predicate)]`: registers the test as skipped unless
+///   `cfg!(predicate)` holds, see [`Trial::with_required_cfg`]
+/// - `#[requires_network]` and `#[requires_docker]`: see
+///   [`Trial::with_requires_network`] and [`Trial::with_requires_docker`]
+/// - `#[main_thread]`: see [`Trial::with_main_thread`]
+/// - `#[should_panic]` and `#[should_panic(expected = "message")]`: see
+///   [`Trial::with_should_panic`] and [`Trial::with_should_panic_expected`]
+/// - `#[ignore]` and `#[ignore = "reason"]`: see [`Trial::with_ignored_flag`]
+///   and [`Trial::with_ignore_reason`]
+/// - `#[ignore_if(env = "VAR")]`: ignores the test if the environment
+///   variable `VAR` is set when tests are collected, e.g. `CI`
+///
+/// The doc comment of the function becomes the description of the test, see
+/// [`Trial::with_description`]. A function that returns a `Result` is
+/// registered with [`Trial::fallible`].
+///
+/// A leading `suite = "name",` puts the test in a suite, which is only run
+/// with `--suite name` (see [`Arguments::suite`]). This keeps tests that a
+/// library registers out of the other test binaries it is linked into.
+/// [`tests!`], [`async_tests!`] and [`setup!`] take a suite the same way.
+///
+/// ```no_run
+/// async_test::test!(
+///     suite = "database",
+///     async fn migrations_apply() {}
+/// );
+/// ```
 #[macro_export]
 macro_rules! test {
-    ($vis:vis async fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) $body:block) => {
-        $vis async fn $name($($arg: $arg_ty),*) {
+    (suite = $suite:literal, $($tokens:tt)*) => {
+        $crate::__test!([::core::option::Option::Some($suite)] [] [] [] $($tokens)*);
+    };
+    ($($tokens:tt)*) => {
+        $crate::__test!([::core::option::Option::None] [] [] [] $($tokens)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __test {
+    // Our own attributes are turned into `Trial` builder calls...
+    // A test that returns something is fallible.
+    (@constructor) => { $crate::Trial::test };
+    (@constructor $ret:ty) => { $crate::Trial::fallible };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[tags($($tag:ident),+ $(,)?)] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_tags([$(stringify!($tag)),+])] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[required_cfg($($cfg:tt)+)] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_required_cfg(cfg!($($cfg)+), stringify!($($cfg)+))] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[requires_network] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_requires_network()] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[requires_docker] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_requires_docker()] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[main_thread] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_main_thread(true)] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[should_panic] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_should_panic()] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[should_panic(expected = $expected:literal)] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_should_panic_expected($expected)] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[ignore] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_ignored_flag(true)] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[ignore = $reason:literal] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .with_ignore_reason($reason)] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[ignore_if(env = $var:literal)] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)*] [$($with)* .__ignore_if_env($var)] [$($doc)*] $($rest)*);
+    };
+    // Doc comments are kept, and also collected into the description...
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[doc = $line:literal] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)* #[doc = $line]] [$($with)*] [$($doc)* $line,] $($rest)*);
+    };
+    // ...and all others are kept on the function.
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] #[$meta:meta] $($rest:tt)*) => {
+        $crate::__test!([$suite] [$($attr)* #[$meta]] [$($with)*] [$($doc)*] $($rest)*);
+    };
+    ([$suite:expr] [$($attr:tt)*] [$($with:tt)*] [$($doc:tt)*] $vis:vis async fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) $(-> $ret:ty)? $body:block) => {
+        $($attr)*
+        $vis async fn $name($($arg: $arg_ty),*) $(-> $ret)? {
             {
                 // $($crate::__sus::has_setup_fn::<_, $arg_ty>();)*
                 $crate::__sus::inventory::submit! {
-                    $crate::__sus::TestBuilder(
-                        |tester: $crate::Tester| tester.add($crate::Trial::test(stringify!($name), $name))
-                    )
+                    $crate::__sus::TestBuilder {
+                        module: $crate::__sus::module_path!(),
+                        function: stringify!($name),
+                        suite: $suite,
+                        build: $crate::__sus::Build::Sync(
+                            |tester: $crate::Tester| {
+                                tester.add($crate::__sus::with_doc(
+                                    $crate::__test!(@constructor $($ret)?)(stringify!($name), $name)$($with)*,
+                                    &[$($doc)*],
+                                ));
+                            }
+                        ),
+                    }
                 }
             }
             {
@@ -881,12 +2815,99 @@ macro_rules! test {
     };
 }
 
+/// Checks that a condition holds without stopping the test.
+///
+/// Unlike `assert!`, a failed check only records its message and location.
+/// The test keeps running, and fails once it completes with every failed
+/// check listed, so that a single run shows all the broken expectations:
+///
+/// ```no_run
+/// # struct User { name: String, email: String }
+/// async_test::test!(
+///     async fn valid_user() {
+///         let user = User { name: String::new(), email: "a@b".to_owned() };
+///         async_test::check!(!user.name.is_empty(), "name is empty");
+///         async_test::check!(user.email.contains('.'), "bad email {}", user.email);
+///     }
+/// );
+/// ```
+///
+/// Without a message, the condition itself is used. Outside of a test,
+/// `check!` panics like `assert!`.
+#[macro_export]
+macro_rules! check {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::__sus::record_check(::std::string::String::from(stringify!($cond)));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::__sus::record_check(::std::format!($($arg)+));
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! tests {
-    ($(#[$meta:meta])* $vis:vis fn $name:ident($tester:ident: $tester_ty:ty) $body:block) => {
+    (suite = $suite:literal, $($tokens:tt)*) => {
+        $crate::__tests!(::core::option::Option::Some($suite), $($tokens)*);
+    };
+    ($($tokens:tt)*) => {
+        $crate::__tests!(::core::option::Option::None, $($tokens)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tests {
+    ($suite:expr, $(#[$meta:meta])* $vis:vis fn $name:ident($tester:ident: $tester_ty:ty) $body:block) => {
         $(#[$meta])* $vis fn $name($tester: $tester_ty) {
             {
-                $crate::__sus::inventory::submit! { $crate::__sus::TestBuilder($name) }
+                $crate::__sus::inventory::submit! {
+                    $crate::__sus::TestBuilder {
+                        module: $crate::__sus::module_path!(),
+                        function: stringify!($name),
+                        suite: $suite,
+                        build: $crate::__sus::Build::Sync($name),
+                    }
+                }
+            }
+            {
+                $body
+            }
+        }
+    };
+}
+
+/// Like [`tests!`], but the function is async so that collecting tests can
+/// await I/O, such as listing remote fixtures.
+///
+/// The function runs on the harness runtime before any test is started.
+#[macro_export]
+macro_rules! async_tests {
+    (suite = $suite:literal, $($tokens:tt)*) => {
+        $crate::__async_tests!(::core::option::Option::Some($suite), $($tokens)*);
+    };
+    ($($tokens:tt)*) => {
+        $crate::__async_tests!(::core::option::Option::None, $($tokens)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __async_tests {
+    ($suite:expr, $(#[$meta:meta])* $vis:vis async fn $name:ident($tester:ident: $tester_ty:ty) $body:block) => {
+        $(#[$meta])* $vis async fn $name($tester: $tester_ty) {
+            {
+                $crate::__sus::inventory::submit! {
+                    $crate::__sus::TestBuilder {
+                        module: $crate::__sus::module_path!(),
+                        function: stringify!($name),
+                        suite: $suite,
+                        build: $crate::__sus::Build::Async(|tester| ::std::boxed::Box::pin($name(tester))),
+                    }
+                }
             }
             {
                 $body
@@ -897,7 +2918,18 @@ macro_rules! tests {
 
 #[macro_export]
 macro_rules! setup {
-    ($(#[$meta:meta])* $vis:vis async fn $name:ident() -> $setup:ty $body:block) => {
+    (suite = $suite:literal, $($tokens:tt)*) => {
+        $crate::__setup!(::core::option::Option::Some($suite), $($tokens)*);
+    };
+    ($($tokens:tt)*) => {
+        $crate::__setup!(::core::option::Option::None, $($tokens)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __setup {
+    ($suite:expr, $(#[$meta:meta])* $vis:vis async fn $name:ident() -> $setup:ty $body:block) => {
         #[doc(hidden)]
         #[allow(non_camel_case_types)]
         $vis struct $name {}
@@ -914,10 +2946,11 @@ macro_rules! setup {
                         type_id: $crate::__sus::TypeId::of::<$setup>,
                         module: $crate::__sus::module_path!(),
                         function: stringify!($name),
-                        setup: || $crate::__sus::spawn(async {
+                        suite: $suite,
+                        setup: || $crate::__sus::spawn($crate::__sus::in_setup(async {
                             let x: $setup = $name().await;
                             $crate::__sus::Arc::new(x) as $crate::__sus::Arc<_>
-                        }),
+                        })),
                     }
                 }
             }
@@ -931,8 +2964,10 @@ macro_rules! setup {
 #[doc(hidden)]
 pub mod __sus {
     pub use crate::builder::SetupInit;
-    pub use crate::builder::TestBuilder;
+    pub use crate::builder::{Build, TestBuilder};
     pub use crate::builder::{Setup, TestRequirementHasSetupFnFor};
+    pub use crate::check::record as record_check;
+    pub use crate::setup_env::in_setup;
     pub use inventory;
     pub use std::sync::Arc;
     pub use std::{any::TypeId, module_path};
@@ -943,4 +2978,14 @@ pub mod __sus {
         Setup<T>: TestRequirementHasSetupFnFor<S>,
     {
     }
+
+    /// Sets the description of a `test!` from the lines of its doc comment.
+    pub fn with_doc(trial: crate::Trial, doc: &[&str]) -> crate::Trial {
+        let lines: Vec<_> = doc.iter().map(|line| line.trim()).collect();
+        let description = lines.join("\n");
+        match description.trim() {
+            "" => trial,
+            description => trial.with_description(description),
+        }
+    }
 }
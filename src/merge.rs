@@ -0,0 +1,326 @@
+//! [`merge_reports`] and `--merge-into`: one report for the runs of several
+//! test binaries.
+//!
+//! Every `[[test]]` target is a binary of its own, with a report of its own.
+//! A combined report is a JSON event stream like the one of a single run,
+//! whose `run-started` and `run-finished` events add up those of the runs:
+//! counts and durations are summed, lists joined, and other values, e.g.
+//! seeds, kept if all runs agree.
+//! In between are the events of each run, including its own `run-started` and
+//! `run-finished`, tagged with the `binary` it came from.
+//!
+//! A combined report can be merged again. A binary that is merged again
+//! replaces its earlier results, so that running a binary twice does not count
+//! its tests twice.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
+use serde_json::{Map, Value};
+
+use crate::{nextest::reporter::junit_message, report::SCHEMA_VERSION};
+
+/// Merges the JSON reports at `paths`, written with `--format json --logfile
+/// PATH` or by `--merge-into`, into one.
+///
+/// The runs of a report of a single binary are named after its file name,
+/// without the extension.
+///
+/// ```no_run
+/// let report = async_test::merge_reports(["target/unit.json", "target/api.json"])?;
+/// report.write_junit(std::fs::File::create("target/junit.xml")?)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn merge_reports<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+) -> io::Result<CombinedReport> {
+    let mut report = CombinedReport::default();
+    for path in paths {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let binary = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        report.merge(&contents, &binary).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })?;
+    }
+    Ok(report)
+}
+
+/// The runs of several test binaries, see [`merge_reports`].
+#[derive(Debug, Clone, Default)]
+pub struct CombinedReport {
+    runs: Vec<Run>,
+}
+
+/// The records of the run of one binary, without their `binary` tag.
+#[derive(Debug, Clone)]
+struct Run {
+    binary: String,
+    /// Always JSON objects.
+    records: Vec<Value>,
+}
+
+impl CombinedReport {
+    /// The binaries whose runs are in the report, in the order they were
+    /// merged.
+    pub fn binaries(&self) -> impl Iterator<Item = &str> {
+        self.runs.iter().map(|run| run.binary.as_str())
+    }
+
+    /// The number of tests that passed, in all runs.
+    pub fn passed(&self) -> usize {
+        self.count_status("passed")
+    }
+
+    /// The number of tests that failed, in all runs.
+    pub fn failed(&self) -> usize {
+        self.count_status("failed")
+    }
+
+    fn count_status(&self, status: &str) -> usize {
+        self.runs
+            .iter()
+            .flat_map(|run| &run.records)
+            .filter(|record| record["type"] == "test-finished" && record["status"] == status)
+            .count()
+    }
+
+    /// Adds the runs of a report, replacing earlier runs of the same
+    /// binaries. Records of a single run are taken to be from `binary`.
+    pub(crate) fn merge(&mut self, contents: &str, binary: &str) -> serde_json::Result<()> {
+        let records = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<Map<String, Value>>>>()?;
+        let combined = records.iter().any(|record| record.contains_key("binary"));
+
+        let mut runs: Vec<Run> = vec![];
+        for mut record in records {
+            let binary = match record.remove("binary") {
+                Some(Value::String(binary)) => binary,
+                // The totals of a combined report, which are added up again
+                // when it is written.
+                _ if combined => continue,
+                _ => binary.to_owned(),
+            };
+            let record = Value::Object(record);
+            // A new run of the same binary, e.g. in an appended run log,
+            // replaces the earlier one.
+            if record["type"] == "run-started" {
+                runs.retain(|run| run.binary != binary);
+            }
+            match runs.iter_mut().find(|run| run.binary == binary) {
+                Some(run) => run.records.push(record),
+                None => runs.push(Run {
+                    binary,
+                    records: vec![record],
+                }),
+            }
+        }
+        for run in runs {
+            self.runs.retain(|earlier| earlier.binary != run.binary);
+            self.runs.push(run);
+        }
+        Ok(())
+    }
+
+    /// Writes the report as a JSON event stream, one record per line.
+    pub fn write_json(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut lines = vec![];
+        if let Some(started) = self.combined("run-started") {
+            lines.push(started);
+        }
+        for run in &self.runs {
+            for record in &run.records {
+                let mut record = record.clone();
+                if let Value::Object(record) = &mut record {
+                    record.insert("binary".to_owned(), run.binary.clone().into());
+                }
+                lines.push(record);
+            }
+        }
+        // Only a report of runs that all finished has a summary.
+        let finished = self
+            .runs
+            .iter()
+            .all(|run| run.records.iter().any(|r| r["type"] == "run-finished"));
+        if let (true, Some(finished)) = (finished, self.combined("run-finished")) {
+            lines.push(finished);
+        }
+        for line in lines {
+            serde_json::to_writer(&mut writer, &line)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the report as JUnit XML, with a test suite per binary.
+    pub fn write_junit(&self, writer: impl Write) -> io::Result<()> {
+        let mut report = Report::new("async-test");
+        for run in &self.runs {
+            let mut suite = TestSuite::new(run.binary.clone());
+            for record in &run.records {
+                let name = record["name"].as_str().unwrap_or_default();
+                let mut status = match (record["type"].as_str(), record["status"].as_str()) {
+                    (Some("test-finished"), Some("passed")) => TestCaseStatus::success(),
+                    (Some("test-finished"), _) => {
+                        let mut status = TestCaseStatus::non_success(NonSuccessKind::Failure);
+                        status.set_type("test failure");
+                        status
+                    }
+                    (Some("test-skipped"), _) => TestCaseStatus::skipped(),
+                    _ => continue,
+                };
+                let failures = record["failures"].as_array().map_or(&[][..], Vec::as_slice);
+                let messages: Vec<_> = failures
+                    .iter()
+                    .filter_map(|failure| failure["message"].as_str())
+                    .collect();
+                if let Some(message) = junit_message(messages.into_iter()) {
+                    status.set_message(message);
+                }
+                if let Some(output) = record["output"].as_str() {
+                    status.set_description(output);
+                }
+                let mut case = TestCase::new(name, status);
+                if let Some(secs) = record["duration_secs"].as_f64() {
+                    case.set_time(Duration::from_secs_f64(secs.max(0.0)));
+                }
+                suite.add_test_case(case);
+            }
+            report.add_test_suite(suite);
+        }
+        report
+            .serialize(writer)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    }
+
+    /// The events of type `ty` of all runs added up, or `None` if no run has
+    /// one.
+    fn combined(&self, ty: &str) -> Option<Value> {
+        let events: Vec<_> = self
+            .runs
+            .iter()
+            .filter_map(|run| run.records.iter().find(|r| r["type"] == ty))
+            .collect();
+        let first = events.first()?;
+        let mut combined = Map::new();
+        combined.insert("schema_version".to_owned(), SCHEMA_VERSION.into());
+        combined.insert("type".to_owned(), ty.into());
+        for key in first.as_object()?.keys() {
+            if combined.contains_key(key) {
+                continue;
+            }
+            let values: Vec<_> = events.iter().filter_map(|e| e.get(key)).collect();
+            if let Some(value) = add_up(key, &values) {
+                combined.insert(key.clone(), value);
+            }
+        }
+        Some(Value::Object(combined))
+    }
+}
+
+/// Sums numbers and joins lists. Other values, and seeds, are kept if they are
+/// all the same, and dropped otherwise.
+fn add_up(key: &str, values: &[&Value]) -> Option<Value> {
+    let first = values.first()?;
+    if key.ends_with("_seed") {
+        return values.iter().all(|v| v == first).then(|| (*first).clone());
+    }
+    if let Some(numbers) = values
+        .iter()
+        .map(|v| v.as_u64())
+        .collect::<Option<Vec<_>>>()
+    {
+        return Some(numbers.into_iter().sum::<u64>().into());
+    }
+    if let Some(numbers) = values
+        .iter()
+        .map(|v| v.as_f64())
+        .collect::<Option<Vec<_>>>()
+    {
+        return Some(numbers.into_iter().sum::<f64>().into());
+    }
+    if let Some(lists) = values
+        .iter()
+        .map(|v| v.as_array())
+        .collect::<Option<Vec<_>>>()
+    {
+        let mut joined: Vec<Value> = vec![];
+        for item in lists.into_iter().flatten() {
+            if !joined.contains(item) {
+                joined.push(item.clone());
+            }
+        }
+        return Some(joined.into());
+    }
+    values.iter().all(|v| v == first).then(|| (*first).clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(binary: &str, passed: u64) -> String {
+        [
+            r#"{"schema_version":1,"type":"run-started","test_count":1,"skip_count":0,"diagnostics":[],"sample_seed":7}"#.to_owned(),
+            format!(r#"{{"schema_version":1,"type":"test-finished","name":"{binary}_test","status":"passed","duration_secs":0.5}}"#),
+            format!(r#"{{"schema_version":1,"type":"run-finished","duration_secs":0.5,"passed":{passed},"failed":0}}"#),
+        ]
+        .join("\n")
+    }
+
+    fn lines(report: &CombinedReport) -> Vec<Value> {
+        let mut json = vec![];
+        report.write_json(&mut json).unwrap();
+        String::from_utf8(json)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn adds_up_runs() {
+        let mut report = CombinedReport::default();
+        report.merge(&run("a", 1), "a").unwrap();
+        report.merge(&run("b", 1), "b").unwrap();
+        let lines = lines(&report);
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0]["type"], "run-started");
+        assert_eq!(lines[0]["test_count"], 2);
+        assert_eq!(lines[0]["sample_seed"], 7);
+        assert_eq!(lines[1]["binary"], "a");
+        assert_eq!(lines[7]["type"], "run-finished");
+        assert_eq!(lines[7]["passed"], 2);
+        assert_eq!(lines[7]["duration_secs"], 1.0);
+        assert_eq!(report.passed(), 2);
+    }
+
+    #[test]
+    fn merging_again_replaces_binary() {
+        let mut report = CombinedReport::default();
+        report.merge(&run("a", 1), "a").unwrap();
+        report.merge(&run("b", 1), "b").unwrap();
+        let mut json = vec![];
+        report.write_json(&mut json).unwrap();
+
+        let mut again = CombinedReport::default();
+        again
+            .merge(std::str::from_utf8(&json).unwrap(), "combined")
+            .unwrap();
+        again.merge(&run("a", 1), "a").unwrap();
+        assert_eq!(again.binaries().collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(lines(&again)[7]["passed"], 2);
+    }
+}
@@ -0,0 +1,55 @@
+//! Values that tests record about themselves, see [`TestMeta`].
+//!
+//! Recorded values are kept in a task-local map that lives as long as the
+//! test, including its retries and iterations, like failed checks.
+
+use std::{cell::RefCell, collections::BTreeMap, future::Future};
+
+use crate::report::MetaValue;
+
+tokio::task_local! {
+    static RECORDED: RefCell<BTreeMap<String, MetaValue>>;
+}
+
+/// Measurements and other details of a test run, shown with `--verbose` and
+/// reported as `meta` in the JSON report.
+///
+/// ```no_run
+/// use async_test::TestMeta;
+///
+/// async_test::test!(
+///     async fn bulk_insert() {
+///         // ...
+///         TestMeta::record("rows_verified", 1000);
+///         TestMeta::record("p99_ms", 12.3);
+///     }
+/// );
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TestMeta;
+
+impl TestMeta {
+    /// Records `value` under `key` for the current test. A later value for a
+    /// key replaces an earlier one.
+    ///
+    /// Values are only recorded on the task of the test itself: outside of a
+    /// test, e.g. in a task it spawned, this does nothing.
+    pub fn record(key: impl Into<String>, value: impl Into<MetaValue>) {
+        _ = RECORDED.try_with(|recorded| {
+            recorded.borrow_mut().insert(key.into(), value.into());
+        });
+    }
+}
+
+/// Runs a test, returning the values it recorded along with its output.
+pub(crate) async fn collected<T>(
+    test: impl Future<Output = T>,
+) -> (T, BTreeMap<String, MetaValue>) {
+    RECORDED
+        .scope(RefCell::default(), async {
+            let output = test.await;
+            (output, RECORDED.with(RefCell::take))
+        })
+        .await
+}
@@ -1,4 +1,7 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
 
 use crate::TestInfo;
 
@@ -14,6 +17,8 @@ pub struct ExecuteStatus {
     // /// Standard error for this test.
     // pub stderr: Bytes,
     pub output: Option<String>,
+    /// The failures that make up `output`.
+    pub failures: Vec<crate::report::Failure>,
     /// The result of execution this test: pass, fail or execution error.
     pub result: ExecutionResult,
     /// The time at which the test started.
@@ -24,6 +29,26 @@ pub struct ExecuteStatus {
     pub is_slow: bool,
     /// The delay will be non-zero if this is a retry and delay was specified.
     pub delay_before_start: Duration,
+    /// Set if the test was repeated with `--repeat`.
+    pub iterations: Option<crate::report::Iterations>,
+    /// Set if the test ran with `--verify-deterministic`.
+    pub determinism: Option<crate::report::Determinism>,
+    /// How many times the test ran, more than 1 if it was retried.
+    pub attempts: usize,
+    /// Set if the process was suspended while the test ran. Not included in
+    /// `time_taken`.
+    pub suspended: Option<Duration>,
+    /// The time the test waited for one of the `--test-tasks` before it
+    /// started. Not included in `time_taken`.
+    pub queued: Duration,
+    /// The time the test waited for its fixtures to be set up before it
+    /// started. Not included in `time_taken`.
+    pub setup_wait: Duration,
+    /// How long the test was expected to take, see
+    /// `Trial::with_expected_duration`.
+    pub expected_duration: Option<Duration>,
+    /// The values the test recorded with `TestMeta::record`.
+    pub meta: BTreeMap<String, crate::report::MetaValue>,
 }
 
 /// Whether a test passed, failed or an error occurred while executing the test.
@@ -35,6 +60,17 @@ pub enum ExecutionResult {
     Fail,
     /// The test was terminated due to timeout.
     Timeout,
+    /// The test failed, as it was expected to.
+    ExpectedFail,
+    /// The test passed, but was expected to fail.
+    UnexpectedPass,
+}
+
+impl ExecutionResult {
+    /// Returns true if the result does not fail the run.
+    pub fn is_success(self) -> bool {
+        matches!(self, Self::Pass | Self::ExpectedFail)
+    }
 }
 
 /// Statistics for a test run.
@@ -54,26 +90,74 @@ pub struct RunStats {
     /// The number of slow tests that passed.
     pub passed_slow: usize,
 
-    // /// The number of tests that passed on retry.
-    // pub flaky: usize,
+    /// The number of tests that failed as expected. Included in `passed`.
+    pub expected_failures: usize,
+
+    /// The number of tests that passed on retry. Included in `passed`.
+    pub flaky: usize,
+
     /// The number of tests that failed.
     pub failed: usize,
 
     /// The number of failed tests that were slow.
     pub failed_slow: usize,
 
-    /// The number of tests that timed out.
+    /// The number of tests that timed out. Not included in `failed`.
     pub timed_out: usize,
 
     /// The number of tests that were skipped.
     pub skipped: usize,
+
+    /// The number of tests left out by filters, tags or `--sample`. Included
+    /// in `skipped`.
+    pub filtered_out: usize,
+
+    /// The number of ignored tests. Included in `skipped`.
+    pub ignored: usize,
+
+    /// The number of tests left out by `--sample`. Included in `skipped`
+    /// and `filtered_out`.
+    pub sampled_out: usize,
+
+    /// The number of tests that require an external service, skipped by
+    /// `--offline`. Included in `skipped`.
+    pub offline: usize,
+
+    /// The number of tests that were not started because `--time-budget`
+    /// ran out, a test failed with `--fail-fast`, `--run-timeout` passed or
+    /// the run was canceled or interrupted. Not included in `skipped`.
+    pub not_run: usize,
+
+    /// Whether a test failed with `--fail-fast`, so that no more tests
+    /// started.
+    pub failed_fast: bool,
+
+    /// Whether `--run-timeout` passed, so that running tests were dropped
+    /// and no more tests started.
+    pub run_timed_out: bool,
+
+    /// Whether the run was canceled through a `RunHandle`, so that no more
+    /// tests started.
+    pub canceled: bool,
+
+    /// Whether Ctrl-C was pressed or SIGTERM or SIGHUP received, so that no
+    /// more tests started.
+    pub interrupted: bool,
+
+    /// The number of test builders that panicked while collecting tests.
+    pub collection_errors: usize,
+
+    /// The total time that finished tests waited for one of the
+    /// `--test-tasks`.
+    pub queue_wait: Duration,
 }
 
 impl RunStats {
-    /// Returns true if any tests failed or were timed out.
+    /// Returns true if any tests failed or were timed out, or if collecting
+    /// tests failed.
     #[inline]
     pub fn any_failed(&self) -> bool {
-        self.failed > 0 || self.timed_out > 0
+        self.failed > 0 || self.timed_out > 0 || self.collection_errors > 0
     }
 }
 
@@ -92,6 +176,9 @@ pub enum ExecutionDescription<'a> {
 
     /// The test was run once, or possibly multiple times. All runs failed.
     Failure { status: &'a ExecuteStatus },
+
+    /// An attempt of the test failed, and it runs again.
+    Retry { status: &'a ExecuteStatus },
 }
 
 impl<'a> ExecutionDescription<'a> {
@@ -99,16 +186,20 @@ impl<'a> ExecutionDescription<'a> {
     pub fn final_status_level(&self) -> FinalStatusLevel {
         match self {
             ExecutionDescription::Success { status, .. } => {
-                // Slow is higher priority than leaky, so return slow first here.
-                if status.is_slow {
+                // A flaky test implies that we print out retry information
+                // for it, which is more important than it being slow.
+                if status.attempts > 1 {
+                    FinalStatusLevel::Flaky
+                } else if status.is_slow {
                     FinalStatusLevel::Slow
                 } else {
                     FinalStatusLevel::Pass
                 }
             }
             ExecutionDescription::Setup { .. } => FinalStatusLevel::Pass,
-            // A flaky test implies that we print out retry information for it.
-            ExecutionDescription::Failure { .. } => FinalStatusLevel::Fail,
+            ExecutionDescription::Failure { .. } | ExecutionDescription::Retry { .. } => {
+                FinalStatusLevel::Fail
+            }
         }
     }
 }
@@ -118,6 +209,12 @@ impl<'a> ExecutionDescription<'a> {
 pub struct TestInstance {
     /// The name of the test.
     pub name: String,
+    /// What the test checks, printed with its failure output.
+    pub description: Option<String>,
+    /// Links printed with its failure output.
+    pub links: Vec<crate::report::Link>,
+    /// Why the test is expected to fail, see `Trial::with_expected_failure`.
+    pub expected_failure: Option<String>,
     // /// Information about the test suite.
     // pub suite_info: &'a RustTestSuite<'a>,
 
@@ -129,7 +226,7 @@ pub struct TestInstance {
 impl ExecuteStatus {
     /// Returns a description of self.
     pub fn describe(&self) -> ExecutionDescription<'_> {
-        if self.result == ExecutionResult::Pass {
+        if self.result.is_success() {
             ExecutionDescription::Success { status: self }
         } else {
             ExecutionDescription::Failure { status: self }
@@ -142,6 +239,13 @@ impl ExecuteStatus {
 pub(crate) struct TestList {
     pub tests: Vec<TestInfo>,
     pub skip_count: usize,
+    /// The tests left out by filters, tags or `--sample`. Included in
+    /// `skip_count`.
+    pub filtered_out: usize,
+    /// The ignored tests. Included in `skip_count`.
+    pub ignored: usize,
+    /// The tests skipped by `--offline`. Included in `skip_count`.
+    pub offline: usize,
 }
 
 impl TestList {
@@ -165,7 +269,9 @@ impl<'a> ExecutionDescription<'a> {
             ExecutionDescription::Success { .. } | ExecutionDescription::Setup { .. } => {
                 StatusLevel::Pass
             }
-            ExecutionDescription::Failure { .. } => StatusLevel::Fail,
+            ExecutionDescription::Failure { .. } | ExecutionDescription::Retry { .. } => {
+                StatusLevel::Fail
+            }
         }
     }
 }
@@ -183,6 +289,37 @@ pub enum MismatchReason {
     /// This test does not match the provided expression filters.
     Expression,
 
+    /// This test does not match the provided tag filters.
+    Tag,
+
+    /// This test requires a `cfg` that does not hold on this platform.
+    Cfg,
+
+    /// This test requires a service, like the network, that is unavailable.
+    Unavailable,
+
+    /// This test requires a service, like the network, and `--offline` is
+    /// set.
+    Offline,
+
     /// This test is in a different partition.
     Partition,
+
+    /// This test was not started because `--time-budget` ran out.
+    Budget,
+
+    /// This test was not started because another test failed with
+    /// `--fail-fast`.
+    FailFast,
+
+    /// This test was not started because `--run-timeout` passed.
+    RunTimeout,
+
+    /// This test was not started because the run was canceled through a
+    /// `RunHandle`.
+    Canceled,
+
+    /// This test was not started, or did not finish in time, because Ctrl-C
+    /// was pressed or SIGTERM or SIGHUP received.
+    Interrupted,
 }
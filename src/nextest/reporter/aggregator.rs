@@ -15,21 +15,40 @@
 // use camino::Utf8PathBuf;
 use chrono::{DateTime, FixedOffset, Utc};
 use debug_ignore::DebugIgnore;
-use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
-use std::{borrow::Cow, collections::HashMap, fs::File, path::PathBuf, time::SystemTime};
+use quick_junit::{NonSuccessKind, Property, Report, TestCase, TestCaseStatus, TestSuite};
+use std::{
+    borrow::Cow, collections::HashMap, fs::File, io::Write, path::PathBuf, time::SystemTime,
+};
 use thiserror::Error;
 
-use crate::nextest::{ExecuteStatus, ExecutionResult};
+use crate::{
+    nextest::{ExecuteStatus, ExecutionResult},
+    report,
+};
 
-use super::TestEvent;
+use super::{
+    checkpoint::Checkpoint, html::HtmlReport, merge_into::MergeInto, metrics::Metrics,
+    report_events, timeline::Timeline, TestEvent,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 #[allow(dead_code)]
 pub(crate) struct EventAggregator<'cfg> {
     // store_dir: PathBuf,
     // TODO: log information in a JSONable report (converting that to XML later) instead of directly
     // writing it to XML
     junit: Option<MetadataJunit<'cfg>>,
+    /// The `--run-log` file.
+    run_log: Option<File>,
+    checkpoint: Option<Checkpoint>,
+    /// The `--metrics-file` counters.
+    metrics: Option<Metrics>,
+    /// The `--html-report` page.
+    html: Option<HtmlReport>,
+    /// The `--trace-timeline` file.
+    timeline: Option<Timeline>,
+    /// The `--merge-into` report.
+    merge_into: Option<MergeInto>,
 }
 
 impl<'cfg> EventAggregator<'cfg> {
@@ -37,13 +56,86 @@ impl<'cfg> EventAggregator<'cfg> {
         Self {
             // store_dir: profile.store_dir().to_owned(),
             junit: Some(MetadataJunit::new(profile)),
+            run_log: None,
+            checkpoint: None,
+            metrics: None,
+            html: None,
+            timeline: None,
+            merge_into: None,
         }
     }
     pub(crate) fn new() -> Self {
         Self {
             // store_dir: profile.store_dir().to_owned(),
             junit: None,
+            run_log: None,
+            checkpoint: None,
+            metrics: None,
+            html: None,
+            timeline: None,
+            merge_into: None,
+        }
+    }
+
+    /// Appends every event to `file` as a [`report::EventRecord`].
+    pub(crate) fn set_run_log(&mut self, file: File) {
+        self.run_log = Some(file);
+    }
+
+    pub(crate) fn set_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.checkpoint = Some(checkpoint);
+    }
+
+    pub(crate) fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = Some(metrics);
+    }
+
+    pub(crate) fn set_html_report(&mut self, html: HtmlReport) {
+        self.html = Some(html);
+    }
+
+    pub(crate) fn set_timeline(&mut self, timeline: Timeline) {
+        self.timeline = Some(timeline);
+    }
+
+    pub(crate) fn set_merge_into(&mut self, merge_into: MergeInto) {
+        self.merge_into = Some(merge_into);
+    }
+
+    /// Writes the event to the run log, checkpoint and merged report, before
+    /// it is written anywhere else.
+    ///
+    /// Each record goes to the run log with a single unbuffered write, so it
+    /// is on disk even if the process is killed right after.
+    pub(crate) fn log_event(&mut self, event: &TestEvent<'cfg>) -> Result<(), WriteEventError> {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.push(event)?;
+        }
+        if let Some(html) = &mut self.html {
+            html.push(event)?;
+        }
+        if let Some(timeline) = &mut self.timeline {
+            timeline.push(event)?;
         }
+        if self.run_log.is_none() && self.checkpoint.is_none() && self.merge_into.is_none() {
+            return Ok(());
+        }
+        let mut lines = vec![];
+        for event in report_events(event) {
+            // Always one record per line, whatever the format of the output.
+            report::write_json(&mut lines, &report::EventRecord::new(event), false)
+                .map_err(WriteEventError::Io)?;
+        }
+        if let Some(file) = &mut self.run_log {
+            file.write_all(&lines).map_err(WriteEventError::Io)?;
+        }
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.push(event, &lines)?;
+        }
+        if let Some(merge_into) = &mut self.merge_into {
+            merge_into.push(event, &lines)?;
+        }
+        Ok(())
     }
 
     pub(crate) fn write_event(&mut self, event: TestEvent<'cfg>) -> Result<(), WriteEventError> {
@@ -54,6 +146,29 @@ impl<'cfg> EventAggregator<'cfg> {
     }
 }
 
+/// The message of the `<failure>` of a test case that failed with `messages`.
+///
+/// JUnit has room for one failure per test case, so several are joined,
+/// numbered like in the terminal, with the first line of each. Their full
+/// text is in the description.
+pub(crate) fn junit_message<'a>(
+    messages: impl ExactSizeIterator<Item = &'a str>,
+) -> Option<String> {
+    let count = messages.len();
+    let mut joined = String::new();
+    for (i, message) in messages.enumerate() {
+        let first_line = message.lines().next().unwrap_or_default();
+        if count > 1 {
+            joined += &format!("[{}/{count}] ", i + 1);
+        }
+        joined += first_line;
+        if i + 1 < count {
+            joined.push('\n');
+        }
+    }
+    (count > 0).then_some(joined)
+}
+
 #[derive(Clone, Debug)]
 struct MetadataJunit<'cfg> {
     config: NextestJunitConfig<'cfg>,
@@ -99,6 +214,19 @@ pub struct NextestJunitConfig<'cfg> {
     store_failure_output: bool,
 }
 
+impl<'cfg> NextestJunitConfig<'cfg> {
+    /// Writes the report called `report_name` to `path` once the run
+    /// finishes.
+    pub(crate) fn new(path: PathBuf, report_name: &'cfg str) -> Self {
+        Self {
+            path,
+            report_name,
+            store_success_output: false,
+            store_failure_output: false,
+        }
+    }
+}
+
 impl<'cfg> MetadataJunit<'cfg> {
     fn new(config: NextestJunitConfig<'cfg>) -> Self {
         Self {
@@ -113,8 +241,14 @@ impl<'cfg> MetadataJunit<'cfg> {
             | TestEvent::RunPaused { .. }
             | TestEvent::RunContinued { .. } => {}
             TestEvent::TestStarted { .. } => {}
-            TestEvent::TestSlow { .. } => {}
-            TestEvent::SetupFinished { .. } => {}
+            TestEvent::TestSlow { .. } | TestEvent::TestsSlow { .. } => {}
+            TestEvent::TestAttemptFailedWillRetry { .. } => {}
+            TestEvent::SetupFinished { .. }
+            | TestEvent::CollectionError { .. }
+            | TestEvent::RunCompared { .. }
+            | TestEvent::ConcurrencyReduced { .. }
+            | TestEvent::FixturesUsed { .. }
+            | TestEvent::RunProgress { .. } => {}
             TestEvent::TestFinished {
                 test_instance,
                 run_status,
@@ -128,7 +262,10 @@ impl<'cfg> MetadataJunit<'cfg> {
                         ExecutionResult::Timeout => {
                             (NonSuccessKind::Failure, "test timeout".into())
                         }
-                        ExecutionResult::Pass => {
+                        ExecutionResult::UnexpectedPass => {
+                            (NonSuccessKind::Failure, "unexpected pass".into())
+                        }
+                        ExecutionResult::Pass | ExecutionResult::ExpectedFail => {
                             unreachable!("this is a failure status")
                         }
                     }
@@ -143,7 +280,9 @@ impl<'cfg> MetadataJunit<'cfg> {
                 let status = run_status.result;
 
                 let testcase_status = match run_status.result {
-                    ExecutionResult::Pass => TestCaseStatus::success(),
+                    ExecutionResult::Pass | ExecutionResult::ExpectedFail => {
+                        TestCaseStatus::success()
+                    }
                     ExecutionResult::Fail => {
                         let mut testcase_status =
                             TestCaseStatus::non_success(NonSuccessKind::Failure);
@@ -156,6 +295,12 @@ impl<'cfg> MetadataJunit<'cfg> {
                         testcase_status.set_type("test timeout");
                         testcase_status
                     }
+                    ExecutionResult::UnexpectedPass => {
+                        let mut testcase_status =
+                            TestCaseStatus::non_success(NonSuccessKind::Failure);
+                        testcase_status.set_type("unexpected pass");
+                        testcase_status
+                    }
                 };
 
                 let mut testcase = TestCase::new(test_instance.name, testcase_status);
@@ -163,16 +308,29 @@ impl<'cfg> MetadataJunit<'cfg> {
                     .set_classname("test")
                     .set_timestamp(to_datetime(run_status.start_time))
                     .set_time(run_status.time_taken);
+                if let Some(description) = &test_instance.description {
+                    testcase.add_property(Property::new("description", description));
+                }
+                if let Some(reason) = &test_instance.expected_failure {
+                    testcase.add_property(Property::new("expected-failure", reason));
+                }
+                for link in &test_instance.links {
+                    testcase.add_property(Property::new(format!("link:{}", link.label), &link.url));
+                }
 
                 // TODO: allure seems to want the output to be in a format where text files are
                 // written out to disk:
                 // https://github.com/allure-framework/allure2/blob/master/plugins/junit-xml-plugin/src/main/java/io/qameta/allure/junitxml/JunitXmlPlugin.java#L192-L196
                 // we may have to update this format to handle that.
-                let is_success = status == ExecutionResult::Pass;
+                let is_success = status.is_success();
                 if !is_success {
                     if let Some(description) = &run_status.output {
                         testcase.status.set_description(description);
                     }
+                    let messages = run_status.failures.iter().map(|f| f.message.as_str());
+                    if let Some(message) = junit_message(messages) {
+                        testcase.status.set_message(message);
+                    }
                 }
 
                 if (junit_store_success_output && is_success)
@@ -204,7 +362,7 @@ impl<'cfg> MetadataJunit<'cfg> {
                 ..
             } => {
                 // Write out the report to the given file.
-                let mut report = Report::new("report");
+                let mut report = Report::new(self.config.report_name);
                 report
                     .set_timestamp(to_datetime(start_time))
                     .set_time(elapsed)
@@ -385,3 +543,22 @@ fn to_datetime(system_time: SystemTime) -> DateTime<FixedOffset> {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod junit_tests {
+    use super::*;
+
+    #[test]
+    fn joins_failures() {
+        assert_eq!(junit_message([].into_iter()), None);
+        assert_eq!(
+            junit_message(["thread 'main' panicked at 'boom'\nbacktrace"].into_iter()).unwrap(),
+            "thread 'main' panicked at 'boom'"
+        );
+        assert_eq!(
+            junit_message(["check failed at 'a'", "thread 'main' panicked at 'b'"].into_iter())
+                .unwrap(),
+            "[1/2] check failed at 'a'\n[2/2] thread 'main' panicked at 'b'"
+        );
+    }
+}
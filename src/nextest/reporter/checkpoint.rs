@@ -0,0 +1,109 @@
+//! Periodic snapshots of the JSON report.
+//!
+//! A run that is killed partway, e.g. by the OOM killer or a CI time limit,
+//! never writes its final report. With `--checkpoint`, the JSON event stream
+//! so far is written to disk every few finished tests or seconds, so most
+//! results survive.
+//!
+//! Each snapshot appends the records since the previous one to the file with
+//! a single write, so a snapshot costs as much as its own records, not the
+//! whole report. Snapshots end with a whole record: a last line without a
+//! newline is a snapshot that is still being written, and readers skip it.
+//! The stream has no summary until `run-finished`, so nothing written before
+//! is ever rewritten.
+
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use super::{aggregator::WriteEventError, TestEvent};
+
+#[derive(Debug)]
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    /// The number of finished tests after which a snapshot is written.
+    every: usize,
+    /// The time after which a snapshot is written, if any test finished
+    /// since the last one.
+    interval: Duration,
+    /// The file, once the first snapshot created it.
+    file: Option<File>,
+    /// The records since the last snapshot.
+    pending: Vec<u8>,
+    finished_since: usize,
+    written_at: Instant,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(path: PathBuf, every: usize, interval: Duration) -> Self {
+        Self {
+            path,
+            every: every.max(1),
+            interval,
+            file: None,
+            pending: vec![],
+            finished_since: 0,
+            written_at: Instant::now(),
+        }
+    }
+
+    /// Adds the records of `event` and writes a snapshot if one is due. The
+    /// last snapshot is written when the run finishes.
+    pub(crate) fn push(
+        &mut self,
+        event: &TestEvent<'_>,
+        lines: &[u8],
+    ) -> Result<(), WriteEventError> {
+        self.pending.extend_from_slice(lines);
+        let due = match event {
+            TestEvent::TestFinished { .. } => {
+                self.finished_since += 1;
+                self.finished_since >= self.every || self.written_at.elapsed() >= self.interval
+            }
+            TestEvent::RunFinished { .. } => true,
+            _ => false,
+        };
+        if due {
+            self.write()?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self) -> Result<(), WriteEventError> {
+        let fs_error = |error| WriteEventError::Fs {
+            file: self.path.clone(),
+            error,
+        };
+        let file = match &mut self.file {
+            Some(file) => file,
+            // Replaces the snapshots of an earlier run.
+            None => self
+                .file
+                .insert(File::create(&self.path).map_err(fs_error)?),
+        };
+        file.write_all(&self.pending).map_err(fs_error)?;
+        self.pending.clear();
+        self.finished_since = 0;
+        self.written_at = Instant::now();
+        Ok(())
+    }
+}
+
+/// Replaces the file at `path` with `contents`, by writing them to a
+/// temporary file next to it and renaming that over it.
+pub(super) fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), WriteEventError> {
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, contents).map_err(|error| WriteEventError::Fs {
+        file: tmp.clone(),
+        error,
+    })?;
+    std::fs::rename(&tmp, path).map_err(|error| WriteEventError::Fs {
+        file: path.to_path_buf(),
+        error,
+    })
+}
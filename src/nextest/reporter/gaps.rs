@@ -0,0 +1,315 @@
+//! `--report-gaps`: why tasks were idle during the run.
+//!
+//! Once the run finishes, the time between its start and end is cut at
+//! every start and end of a test, a setup or a wait. Each piece in which
+//! fewer tests and setups ran than there are tasks is attributed to one
+//! cause:
+//!
+//! - Startup, before anything started, e.g. probing services.
+//! - A fixture, while it was set up and tests waited for it.
+//! - The long tail, after the last test started, when there is nothing
+//!   left to start.
+//! - Otherwise, no test was ready to start, e.g. because of test groups or
+//!   the limit of heavy tests.
+//!
+//! Time that tests spent waiting for a free task is a cause of its own,
+//! since more tasks could have run them sooner.
+
+use std::time::{Duration, SystemTime};
+
+use super::{
+    timeline::{Span, SpanKind},
+    TestEvent,
+};
+
+/// The number of causes that are printed.
+const TOP_CAUSES: usize = 5;
+
+/// The share of the task time below which a cause is left out, and of the
+/// long tail below which a test is not counted as part of it.
+const MIN_SHARE: f64 = 0.01;
+
+#[derive(Debug, Default)]
+pub(super) struct Gaps {
+    /// The tests and setups that finished, in the order they did.
+    spans: Vec<Span>,
+}
+
+/// The idle task time of a run, with its top causes.
+#[derive(Debug)]
+pub(super) struct Analysis {
+    /// The time all tasks were available for, the tasks times the run.
+    pub(super) capacity: Duration,
+    /// The part of `capacity` in which tasks were idle.
+    pub(super) idle: Duration,
+    /// The top causes, with the one that cost the most first.
+    pub(super) causes: Vec<Cause>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Cause {
+    pub(super) kind: CauseKind,
+    /// The task time the cause cost.
+    pub(super) cost: Duration,
+    /// The time in which it did.
+    pub(super) during: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum CauseKind {
+    Startup,
+    Fixture(String),
+    /// The tests that ran after the last one started, with the longest
+    /// first.
+    Tail(Vec<(String, Duration)>),
+    NotReady,
+    Queued,
+}
+
+impl Cause {
+    /// Describes the cause for a run with `tasks` tasks, and what might
+    /// remove it.
+    pub(super) fn describe(&self, tasks: usize) -> String {
+        let during = self.during.as_secs_f64();
+        let idle = match self.during.is_zero() {
+            true => 0,
+            false => (self.cost.as_secs_f64() / during).round() as usize,
+        };
+        let idle = match idle {
+            1 => "1 task".to_owned(),
+            idle => format!("{idle} tasks"),
+        };
+        match &self.kind {
+            CauseKind::Startup => format!("{idle} idle for {during:.3}s before any test started"),
+            CauseKind::Fixture(name) => format!(
+                "{idle} idle for {during:.3}s waiting on fixture {name}, make it faster or cache \
+                its artifacts"
+            ),
+            CauseKind::Tail(tests) => {
+                let ran = match tests.len() {
+                    1 => "the last test ran".to_owned(),
+                    count => format!("the last {count} tests ran"),
+                };
+                let (longest, duration) = &tests[0];
+                format!(
+                    "long tail: {ran} alone for {during:.3}s, split {longest} ({:.3}s) or start \
+                    it earlier",
+                    duration.as_secs_f64()
+                )
+            }
+            CauseKind::NotReady => format!(
+                "{idle} idle for {during:.3}s with no test ready to start, e.g. held back by \
+                test groups or heavy tests"
+            ),
+            CauseKind::Queued => format!(
+                "tests waited {:.3}s in total for a free task, raise --test-tasks from {tasks} if \
+                the machine has capacity to spare",
+                self.cost.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl Gaps {
+    pub(super) fn push(&mut self, event: &TestEvent<'_>) {
+        if let Some(span) = Span::from_event(event) {
+            self.spans.push(span);
+        }
+    }
+
+    /// Finds the causes of idle tasks in a run with `tasks` tasks, that
+    /// started at `run_start` and took `elapsed`.
+    pub(super) fn analyze(
+        &self,
+        tasks: usize,
+        run_start: SystemTime,
+        elapsed: Duration,
+    ) -> Analysis {
+        let offset = |time: SystemTime| {
+            time.duration_since(run_start)
+                .unwrap_or_default()
+                .min(elapsed)
+        };
+        // What ran, and what waited for a fixture, as offsets into the run.
+        let mut busy = vec![];
+        let mut setups = vec![];
+        let mut waits = vec![];
+        let mut queued = Duration::ZERO;
+        for span in &self.spans {
+            let (start, end) = (offset(span.start), offset(span.end()));
+            busy.push((start, end));
+            match &span.kind {
+                SpanKind::Fixture => setups.push((start, end, span.name.as_str())),
+                SpanKind::Test {
+                    setup_wait,
+                    queued: test_queued,
+                    ..
+                } => {
+                    queued += *test_queued;
+                    let waited = span.start.checked_sub(*test_queued);
+                    if let Some(waited) = waited {
+                        let waited = offset(waited);
+                        waits.push((waited.saturating_sub(*setup_wait), waited));
+                    }
+                }
+            }
+        }
+        let first_start = busy.iter().map(|&(start, _)| start).min();
+        let last_start = busy.iter().map(|&(start, _)| start).max();
+
+        let mut points: Vec<Duration> = busy
+            .iter()
+            .chain(&waits)
+            .flat_map(|&(start, end)| [start, end])
+            .chain([Duration::ZERO, elapsed])
+            .collect();
+        points.sort();
+        points.dedup();
+
+        let mut causes: Vec<Cause> = vec![];
+        let mut add = |kind, cost, during| match causes.iter_mut().find(|c| c.kind == kind) {
+            Some(cause) => {
+                cause.cost += cost;
+                cause.during += during;
+            }
+            None => causes.push(Cause { kind, cost, during }),
+        };
+        let mut idle = Duration::ZERO;
+        // How long the long tail took, what it cost and what ran in it.
+        let (mut tail, mut tail_cost) = (Duration::ZERO, Duration::ZERO);
+        let mut in_tail = vec![Duration::ZERO; busy.len()];
+        for window in points.windows(2) {
+            let (from, to) = (window[0], window[1]);
+            let covers = |&(start, end): &(Duration, Duration)| start <= from && to <= end;
+            let running = busy.iter().filter(|span| covers(span)).count();
+            let idle_tasks = tasks.saturating_sub(running);
+            if idle_tasks == 0 {
+                continue;
+            }
+            let during = to - from;
+            let cost = during * idle_tasks as u32;
+            idle += cost;
+
+            let setup = setups
+                .iter()
+                .filter(|&&(start, end, _)| covers(&(start, end)))
+                .min_by_key(|&&(start, _, _)| start);
+            let kind = if first_start.map_or(true, |first| to <= first) {
+                CauseKind::Startup
+            } else if let Some(&(_, _, name)) = setup.filter(|_| waits.iter().any(covers)) {
+                CauseKind::Fixture(name.to_owned())
+            } else if last_start.is_some_and(|last| from >= last) {
+                for (in_tail, span) in in_tail.iter_mut().zip(&busy) {
+                    if covers(span) {
+                        *in_tail += during;
+                    }
+                }
+                tail += during;
+                tail_cost += cost;
+                continue;
+            } else {
+                CauseKind::NotReady
+            };
+            add(kind, cost, during);
+        }
+        let mut tests: Vec<_> = self
+            .spans
+            .iter()
+            .zip(in_tail)
+            // Leaves out tests that only just overlap with it.
+            .filter(|(span, in_tail)| {
+                *in_tail > tail.mul_f64(MIN_SHARE) && matches!(span.kind, SpanKind::Test { .. })
+            })
+            .map(|(span, _)| (span.name.clone(), span.duration))
+            .collect();
+        tests.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        // A tail of only setups is not split like tests.
+        if !tests.is_empty() {
+            add(CauseKind::Tail(tests), tail_cost, tail);
+        }
+        add(CauseKind::Queued, queued, Duration::ZERO);
+
+        let capacity = elapsed * tasks as u32;
+        let min_cost = capacity.mul_f64(MIN_SHARE);
+        causes.retain(|cause| !cause.cost.is_zero() && cause.cost >= min_cost);
+        causes.sort_by(|a, b| b.cost.cmp(&a.cost));
+        causes.truncate(TOP_CAUSES);
+        Analysis {
+            capacity,
+            idle,
+            causes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nextest::ExecutionResult;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn span(name: &str, kind: SpanKind, start: u64, duration: u64) -> Span {
+        Span {
+            name: name.to_owned(),
+            kind,
+            start: SystemTime::UNIX_EPOCH + secs(start),
+            duration: secs(duration),
+        }
+    }
+
+    fn test(setup_wait: u64) -> SpanKind {
+        SpanKind::Test {
+            result: ExecutionResult::Pass,
+            attempts: 1,
+            setup_wait: secs(setup_wait),
+            queued: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn causes() {
+        // With 4 tasks: a fixture takes 10s to set up while 3 tests wait,
+        // then a long test runs alone.
+        let gaps = Gaps {
+            spans: vec![
+                span("db", SpanKind::Fixture, 0, 10),
+                span("a", test(10), 10, 2),
+                span("b", test(10), 10, 2),
+                span("c", test(10), 10, 2),
+                span("long", test(0), 0, 60),
+            ],
+        };
+        let analysis = gaps.analyze(4, SystemTime::UNIX_EPOCH, secs(60));
+        assert_eq!(analysis.capacity, secs(240));
+        // 2 tasks for 10s, 0 for 2s, then 3 for 48s.
+        assert_eq!(analysis.idle, secs(2 * 10 + 3 * 48));
+        assert_eq!(
+            analysis.causes,
+            [
+                Cause {
+                    kind: CauseKind::Tail(vec![("long".to_owned(), secs(60))]),
+                    cost: secs(3 * 48),
+                    during: secs(48),
+                },
+                Cause {
+                    kind: CauseKind::Fixture("db".to_owned()),
+                    cost: secs(2 * 10),
+                    during: secs(10),
+                },
+            ]
+        );
+        assert_eq!(
+            analysis.causes[0].describe(4),
+            "long tail: the last test ran alone for 48.000s, split long (60.000s) or start it \
+            earlier"
+        );
+        assert_eq!(
+            analysis.causes[1].describe(4),
+            "2 tasks idle for 10.000s waiting on fixture db, make it faster or cache its \
+            artifacts"
+        );
+    }
+}
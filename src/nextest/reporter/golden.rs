@@ -0,0 +1,340 @@
+//! Golden-output tests for the human-readable reporter.
+//!
+//! Each test renders a scripted sequence of events with [`render_events`] and
+//! compares the result with `golden/<name>.txt`. After an intentional change
+//! to the output, run the tests with `UPDATE_GOLDEN=1` to rewrite the files,
+//! and review their diff like any other change.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use super::{render_events, CancelReason, TestEvent, TestOutputDisplay};
+use crate::{
+    config::RunConfig,
+    nextest::{ExecuteStatus, ExecutionResult, MismatchReason, RunStats, TestInstance, TestList},
+    report::Failure,
+    Arguments, ShouldPanic, TestInfo,
+};
+
+fn check(name: &str, test_list: &TestList, events: Vec<TestEvent<'_>>) {
+    let actual = render_events(test_list, events);
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/nextest/reporter/golden")
+        .join(name)
+        .with_extension("txt");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    pretty_assertions::assert_eq!(
+        expected,
+        actual,
+        "output differs from {}, rerun with UPDATE_GOLDEN=1 to update it",
+        path.display()
+    );
+}
+
+fn test_list(names: &[&str], skip_count: usize) -> TestList {
+    TestList {
+        tests: names
+            .iter()
+            .map(|name| TestInfo {
+                name: name.to_string(),
+                is_ignored: false,
+                tags: vec![],
+                unmet_cfg: None,
+                capabilities: vec![],
+                main_thread: false,
+                description: None,
+                links: vec![],
+                expected_failure: None,
+                ignore_reason: None,
+                timeout: None,
+                expected_duration: None,
+                retries: None,
+                group: None,
+                should_panic: ShouldPanic::No,
+            })
+            .collect(),
+        skip_count,
+        filtered_out: 0,
+        ignored: 0,
+        offline: 0,
+    }
+}
+
+fn started<'a>(test_list: &'a TestList, config: &'a RunConfig) -> TestEvent<'a> {
+    TestEvent::RunStarted {
+        test_list,
+        config,
+        diagnostics: &[],
+    }
+}
+
+fn instance(name: &str) -> TestInstance {
+    TestInstance {
+        name: name.to_owned(),
+        description: None,
+        links: vec![],
+        expected_failure: None,
+    }
+}
+
+fn finished(
+    name: &str,
+    result: ExecutionResult,
+    millis: u64,
+    output: Option<&str>,
+) -> TestEvent<'static> {
+    let time_taken = Duration::from_millis(millis);
+    TestEvent::TestFinished {
+        test_instance: instance(name),
+        success_output: TestOutputDisplay::Never,
+        failure_output: TestOutputDisplay::Immediate,
+        junit_store_success_output: false,
+        junit_store_failure_output: false,
+        run_status: ExecuteStatus {
+            output: output.map(str::to_owned),
+            failures: vec![],
+            result,
+            start_time: SystemTime::UNIX_EPOCH,
+            time_taken,
+            is_slow: time_taken >= Duration::from_secs(15),
+            delay_before_start: Duration::ZERO,
+            iterations: None,
+            determinism: None,
+            attempts: 1,
+            suspended: None,
+            queued: Duration::ZERO,
+            setup_wait: Duration::ZERO,
+            expected_duration: None,
+            meta: Default::default(),
+        },
+        current_stats: RunStats::default(),
+        running: 0,
+        cancel_state: None,
+    }
+}
+
+fn run_finished(millis: u64, run_stats: RunStats) -> TestEvent<'static> {
+    TestEvent::RunFinished {
+        start_time: SystemTime::UNIX_EPOCH,
+        elapsed: Duration::from_millis(millis),
+        run_stats,
+        durations: None,
+    }
+}
+
+#[test]
+fn pass_fail_skip() {
+    let test_list = test_list(&["passes", "fails"], 1);
+    let config = RunConfig::new(&Arguments::default());
+    check(
+        "pass_fail_skip",
+        &test_list,
+        vec![
+            started(&test_list, &config),
+            TestEvent::SetupFinished {
+                test_instance: instance("fixtures::database"),
+                start_time: SystemTime::UNIX_EPOCH,
+                duration: Duration::from_millis(120),
+                current_stats: RunStats::default(),
+                running: 0,
+            },
+            TestEvent::TestSkipped {
+                test_instance: instance("elsewhere"),
+                reason: MismatchReason::Cfg,
+                message: Some("requires cfg(miri)".to_owned()),
+            },
+            finished("passes", ExecutionResult::Pass, 5, None),
+            finished(
+                "fails",
+                ExecutionResult::Fail,
+                7,
+                Some("thread 'main' panicked at 'boom', tests/golden.rs:1:1"),
+            ),
+            run_finished(
+                130,
+                RunStats {
+                    initial_run_count: 2,
+                    finished_count: 2,
+                    passed: 1,
+                    failed: 1,
+                    skipped: 1,
+                    ..RunStats::default()
+                },
+            ),
+        ],
+    );
+}
+
+#[test]
+fn slow_and_timeout() {
+    let test_list = test_list(&["slow", "slower", "stuck"], 0);
+    let config = RunConfig::new(&Arguments::default());
+    check(
+        "slow_and_timeout",
+        &test_list,
+        vec![
+            started(&test_list, &config),
+            TestEvent::TestSlow {
+                test_instance: instance("slow"),
+                elapsed: Duration::from_secs(15),
+                will_terminate: false,
+            },
+            TestEvent::TestsSlow {
+                count: 3,
+                slowest: vec![
+                    (instance("stuck"), Duration::from_secs(45)),
+                    (instance("slower"), Duration::from_secs(30)),
+                    (instance("slow"), Duration::from_secs(30)),
+                ],
+            },
+            finished("slow", ExecutionResult::Pass, 31_000, None),
+            TestEvent::TestSlow {
+                test_instance: instance("stuck"),
+                elapsed: Duration::from_secs(60),
+                will_terminate: true,
+            },
+            finished(
+                "stuck",
+                ExecutionResult::Timeout,
+                60_000,
+                Some("test timed out"),
+            ),
+            finished("slower", ExecutionResult::Pass, 61_000, None),
+            run_finished(
+                61_000,
+                RunStats {
+                    initial_run_count: 3,
+                    finished_count: 3,
+                    passed: 2,
+                    passed_slow: 2,
+                    timed_out: 1,
+                    ..RunStats::default()
+                },
+            ),
+        ],
+    );
+}
+
+#[test]
+fn cancel() {
+    let test_list = test_list(&["first", "second", "third"], 0);
+    let config = RunConfig::new(&Arguments::default());
+    check(
+        "cancel",
+        &test_list,
+        vec![
+            started(&test_list, &config),
+            finished("first", ExecutionResult::Pass, 3, None),
+            TestEvent::RunPaused {
+                running: 2,
+                reason: CancelReason::Signal,
+            },
+            TestEvent::RunContinued {
+                running: 2,
+                reason: CancelReason::Signal,
+            },
+            TestEvent::RunBeginCancel {
+                running: 2,
+                reason: CancelReason::Signal,
+                message: None,
+            },
+            finished(
+                "second",
+                ExecutionResult::Fail,
+                40,
+                Some("terminated by signal"),
+            ),
+            run_finished(
+                45,
+                RunStats {
+                    initial_run_count: 3,
+                    finished_count: 2,
+                    passed: 1,
+                    failed: 1,
+                    ..RunStats::default()
+                },
+            ),
+        ],
+    );
+}
+
+#[test]
+fn interrupt() {
+    let test_list = test_list(&["first", "second", "third"], 0);
+    let config = RunConfig::new(&Arguments::default());
+    check(
+        "interrupt",
+        &test_list,
+        vec![
+            started(&test_list, &config),
+            finished("first", ExecutionResult::Pass, 3, None),
+            TestEvent::RunBeginCancel {
+                running: 2,
+                reason: CancelReason::Interrupt,
+                message: None,
+            },
+            finished("second", ExecutionResult::Pass, 40, None),
+            // "third" does not finish in time.
+            TestEvent::TestSkipped {
+                test_instance: instance("third"),
+                reason: MismatchReason::Interrupted,
+                message: Some("the run was interrupted".to_owned()),
+            },
+            run_finished(
+                2_045,
+                RunStats {
+                    initial_run_count: 3,
+                    finished_count: 2,
+                    passed: 2,
+                    not_run: 1,
+                    interrupted: true,
+                    ..RunStats::default()
+                },
+            ),
+        ],
+    );
+}
+
+#[test]
+fn multiple_failures() {
+    let test_list = test_list(&["validates"], 0);
+    let config = RunConfig::new(&Arguments::default());
+    let failures = vec![
+        Failure {
+            message: "check failed at 'name is empty', tests/golden.rs:2:5".to_owned(),
+            location: Some("tests/golden.rs:2:5".to_owned()),
+        },
+        Failure {
+            message: "thread 'main' panicked at 'boom', tests/golden.rs:3:5".to_owned(),
+            location: Some("tests/golden.rs:3:5".to_owned()),
+        },
+    ];
+    let mut validates = finished("validates", ExecutionResult::Fail, 4, Some("unused"));
+    if let TestEvent::TestFinished { run_status, .. } = &mut validates {
+        run_status.failures = failures;
+    }
+    check(
+        "multiple_failures",
+        &test_list,
+        vec![
+            started(&test_list, &config),
+            validates,
+            run_finished(
+                5,
+                RunStats {
+                    initial_run_count: 1,
+                    finished_count: 1,
+                    failed: 1,
+                    ..RunStats::default()
+                },
+            ),
+        ],
+    );
+}
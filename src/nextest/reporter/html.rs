@@ -0,0 +1,287 @@
+//! A self-contained HTML page with the results of a run.
+//!
+//! With `--html-report`, the page is written once the run finishes, from the
+//! same [`report::TestResult`]s and [`report::RunSummary`] as the JSON
+//! report: the counts, a chart of how long tests took, the output of every
+//! failed test and sortable tables of the slowest and of all tests. It loads
+//! nothing from elsewhere, so it can be kept as a CI artifact or opened
+//! straight from disk, see `--open-report`.
+
+use std::{fmt::Write, path::PathBuf};
+
+use super::{aggregator::WriteEventError, checkpoint::write_atomically, report_events, TestEvent};
+use crate::{
+    durations::bucket_label,
+    report::{self, DurationSummary, RunSummary, TestResult, TestStatus},
+};
+
+/// The number of tests in the table of slowest tests.
+const SLOWEST: usize = 10;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { text-align: left; padding: 0.2em 1em 0.2em 0; }
+th { cursor: pointer; user-select: none; }
+td.duration { text-align: right; font-family: monospace; }
+pre { background: #f4f4f4; padding: 1em; overflow-x: auto; }
+summary { cursor: pointer; color: #666; }
+svg text { font-size: 12px; fill: #222; }
+svg rect { fill: #5b8def; }
+.passed { color: #18794e; }
+.failed { color: #cd2b31; }
+.flaky { color: #ad5700; }";
+
+/// Sorts a table by the column whose header is clicked, by the `data-sort`
+/// of its cells if they have one. Clicking again reverses the order.
+const SCRIPT: &str = "\
+document.querySelectorAll('table.tests th').forEach((th, column) => {
+  th.addEventListener('click', () => {
+    const body = th.closest('table').tBodies[0];
+    const key = row => {
+      const cell = row.cells[column];
+      return cell.dataset.sort !== undefined ? Number(cell.dataset.sort) : cell.textContent;
+    };
+    const ascending = th.dataset.order !== 'asc';
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    [...body.rows]
+      .sort((a, b) => (key(a) < key(b) ? -1 : key(a) > key(b) ? 1 : 0) * (ascending ? 1 : -1))
+      .forEach(row => body.appendChild(row));
+  });
+});";
+
+#[derive(Debug)]
+pub(crate) struct HtmlReport {
+    path: PathBuf,
+    /// The tests that finished, in the order they did.
+    tests: Vec<TestResult>,
+}
+
+impl HtmlReport {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tests: vec![],
+        }
+    }
+
+    /// Adds `event` to the report, and writes it once the run finishes.
+    pub(crate) fn push(&mut self, event: &TestEvent<'_>) -> Result<(), WriteEventError> {
+        for event in report_events(event) {
+            match event {
+                report::Event::TestFinished(result) => self.tests.push(result),
+                report::Event::RunFinished(summary) => {
+                    write_atomically(&self.path, self.render(&summary).as_bytes())?
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&self, summary: &RunSummary) -> String {
+        let failed = summary.failed + summary.timed_out;
+        let outcome = match failed + summary.collection_errors {
+            0 => "passed",
+            _ => "failed",
+        };
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>Test run {outcome}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n\
+            <h1>Test run <span class=\"{outcome}\">{outcome}</span></h1>"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "<p>{} passed, {failed} failed, {} skipped, {} not run in {:.3}s</p>",
+            summary.passed, summary.skipped, summary.not_run, summary.duration_secs
+        )
+        .unwrap();
+        if summary.flaky > 0 || summary.collection_errors > 0 {
+            writeln!(
+                out,
+                "<p>{} flaky, {} collection errors</p>",
+                summary.flaky, summary.collection_errors
+            )
+            .unwrap();
+        }
+
+        if let Some(durations) = &summary.durations {
+            writeln!(out, "<h2>Durations</h2>").unwrap();
+            writeln!(
+                out,
+                "<p>p50 {:.3}s, p90 {:.3}s, p99 {:.3}s, max {:.3}s</p>",
+                durations.p50_secs, durations.p90_secs, durations.p99_secs, durations.max_secs
+            )
+            .unwrap();
+            write_chart(&mut out, durations);
+        }
+
+        let failures: Vec<_> = self
+            .tests
+            .iter()
+            .filter(|test| test.status == TestStatus::Failed)
+            .collect();
+        if !failures.is_empty() {
+            writeln!(out, "<h2>Failures</h2>").unwrap();
+            for test in failures {
+                writeln!(
+                    out,
+                    "<h3 class=\"failed\" id=\"{0}\">{0}</h3>",
+                    escape(&test.name)
+                )
+                .unwrap();
+                write_failures(&mut out, test);
+            }
+        }
+
+        let mut slowest: Vec<_> = self.tests.iter().collect();
+        slowest.sort_by(|a, b| {
+            b.duration_secs
+                .total_cmp(&a.duration_secs)
+                .then(a.name.cmp(&b.name))
+        });
+        slowest.truncate(SLOWEST);
+        if !slowest.is_empty() {
+            writeln!(out, "<h2>Slowest tests</h2>").unwrap();
+            write_table(&mut out, slowest);
+        }
+
+        let mut all: Vec<_> = self.tests.iter().collect();
+        all.sort_by(|a, b| a.name.cmp(&b.name));
+        if !all.is_empty() {
+            writeln!(out, "<h2>All tests</h2>").unwrap();
+            write_table(&mut out, all);
+        }
+        writeln!(out, "<script>\n{SCRIPT}\n</script>\n</body>\n</html>").unwrap();
+        out
+    }
+}
+
+/// Writes the failures of a test, with their backtraces folded away.
+fn write_failures(out: &mut String, test: &TestResult) {
+    if test.failures.is_empty() {
+        if let Some(output) = &test.output {
+            writeln!(out, "<pre>{}</pre>", escape(output)).unwrap();
+        }
+        return;
+    }
+    let count = test.failures.len();
+    for (i, failure) in test.failures.iter().enumerate() {
+        let (message, backtrace) = match failure.message.split_once("\nstack backtrace:\n") {
+            Some((message, backtrace)) => (message, Some(backtrace)),
+            None => (failure.message.as_str(), None),
+        };
+        let number = match count {
+            1 => String::new(),
+            count => format!("[{}/{count}] ", i + 1),
+        };
+        writeln!(out, "<pre>{number}{}</pre>", escape(message)).unwrap();
+        if let Some(backtrace) = backtrace {
+            writeln!(
+                out,
+                "<details><summary>stack backtrace</summary><pre>{}</pre></details>",
+                escape(backtrace)
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Writes a bar chart of the number of tests per duration bucket.
+fn write_chart(out: &mut String, durations: &DurationSummary) {
+    const BAR_WIDTH: usize = 80;
+    const HEIGHT: usize = 120;
+
+    let buckets = &durations.histogram;
+    let max = buckets.iter().map(|bucket| bucket.count).max();
+    let max = max.unwrap_or(0).max(1);
+
+    writeln!(
+        out,
+        "<svg width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Tests per duration\">",
+        buckets.len() * BAR_WIDTH,
+        HEIGHT + 40
+    )
+    .unwrap();
+    for (i, bucket) in buckets.iter().enumerate() {
+        let count = bucket.count;
+        let height = count * HEIGHT / max;
+        let x = i * BAR_WIDTH;
+        writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{height}\"/>\
+            <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{count}</text>\
+            <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            x + 8,
+            HEIGHT + 16 - height,
+            BAR_WIDTH - 16,
+            x + BAR_WIDTH / 2,
+            HEIGHT + 12 - height,
+            x + BAR_WIDTH / 2,
+            HEIGHT + 34,
+            escape(&bucket_label(bucket))
+        )
+        .unwrap();
+    }
+    writeln!(out, "</svg>").unwrap();
+}
+
+fn write_table(out: &mut String, tests: Vec<&TestResult>) {
+    writeln!(
+        out,
+        "<table class=\"tests\">\n<thead><tr><th>Status</th><th>Duration</th><th>Test</th>\
+        </tr></thead>\n<tbody>"
+    )
+    .unwrap();
+    for test in tests {
+        let (class, status) = match test.status {
+            TestStatus::Passed if test.attempts > 1 => ("flaky", "FLAKY"),
+            TestStatus::Passed if test.expected_failure.is_some() => ("passed", "XFAIL"),
+            TestStatus::Passed => ("passed", "PASS"),
+            _ if test.expected_failure.is_some() => ("failed", "XPASS"),
+            _ => ("failed", "FAIL"),
+        };
+        let name = escape(&test.name);
+        let name = match test.status {
+            TestStatus::Passed => name,
+            // Failed tests link to their output.
+            _ => format!("<a href=\"#{name}\">{name}</a>"),
+        };
+        writeln!(
+            out,
+            "<tr><td class=\"{class}\">{status}</td>\
+            <td class=\"duration\" data-sort=\"{0}\">{0:.3}s</td><td>{name}</td></tr>",
+            test.duration_secs
+        )
+        .unwrap();
+    }
+    writeln!(out, "</tbody>\n</table>").unwrap();
+}
+
+/// Opens the report at `path` in the default browser, without waiting for
+/// it.
+pub(crate) fn open(path: &std::path::Path) {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        // The empty argument is the title of the window.
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    if let Err(e) = command.arg(path).spawn() {
+        eprintln!("warning: failed to open {}: {e}", path.display());
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,63 @@
+//! `--merge-into`: merges the report of the run into a combined report, so
+//! that the test binaries of a crate end up with one report between them.
+//!
+//! The records of the run are kept until it finishes, then merged into the
+//! file with [`CombinedReport`], replacing the results of an earlier run of
+//! the same binary. Binaries run one after another under `cargo test`, so
+//! they never write the file at the same time.
+
+use std::{io, path::PathBuf};
+
+use super::{aggregator::WriteEventError, checkpoint::write_atomically, TestEvent};
+use crate::{cache::binary_name, CombinedReport};
+
+#[derive(Debug)]
+pub(crate) struct MergeInto {
+    path: PathBuf,
+    binary: String,
+    /// The records of the run so far, one per line.
+    lines: Vec<u8>,
+}
+
+impl MergeInto {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            binary: binary_name(),
+            lines: vec![],
+        }
+    }
+
+    /// Adds the records of `event`, and merges them into the file once the
+    /// run finishes.
+    pub(crate) fn push(
+        &mut self,
+        event: &TestEvent<'_>,
+        lines: &[u8],
+    ) -> Result<(), WriteEventError> {
+        self.lines.extend_from_slice(lines);
+        if !matches!(event, TestEvent::RunFinished { .. }) {
+            return Ok(());
+        }
+
+        let fs_error = |error| WriteEventError::Fs {
+            file: self.path.clone(),
+            error,
+        };
+        let earlier = match std::fs::read_to_string(&self.path) {
+            Ok(earlier) => earlier,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(fs_error(e)),
+        };
+        let run = String::from_utf8_lossy(&self.lines);
+        let mut report = CombinedReport::default();
+        report
+            .merge(&earlier, "")
+            .and_then(|()| report.merge(&run, &self.binary))
+            .map_err(|e| fs_error(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+
+        let mut contents = vec![];
+        report.write_json(&mut contents).map_err(fs_error)?;
+        write_atomically(&self.path, &contents)
+    }
+}
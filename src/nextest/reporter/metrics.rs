@@ -0,0 +1,131 @@
+//! The final counters of a run in the Prometheus text exposition format.
+//!
+//! With `--metrics-file`, the counters are written once the run finishes, so
+//! that a CI sidecar can push them to monitoring without parsing the report.
+//! The file is replaced atomically like a checkpoint, so a sidecar that polls
+//! it never reads half of it.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::PathBuf,
+    time::Duration,
+};
+
+use super::{aggregator::WriteEventError, checkpoint::write_atomically, TestEvent};
+use crate::nextest::RunStats;
+
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    path: PathBuf,
+    /// The tags of every test in the run.
+    tags: HashMap<String, Vec<String>>,
+    /// The time spent in tests per tag, with `""` for untagged tests.
+    test_durations: BTreeMap<String, Duration>,
+    setup_duration: Duration,
+}
+
+impl Metrics {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tags: HashMap::new(),
+            test_durations: BTreeMap::new(),
+            setup_duration: Duration::ZERO,
+        }
+    }
+
+    /// Adds `event` to the counters, and writes them once the run finishes.
+    pub(crate) fn push(&mut self, event: &TestEvent<'_>) -> Result<(), WriteEventError> {
+        match event {
+            TestEvent::RunStarted { test_list, .. } => {
+                self.tags = test_list
+                    .tests
+                    .iter()
+                    .map(|test| (test.name.clone(), test.tags.clone()))
+                    .collect();
+            }
+            TestEvent::SetupFinished { duration, .. } => self.setup_duration += *duration,
+            TestEvent::TestFinished {
+                test_instance,
+                run_status,
+                ..
+            } => match self.tags.get(&test_instance.name) {
+                Some(tags) if !tags.is_empty() => {
+                    for tag in tags {
+                        *self.test_durations.entry(tag.clone()).or_default() +=
+                            run_status.time_taken;
+                    }
+                }
+                _ => {
+                    *self.test_durations.entry(String::new()).or_default() += run_status.time_taken
+                }
+            },
+            TestEvent::RunFinished {
+                elapsed, run_stats, ..
+            } => write_atomically(&self.path, self.render(*elapsed, run_stats).as_bytes())?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&self, elapsed: Duration, stats: &RunStats) -> String {
+        let mut out = String::new();
+        header(
+            &mut out,
+            "tests_total",
+            "Tests in the run, by result. Skipped includes filtered out and ignored tests.",
+        );
+        for (result, count) in [
+            ("passed", stats.passed),
+            ("failed", stats.failed),
+            ("timed_out", stats.timed_out),
+            ("skipped", stats.skipped),
+            ("not_run", stats.not_run),
+        ] {
+            writeln!(out, "async_test_tests_total{{result=\"{result}\"}} {count}").unwrap();
+        }
+
+        header(&mut out, "failed_total", "Tests that failed or timed out.");
+        writeln!(
+            out,
+            "async_test_failed_total {}",
+            stats.failed + stats.timed_out
+        )
+        .unwrap();
+
+        header(
+            &mut out,
+            "duration_seconds",
+            "Time spent by kind. A test counts towards each of its tags.",
+        );
+        let mut duration = |kind: &str, tag: &str, duration: Duration| {
+            writeln!(
+                out,
+                "async_test_duration_seconds{{kind=\"{kind}\",tag=\"{}\"}} {}",
+                escape(tag),
+                duration.as_secs_f64()
+            )
+            .unwrap()
+        };
+        duration("run", "", elapsed);
+        duration("setup", "", self.setup_duration);
+        for (tag, test_duration) in &self.test_durations {
+            duration("test", tag, *test_duration);
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    writeln!(out, "# HELP async_test_{name} {help}").unwrap();
+    writeln!(out, "# TYPE async_test_{name} counter").unwrap();
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -6,6 +6,16 @@
 //! The main structure in this module is [`TestReporter`].
 
 mod aggregator;
+mod checkpoint;
+mod gaps;
+#[cfg(test)]
+mod golden;
+mod html;
+mod merge_into;
+mod metrics;
+mod slow;
+mod timeline;
+mod watchdog;
 // use crate::{
 //     config::NextestProfile,
 //     errors::WriteEventError,
@@ -25,13 +35,35 @@ use serde::Deserialize;
 use std::{
     borrow::Cow,
     cmp::Reverse,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Write as _},
     io,
     io::{BufWriter, Write},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
     time::{Duration, SystemTime},
 };
 
-use self::aggregator::{EventAggregator, WriteEventError};
+use self::{
+    aggregator::{EventAggregator, NextestJunitConfig, WriteEventError},
+    gaps::Gaps,
+    watchdog::{AlternateSink, Watchdog},
+};
+pub(crate) use self::{
+    aggregator::junit_message,
+    checkpoint::Checkpoint,
+    html::{open as open_html_report, HtmlReport},
+    merge_into::MergeInto,
+    metrics::Metrics,
+    slow::SlowBatcher,
+    timeline::Timeline,
+};
+
+use crate::{
+    config::RunConfig,
+    report::{self, Determinism, Diagnostic, DiagnosticLevel},
+    report_time::{TimeLevel, TimeThresholds},
+};
 
 use super::{
     ExecuteStatus, ExecutionDescription, ExecutionResult, MismatchReason, RunStats, TestInstance,
@@ -118,6 +150,9 @@ pub enum FinalStatusLevel {
     /// Only output test failures.
     Fail,
 
+    /// Output tests that passed on a retry, and all variants above.
+    Flaky,
+
     /// Output information about slow tests, and all variants above.
     Slow,
 
@@ -141,7 +176,10 @@ pub enum ReporterOutput<'a> {
     Stderr,
 
     /// Write output to a buffer.
-    Buffer(&'a mut dyn io::Write),
+    Buffer(&'a mut (dyn io::Write + Send)),
+
+    /// Pass the events of the JSON stream to a custom reporter.
+    Custom(Box<dyn report::Reporter + 'a>),
 }
 
 /// Test reporter builder.
@@ -154,6 +192,12 @@ pub struct TestReporterBuilder {
     // verbose: bool,
     hide_progress_bar: bool,
     imitate_cargo: bool,
+    stall_timeout: Option<Duration>,
+    json: bool,
+    json_pretty: bool,
+    summary_line: bool,
+    name_width: Option<usize>,
+    junit_path: Option<PathBuf>,
 }
 
 impl TestReporterBuilder {
@@ -199,6 +243,50 @@ impl TestReporterBuilder {
         self.imitate_cargo = imitate_cargo;
         self
     }
+
+    /// Warns on an alternate sink if writing a single event takes longer than
+    /// this. `None` disables the check.
+    pub fn set_stall_timeout(&mut self, stall_timeout: Option<Duration>) -> &mut Self {
+        self.stall_timeout = stall_timeout;
+        self
+    }
+
+    /// Writes events as [`report::EventRecord`]s, one per line. Without a
+    /// buffer, they are written to stdout.
+    pub fn set_json(&mut self, json: bool) -> &mut Self {
+        self.json = json;
+        self
+    }
+
+    /// Indents the JSON events over several lines, see [`set_json`].
+    ///
+    /// [`set_json`]: Self::set_json
+    pub fn set_json_pretty(&mut self, json_pretty: bool) -> &mut Self {
+        self.json_pretty = json_pretty;
+        self
+    }
+
+    /// Writes nothing but a single line at the end of the run, like
+    /// `OK 3 passed, 0 failed, 1 skipped in 1.2s`. Without a buffer, it is
+    /// written to stdout.
+    pub fn set_summary_line(&mut self, summary_line: bool) -> &mut Self {
+        self.summary_line = summary_line;
+        self
+    }
+
+    /// Shortens test names in status lines to this many characters, keeping
+    /// their start and end. The final summary always shows full names.
+    pub fn set_name_width(&mut self, name_width: Option<usize>) -> &mut Self {
+        self.name_width = name_width;
+        self
+    }
+
+    /// Writes a JUnit XML report to this path once the run finishes, next to
+    /// the regular output.
+    pub fn set_junit_path(&mut self, junit_path: Option<PathBuf>) -> &mut Self {
+        self.junit_path = junit_path;
+        self
+    }
 }
 
 impl TestReporterBuilder {
@@ -209,7 +297,12 @@ impl TestReporterBuilder {
         output: ReporterOutput<'a>,
     ) -> TestReporter<'a> {
         let styles = Box::default();
-        let aggregator = EventAggregator::new();
+        let aggregator = match &self.junit_path {
+            Some(path) => {
+                EventAggregator::new_junit(NextestJunitConfig::new(path.clone(), "async-test"))
+            }
+            None => EventAggregator::new(),
+        };
 
         let status_level = self.status_level.unwrap_or(StatusLevel::Pass);
         let final_status_level = self.final_status_level.unwrap_or(FinalStatusLevel::Slow);
@@ -217,7 +310,31 @@ impl TestReporterBuilder {
         let force_success_output = self.success_output;
         let force_failure_output = self.failure_output;
 
+        // Warn wherever the regular output does not go.
+        let watchdog = self.stall_timeout.map(|timeout| {
+            let sink = match output {
+                ReporterOutput::Stderr if !self.json && !self.summary_line => AlternateSink::Stdout,
+                _ => AlternateSink::Stderr,
+            };
+            Watchdog::spawn(timeout, sink)
+        });
+
         let stderr = match output {
+            ReporterOutput::Custom(reporter) => ReporterStderrImpl::Custom(reporter),
+            ReporterOutput::Stderr if self.summary_line => {
+                ReporterStderrImpl::SummaryLine(Box::new(io::stdout()))
+            }
+            ReporterOutput::Buffer(buf) if self.summary_line => {
+                ReporterStderrImpl::SummaryLine(Box::new(buf))
+            }
+            ReporterOutput::Stderr if self.json => ReporterStderrImpl::Json {
+                out: Box::new(io::stdout()),
+                pretty: self.json_pretty,
+            },
+            ReporterOutput::Buffer(buf) if self.json => ReporterStderrImpl::Json {
+                out: Box::new(buf),
+                pretty: self.json_pretty,
+            },
             // ReporterStderr::Terminal if is_ci::uncached() => {
             //     // Some CI environments appear to pretend to be a terminal. Disable the progress bar
             //     // in these environments.
@@ -270,9 +387,24 @@ impl TestReporterBuilder {
                 styles,
                 cancel_status: None,
                 final_outputs: DebugIgnore(vec![]),
+                sample_seed: None,
+                sampled: vec![],
+                nondeterministic: vec![],
+                over_expected: vec![],
+                name_width: self.name_width,
+                tag_summary: None,
+                report_fixtures: false,
+                gaps: None,
+                github: false,
+                verbose: false,
+                tasks: 0,
+                disabled_sinks: vec![],
+                report_time: None,
             },
             stderr,
             metadata_reporter: aggregator,
+            watchdog,
+            observers: vec![],
         }
     }
 }
@@ -281,7 +413,15 @@ enum ReporterStderrImpl<'a> {
     StderrWithBar(ProgressBar),
     StderrWithoutBar,
     ImitateCargo,
-    Buffer(&'a mut dyn std::io::Write),
+    Buffer(&'a mut (dyn std::io::Write + Send)),
+    Json {
+        out: Box<dyn std::io::Write + Send + 'a>,
+        pretty: bool,
+    },
+    SummaryLine(Box<dyn std::io::Write + Send + 'a>),
+    Custom(Box<dyn report::Reporter + 'a>),
+    /// The output panicked, nothing is written anymore.
+    Disabled,
 }
 
 /// Functionality to report test results to stderr and JUnit
@@ -289,6 +429,10 @@ pub struct TestReporter<'a> {
     inner: TestReporterImpl,
     stderr: ReporterStderrImpl<'a>,
     metadata_reporter: EventAggregator<'a>,
+    watchdog: Option<Watchdog>,
+    /// Get the events next to the regular output, see `run_with_observer`
+    /// and `--log-events`. Each is named for the warning if it panics.
+    observers: Vec<(&'static str, Box<dyn report::Reporter + 'a>)>,
 }
 
 impl<'a> TestReporter<'a> {
@@ -297,8 +441,49 @@ impl<'a> TestReporter<'a> {
         self.inner.styles.colorize();
     }
 
+    /// Appends every event to `file` as it happens, see `--run-log`.
+    pub(crate) fn set_run_log(&mut self, file: std::fs::File) {
+        self.metadata_reporter.set_run_log(file);
+    }
+
+    /// Writes snapshots of the JSON report while the run goes on, see
+    /// `--checkpoint`.
+    pub(crate) fn set_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.metadata_reporter.set_checkpoint(checkpoint);
+    }
+
+    /// Writes the final counters to a file, see `--metrics-file`.
+    pub(crate) fn set_metrics(&mut self, metrics: Metrics) {
+        self.metadata_reporter.set_metrics(metrics);
+    }
+
+    /// Writes an HTML page with the results to a file, see `--html-report`.
+    pub(crate) fn set_html_report(&mut self, html: HtmlReport) {
+        self.metadata_reporter.set_html_report(html);
+    }
+
+    /// Writes a timeline of the run to a file, see `--trace-timeline`.
+    pub(crate) fn set_timeline(&mut self, timeline: Timeline) {
+        self.metadata_reporter.set_timeline(timeline);
+    }
+
+    /// Merges the results into a combined report, see `--merge-into`.
+    pub(crate) fn set_merge_into(&mut self, merge_into: MergeInto) {
+        self.metadata_reporter.set_merge_into(merge_into);
+    }
+
+    /// Passes every event to `observer` as well.
+    pub(crate) fn add_observer(
+        &mut self,
+        what: &'static str,
+        observer: Box<dyn report::Reporter + 'a>,
+    ) {
+        self.observers.push((what, observer));
+    }
+
     /// Report a test event.
     pub(crate) fn report_event(&mut self, event: TestEvent<'a>) -> Result<(), WriteEventError> {
+        let _guard = self.watchdog.as_ref().map(Watchdog::guard);
         self.write_event(event)
     }
 
@@ -308,7 +493,57 @@ impl<'a> TestReporter<'a> {
 
     /// Report this test event to the given writer.
     fn write_event(&mut self, event: TestEvent<'a>) -> Result<(), WriteEventError> {
+        self.metadata_reporter.log_event(&event)?;
+
+        // A panic while writing, e.g. in a custom reporter, disables the sink
+        // instead of ending the run.
+        match catch_unwind(AssertUnwindSafe(|| self.write_output(&event))) {
+            Ok(result) => result?,
+            Err(payload) => {
+                let what = match self.stderr {
+                    ReporterStderrImpl::Custom(_) => "the reporter",
+                    _ => "the output",
+                };
+                if let ReporterStderrImpl::StderrWithBar(progress_bar) = &self.stderr {
+                    progress_bar.finish_and_clear();
+                }
+                self.stderr = ReporterStderrImpl::Disabled;
+                // Nothing else is written to the output anymore.
+                eprintln!(
+                    "warning: {what} panicked and is disabled for the rest of the run: {}",
+                    describe_sink_panic(&*payload)
+                );
+            }
+        }
+        if !self.observers.is_empty() {
+            let events = report_events(&event);
+            let disabled_sinks = &mut self.inner.disabled_sinks;
+            self.observers.retain_mut(|(what, observer)| {
+                let observed = catch_unwind(AssertUnwindSafe(|| {
+                    for event in &events {
+                        observer.report(event);
+                    }
+                }));
+                let Err(payload) = observed else {
+                    return true;
+                };
+                disabled_sinks.push(format!(
+                    "{what} panicked and got no events after that: {}",
+                    describe_sink_panic(&*payload)
+                ));
+                false
+            });
+        }
+        self.metadata_reporter.write_event(event)?;
+        Ok(())
+    }
+
+    /// Writes `event` to the output.
+    fn write_output(&mut self, event: &TestEvent<'a>) -> Result<(), WriteEventError> {
         match &mut self.stderr {
+            // The progress bar already shows the counts on a terminal.
+            ReporterStderrImpl::StderrWithBar(progress_bar)
+                if matches!(event, TestEvent::RunProgress { .. }) && !progress_bar.is_hidden() => {}
             ReporterStderrImpl::StderrWithBar(progress_bar) => {
                 // Write to a string that will be printed as a log line.
                 let mut buf: Vec<u8> = Vec::new();
@@ -337,14 +572,34 @@ impl<'a> TestReporter<'a> {
                 let mut stdout = BufWriter::new(std::io::stdout());
 
                 match &event {
-                    TestEvent::RunStarted { .. } => {}
+                    TestEvent::RunStarted { diagnostics, .. } => {
+                        for diagnostic in *diagnostics {
+                            let level = match diagnostic.level {
+                                DiagnosticLevel::Warning => "warning",
+                                DiagnosticLevel::Note | DiagnosticLevel::Unknown => "note",
+                            };
+                            writeln!(
+                                stderr,
+                                "{level}: {}: {}",
+                                diagnostic.builder, diagnostic.message
+                            )
+                            .map_err(WriteEventError::Io)?;
+                        }
+                    }
+                    TestEvent::CollectionError { builder, message } => {
+                        writeln!(
+                            stderr,
+                            "error: collecting tests in {builder} panicked\n{message}\n"
+                        )
+                        .map_err(WriteEventError::Io)?;
+                    }
                     TestEvent::SetupFinished { .. } => {}
                     TestEvent::TestFinished {
                         test_instance,
                         run_status,
                         ..
                     } => {
-                        if run_status.result != ExecutionResult::Pass {
+                        if !run_status.result.is_success() {
                             self.inner.final_outputs.push((
                                 test_instance.clone(),
                                 FinalOutput::Executed {
@@ -353,7 +608,7 @@ impl<'a> TestReporter<'a> {
                                 },
                             ))
                         }
-                        let s = match run_status.result == ExecutionResult::Pass {
+                        let s = match run_status.result.is_success() {
                             true => "ok",
                             false => "FAILED",
                         };
@@ -378,6 +633,14 @@ impl<'a> TestReporter<'a> {
                                 {
                                     // writeln!(stderr, "---- {} stdout ----", instance.name)
                                     //     .map_err(WriteEventError::Io)?;
+                                    if let Some(description) = &instance.description {
+                                        writeln!(stderr, "{description}\n")
+                                            .map_err(WriteEventError::Io)?;
+                                    }
+                                    for link in &instance.links {
+                                        writeln!(stderr, "{}: {}", link.label, link.url)
+                                            .map_err(WriteEventError::Io)?;
+                                    }
                                     writeln!(stderr, "{msg}\n").map_err(WriteEventError::Io)?;
                                     stderr.flush().map_err(WriteEventError::Io)?;
                                 }
@@ -402,12 +665,21 @@ impl<'a> TestReporter<'a> {
                         // )
                         // .map_err(WriteEventError::Io)?;
                     }
+                    TestEvent::RunCompared { .. } | TestEvent::FixturesUsed { .. } => {
+                        self.inner
+                            .write_event_impl(&event, &mut stderr)
+                            .map_err(WriteEventError::Io)?;
+                    }
                     TestEvent::TestStarted { .. } => {}
                     TestEvent::TestSlow { .. } => {}
+                    TestEvent::TestsSlow { .. } => {}
+                    TestEvent::TestAttemptFailedWillRetry { .. } => {}
+                    TestEvent::RunProgress { .. } => {}
                     TestEvent::TestSkipped { .. } => {}
                     TestEvent::RunBeginCancel { .. } => {}
                     TestEvent::RunPaused { .. } => {}
                     TestEvent::RunContinued { .. } => {}
+                    TestEvent::ConcurrencyReduced { .. } => {}
                 }
 
                 stdout.flush().map_err(WriteEventError::Io)?;
@@ -418,12 +690,225 @@ impl<'a> TestReporter<'a> {
                     .write_event_impl(&event, buf)
                     .map_err(WriteEventError::Io)?;
             }
+            ReporterStderrImpl::Json { out, pretty } => {
+                for event in report_events(&event) {
+                    report::write_json(&mut *out, &report::EventRecord::new(event), *pretty)
+                        .map_err(WriteEventError::Io)?;
+                }
+                out.flush().map_err(WriteEventError::Io)?;
+            }
+            ReporterStderrImpl::Custom(reporter) => {
+                for event in report_events(&event) {
+                    reporter.report(&event);
+                }
+            }
+            ReporterStderrImpl::SummaryLine(out) => {
+                if let TestEvent::RunFinished {
+                    elapsed, run_stats, ..
+                } = &event
+                {
+                    writeln!(out, "{}", summary_line(*elapsed, run_stats))
+                        .and_then(|()| out.flush())
+                        .map_err(WriteEventError::Io)?;
+                }
+            }
+            ReporterStderrImpl::Disabled => {}
         }
-        self.metadata_reporter.write_event(event)?;
         Ok(())
     }
 }
 
+/// The message and location of a panic of a sink.
+fn describe_sink_panic(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = crate::payload::message(payload).unwrap_or("Box<dyn Any>".into());
+    let failure = crate::describe_panic(&message, "async_test::nextest::reporter");
+    match failure.location {
+        Some(location) => format!("{message}, {location}"),
+        None => message.into_owned(),
+    }
+}
+
+/// Renders `events` the way they are written to a logfile: without colors,
+/// progress bar or stall warnings.
+///
+/// This drives the same code as a real run, so it is the way to test output
+/// formatting without running any tests.
+pub(crate) fn render_events<'e>(
+    test_list: &TestList,
+    events: impl IntoIterator<Item = TestEvent<'e>>,
+) -> String {
+    let mut out = Vec::new();
+    let mut reporter = TestReporterBuilder::default()
+        .set_stall_timeout(None)
+        .build(test_list, ReporterOutput::Buffer(&mut out));
+    for event in events {
+        reporter.report_event(event).unwrap();
+    }
+    drop(reporter);
+    String::from_utf8(out).unwrap()
+}
+
+/// The names of the tests that run because they are in the sample, or none
+/// without `--sample`.
+fn sampled_names(test_list: &TestList, config: &RunConfig) -> Vec<String> {
+    match config.sample {
+        Some(_) => test_list
+            .tests
+            .iter()
+            .map(|test| test.name.clone())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Converts an event into the stable events of the JSON stream.
+fn report_events(event: &TestEvent<'_>) -> Vec<report::Event> {
+    let event = match event {
+        TestEvent::RunStarted {
+            test_list,
+            config,
+            diagnostics,
+        } => report::Event::RunStarted {
+            test_count: test_list.run_count(),
+            skip_count: test_list.skip_count(),
+            diagnostics: diagnostics.to_vec(),
+            sample_seed: config.sample.map(|sample| sample.seed()),
+            sampled: sampled_names(test_list, config),
+            shuffle_seed: config.shuffle.map(|shuffle| shuffle.seed()),
+            env_file_keys: config.env_file_keys.clone(),
+        },
+        TestEvent::CollectionError { builder, message } => {
+            report::Event::CollectionError(report::CollectionError {
+                builder: builder.clone(),
+                message: message.clone(),
+            })
+        }
+        TestEvent::SetupFinished {
+            test_instance,
+            duration,
+            ..
+        } => report::Event::SetupFinished {
+            name: test_instance.name.clone(),
+            duration_secs: duration.as_secs_f64(),
+        },
+        TestEvent::TestSlow {
+            test_instance,
+            elapsed,
+            ..
+        } => report::Event::TestSlow {
+            name: test_instance.name.clone(),
+            elapsed_secs: elapsed.as_secs_f64(),
+        },
+        // Batching is disabled for the JSON stream, so every tick arrives as
+        // a `TestSlow` of its own.
+        TestEvent::TestsSlow { slowest, .. } => {
+            return slowest
+                .iter()
+                .map(|(test_instance, elapsed)| report::Event::TestSlow {
+                    name: test_instance.name.clone(),
+                    elapsed_secs: elapsed.as_secs_f64(),
+                })
+                .collect()
+        }
+        TestEvent::TestSkipped {
+            test_instance,
+            reason,
+            message,
+        } => report::Event::TestSkipped(report::SkippedTest {
+            name: test_instance.name.clone(),
+            reason: match reason {
+                MismatchReason::Ignored => report::SkipReason::Ignored,
+                MismatchReason::String | MismatchReason::Expression => report::SkipReason::Filter,
+                MismatchReason::Tag => report::SkipReason::Tag,
+                MismatchReason::Partition => report::SkipReason::Partition,
+                MismatchReason::Cfg => report::SkipReason::Cfg,
+                MismatchReason::Unavailable => report::SkipReason::Unavailable,
+                MismatchReason::Offline => report::SkipReason::Offline,
+                MismatchReason::Budget => report::SkipReason::Budget,
+                MismatchReason::FailFast => report::SkipReason::FailFast,
+                MismatchReason::RunTimeout => report::SkipReason::RunTimeout,
+                MismatchReason::Canceled => report::SkipReason::Canceled,
+                MismatchReason::Interrupted => report::SkipReason::Interrupted,
+            },
+            message: message.clone(),
+        }),
+        TestEvent::TestFinished {
+            test_instance,
+            run_status,
+            ..
+        } => report::Event::TestFinished(report::TestResult {
+            name: test_instance.name.clone(),
+            status: match run_status.result.is_success() {
+                true => report::TestStatus::Passed,
+                false => report::TestStatus::Failed,
+            },
+            duration_secs: run_status.time_taken.as_secs_f64(),
+            slow: run_status.is_slow,
+            output: run_status.output.clone(),
+            failures: run_status.failures.clone(),
+            description: test_instance.description.clone(),
+            links: test_instance.links.clone(),
+            expected_failure: test_instance.expected_failure.clone(),
+            iterations: run_status.iterations.clone(),
+            determinism: run_status.determinism,
+            attempts: run_status.attempts,
+            suspended_secs: run_status
+                .suspended
+                .map(|suspended| suspended.as_secs_f64()),
+            queued_secs: run_status.queued.as_secs_f64(),
+            setup_wait_secs: run_status.setup_wait.as_secs_f64(),
+            expected_duration_secs: run_status
+                .expected_duration
+                .map(|expected| expected.as_secs_f64()),
+            meta: run_status.meta.clone(),
+        }),
+        TestEvent::ConcurrencyReduced { reduction } => {
+            report::Event::ConcurrencyReduced(reduction.clone())
+        }
+        TestEvent::RunCompared { comparison } => report::Event::Comparison(comparison.clone()),
+        TestEvent::FixturesUsed { fixtures } => report::Event::FixtureUsage {
+            fixtures: fixtures.clone(),
+        },
+        TestEvent::RunFinished {
+            elapsed,
+            run_stats,
+            durations,
+            ..
+        } => report::Event::RunFinished(report::RunSummary {
+            duration_secs: elapsed.as_secs_f64(),
+            test_count: run_stats.initial_run_count,
+            passed: run_stats.passed,
+            failed: run_stats.failed,
+            skipped: run_stats.skipped,
+            collection_errors: run_stats.collection_errors,
+            filtered_out: run_stats.filtered_out,
+            ignored: run_stats.ignored,
+            sampled_out: run_stats.sampled_out,
+            offline: run_stats.offline,
+            not_run: run_stats.not_run,
+            timed_out: run_stats.timed_out,
+            flaky: run_stats.flaky,
+            queue_wait_secs: run_stats.queue_wait.as_secs_f64(),
+            durations: durations.clone(),
+        }),
+        TestEvent::TestStarted { test_instance, .. } => report::Event::TestStarted {
+            name: test_instance.name.clone(),
+        },
+        TestEvent::RunBeginCancel {
+            reason, message, ..
+        } => report::Event::RunCanceled {
+            reason: message
+                .clone()
+                .unwrap_or_else(|| reason.as_str().to_owned()),
+        },
+        TestEvent::RunPaused { .. }
+        | TestEvent::RunContinued { .. }
+        | TestEvent::TestAttemptFailedWillRetry { .. }
+        | TestEvent::RunProgress { .. } => return vec![],
+    };
+    vec![event]
+}
+
 fn update_progress_bar(event: &TestEvent<'_>, styles: &Styles, progress_bar: &mut ProgressBar) {
     match event {
         TestEvent::TestStarted {
@@ -500,7 +985,7 @@ fn write_summary_str(run_stats: &RunStats, styles: &Styles, out: &mut String) ->
         "passed".style(styles.pass)
     )?;
 
-    if run_stats.passed_slow > 0 {
+    if run_stats.passed_slow > 0 || run_stats.expected_failures > 0 || run_stats.flaky > 0 {
         let mut text = Vec::with_capacity(3);
         if run_stats.passed_slow > 0 {
             text.push(format!(
@@ -509,6 +994,20 @@ fn write_summary_str(run_stats: &RunStats, styles: &Styles, out: &mut String) ->
                 "slow".style(styles.skip),
             ));
         }
+        if run_stats.expected_failures > 0 {
+            text.push(format!(
+                "{} {}",
+                run_stats.expected_failures.style(styles.count),
+                "expected to fail".style(styles.skip),
+            ));
+        }
+        if run_stats.flaky > 0 {
+            text.push(format!(
+                "{} {}",
+                run_stats.flaky.style(styles.count),
+                "flaky".style(styles.flaky),
+            ));
+        }
         write!(out, " ({})", text.join(", "))?;
     }
     write!(out, ", ")?;
@@ -522,6 +1021,19 @@ fn write_summary_str(run_stats: &RunStats, styles: &Styles, out: &mut String) ->
         )?;
     }
 
+    if run_stats.collection_errors > 0 {
+        write!(
+            out,
+            "{} {}, ",
+            run_stats.collection_errors.style(styles.count),
+            match run_stats.collection_errors {
+                1 => "collection error",
+                _ => "collection errors",
+            }
+            .style(styles.fail),
+        )?;
+    }
+
     if run_stats.timed_out > 0 {
         write!(
             out,
@@ -531,19 +1043,90 @@ fn write_summary_str(run_stats: &RunStats, styles: &Styles, out: &mut String) ->
         )?;
     }
 
-    write!(
-        out,
-        "{} {}",
-        run_stats.skipped.style(styles.count),
-        "skipped".style(styles.skip),
-    )?;
+    if run_stats.not_run > 0 {
+        write!(
+            out,
+            "{} {}, ",
+            run_stats.not_run.style(styles.count),
+            if run_stats.interrupted {
+                "not run (interrupted)"
+            } else if run_stats.run_timed_out {
+                "not run (run timeout)"
+            } else if run_stats.canceled {
+                "not run (canceled)"
+            } else if run_stats.failed_fast {
+                "not run (fail-fast)"
+            } else {
+                "not run (budget exceeded)"
+            }
+            .style(styles.skip),
+        )?;
+    }
 
-    Ok(())
+    let skipped = skipped_parts(
+        run_stats.filtered_out,
+        run_stats.ignored,
+        run_stats.offline,
+        run_stats.skipped - run_stats.filtered_out - run_stats.ignored - run_stats.offline,
+        styles,
+    );
+    match skipped.is_empty() {
+        true => write!(
+            out,
+            "{} {}",
+            0.style(styles.count),
+            "skipped".style(styles.skip)
+        ),
+        false => write!(out, "{}", skipped.join(", ")),
+    }
+}
+
+/// Waits for a task permit or for fixtures shorter than this are only the
+/// time it takes to spawn a test, and are not shown.
+const QUEUE_NOISE: Duration = Duration::from_millis(1);
+
+/// The line of `--format summary-line`, e.g.
+/// `OK 3 passed, 0 failed, 1 skipped in 1.2s`. Timeouts count as failed, and
+/// any failure or collection error makes the run `CRITICAL`.
+fn summary_line(elapsed: Duration, run_stats: &RunStats) -> String {
+    let failed = run_stats.failed + run_stats.timed_out;
+    let status = match failed + run_stats.collection_errors {
+        0 => "OK",
+        _ => "CRITICAL",
+    };
+    format!(
+        "{status} {} passed, {failed} failed, {} skipped in {:.1}s",
+        run_stats.passed,
+        run_stats.skipped,
+        elapsed.as_secs_f64()
+    )
+}
+
+/// Describes the tests that did not run, like nextest: tests that were never
+/// considered are "filtered out", and only those skipped at runtime, e.g. for
+/// an unmet `cfg`, are "skipped". Counts of zero are left out.
+fn skipped_parts(
+    filtered_out: usize,
+    ignored: usize,
+    offline: usize,
+    skipped: usize,
+    styles: &Styles,
+) -> Vec<String> {
+    [
+        (filtered_out, "filtered out"),
+        (ignored, "ignored"),
+        (offline, "offline"),
+        (skipped, "skipped"),
+    ]
+    .into_iter()
+    .filter(|&(count, _)| count > 0)
+    .map(|(count, label)| format!("{} {}", count.style(styles.count), label.style(styles.skip)))
+    .collect()
 }
 
 #[derive(Debug)]
 enum FinalOutput {
-    Skipped(MismatchReason),
+    Skipped(MismatchReason, Option<String>),
     Executed {
         run_status: ExecuteStatus,
         test_output_display: TestOutputDisplay,
@@ -567,6 +1150,73 @@ struct TestReporterImpl {
     styles: Box<Styles>,
     cancel_status: Option<CancelReason>,
     final_outputs: DebugIgnore<Vec<(TestInstance, FinalOutput)>>,
+    /// The seed of `--sample`, repeated in the summary.
+    sample_seed: Option<u64>,
+    /// The tests in the sample, listed in the summary with `--verbose`.
+    sampled: Vec<String>,
+    /// Tests whose runs under `--verify-deterministic` had different
+    /// outcomes, listed after the summary.
+    nondeterministic: Vec<(TestInstance, Determinism)>,
+    /// Tests that took longer than their expected duration, with how long
+    /// they took and were expected to take, listed after the summary.
+    over_expected: Vec<(TestInstance, Duration, Duration)>,
+    name_width: Option<usize>,
+    /// Results per tag, with `--summary-by-tag`.
+    tag_summary: Option<TagSummary>,
+    /// Whether fixture usage is printed, with `--report-fixtures`.
+    report_fixtures: bool,
+    /// The tests and setups of the run, with `--report-gaps`.
+    gaps: Option<Gaps>,
+    /// Whether failures are annotated for GitHub Actions, with `--format
+    /// github`.
+    github: bool,
+    /// Whether the queue time of tests is printed, with `--verbose`.
+    verbose: bool,
+    /// The number of tests that may run at once, see `--test-tasks`.
+    tasks: usize,
+    /// Why sinks next to the output were disabled, listed after the summary.
+    disabled_sinks: Vec<String>,
+    /// Durations over these times are highlighted, with `--report-time`.
+    report_time: Option<TimeThresholds>,
+}
+
+/// The number of passed and failed tests with each tag.
+#[derive(Debug, Default)]
+struct TagSummary {
+    /// The tags of every test in the run.
+    tags: HashMap<String, Vec<String>>,
+    /// Passed and failed tests per tag.
+    counts: BTreeMap<String, (usize, usize)>,
+    /// Passed and failed tests without tags.
+    untagged: (usize, usize),
+}
+
+impl TagSummary {
+    fn new(test_list: &TestList) -> Self {
+        Self {
+            tags: test_list
+                .tests
+                .iter()
+                .map(|test| (test.name.clone(), test.tags.clone()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    fn finished(&mut self, name: &str, passed: bool) {
+        let add = |counts: &mut (usize, usize)| match passed {
+            true => counts.0 += 1,
+            false => counts.1 += 1,
+        };
+        match self.tags.get(name) {
+            Some(tags) if !tags.is_empty() => {
+                for tag in tags {
+                    add(self.counts.entry(tag.clone()).or_default());
+                }
+            }
+            _ => add(&mut self.untagged),
+        }
+    }
 }
 
 impl<'a> TestReporterImpl {
@@ -575,8 +1225,31 @@ impl<'a> TestReporterImpl {
         event: &TestEvent<'a>,
         writer: &mut impl Write,
     ) -> io::Result<()> {
+        if let Some(gaps) = &mut self.gaps {
+            gaps.push(event);
+        }
         match event {
-            TestEvent::RunStarted { test_list, .. } => {
+            TestEvent::RunStarted {
+                test_list,
+                config,
+                diagnostics,
+            } => {
+                for diagnostic in *diagnostics {
+                    let (label, style) = match diagnostic.level {
+                        DiagnosticLevel::Warning => ("Warning", self.styles.skip),
+                        DiagnosticLevel::Note | DiagnosticLevel::Unknown => {
+                            ("Note", self.styles.task)
+                        }
+                    };
+                    writeln!(
+                        writer,
+                        "{:>12} {}: {}",
+                        label.style(style),
+                        diagnostic.builder,
+                        diagnostic.message
+                    )?;
+                }
+
                 write!(writer, "{:>12} ", "Starting".style(self.styles.pass))?;
 
                 let count_style = self.styles.count;
@@ -593,12 +1266,57 @@ impl<'a> TestReporterImpl {
                     test_list.run_count().style(count_style),
                 )?;
 
-                let skip_count = test_list.skip_count();
-                if skip_count > 0 {
-                    write!(writer, " ({} skipped)", skip_count.style(count_style))?;
+                let skipped = skipped_parts(
+                    test_list.filtered_out,
+                    test_list.ignored,
+                    test_list.offline,
+                    test_list.skip_count()
+                        - test_list.filtered_out
+                        - test_list.ignored
+                        - test_list.offline,
+                    &self.styles,
+                );
+                if !skipped.is_empty() {
+                    write!(writer, " ({})", skipped.join(", "))?;
                 }
 
                 writeln!(writer)?;
+
+                if config.verbose {
+                    for line in config.lines() {
+                        writeln!(writer, "{:>12} {line}", "Config".style(self.styles.pass))?;
+                    }
+                }
+
+                self.sample_seed = config.sample.map(|sample| sample.seed());
+                if config.verbose {
+                    self.sampled = sampled_names(test_list, config);
+                }
+                self.tag_summary = config.summary_by_tag.then(|| TagSummary::new(test_list));
+                self.report_fixtures = config.report_fixtures;
+                self.gaps = config.report_gaps.then(Gaps::default);
+                self.github = config.github;
+                self.report_time = config.report_time;
+                self.verbose = config.verbose;
+                self.tasks = config.tasks.get();
+
+                if let Some(seed) = config.shuffle.map(|shuffle| shuffle.seed()) {
+                    writeln!(
+                        writer,
+                        "{:>12} starting tests in random order with seed {} (reproduce with \
+                        --shuffle-seed {seed})",
+                        "Shuffle".style(self.styles.skip),
+                        seed.style(count_style),
+                    )?;
+                }
+                if let Some(seed) = config.chaos.map(|chaos| chaos.seed()) {
+                    writeln!(
+                        writer,
+                        "{:>12} injecting delays with seed {} (reproduce with --chaos seed={seed})",
+                        "Chaos".style(self.styles.skip),
+                        seed.style(count_style),
+                    )?;
+                }
             }
             TestEvent::TestStarted { .. } => {}
             TestEvent::TestSlow {
@@ -614,9 +1332,29 @@ impl<'a> TestReporterImpl {
                 }
 
                 self.write_slow_duration(*elapsed, writer)?;
-                self.write_instance(test_instance, writer)?;
+                self.write_short_instance(test_instance, writer)?;
                 writeln!(writer)?;
             }
+            TestEvent::TestsSlow { count, slowest } => {
+                if self.status_level >= StatusLevel::Slow {
+                    write!(writer, "{:>12} ", "SLOW".style(self.styles.skip))?;
+                    if let Some((_, elapsed)) = slowest.first() {
+                        self.write_slow_duration(*elapsed, writer)?;
+                    }
+                    write!(
+                        writer,
+                        "{} tests slow (slowest: ",
+                        count.style(self.styles.count)
+                    )?;
+                    for (i, (test_instance, _)) in slowest.iter().enumerate() {
+                        if i > 0 {
+                            write!(writer, ", ")?;
+                        }
+                        self.write_short_instance(test_instance, writer)?;
+                    }
+                    writeln!(writer, ")")?;
+                }
+            }
 
             TestEvent::TestFinished {
                 test_instance,
@@ -625,9 +1363,27 @@ impl<'a> TestReporterImpl {
                 run_status,
                 ..
             } => {
+                if let Some(determinism) = run_status.determinism {
+                    if determinism.is_nondeterministic() {
+                        self.nondeterministic
+                            .push((test_instance.clone(), determinism));
+                    }
+                }
+                if let Some(expected) = run_status.expected_duration {
+                    if run_status.time_taken > expected {
+                        self.over_expected.push((
+                            test_instance.clone(),
+                            run_status.time_taken,
+                            expected,
+                        ));
+                    }
+                }
+                if let Some(tag_summary) = &mut self.tag_summary {
+                    tag_summary.finished(&test_instance.name, run_status.result.is_success());
+                }
                 let describe = run_status.describe();
                 let last_status = run_status.result;
-                let test_output_display = match last_status == ExecutionResult::Pass {
+                let test_output_display = match last_status.is_success() {
                     true => self.success_output(*success_output),
                     false => self.failure_output(*failure_output),
                 };
@@ -643,6 +1399,9 @@ impl<'a> TestReporterImpl {
                         self.write_stdout_stderr(test_instance, run_status, false, writer)?;
                     }
                 }
+                if self.github && !last_status.is_success() {
+                    self.write_annotations(test_instance, run_status, writer)?;
+                }
 
                 // Store the output in final_outputs if test output display is requested, or if
                 // we have to print a one-line summary at the end.
@@ -659,6 +1418,15 @@ impl<'a> TestReporterImpl {
                 }
             }
 
+            TestEvent::CollectionError { builder, message } => {
+                writeln!(
+                    writer,
+                    "{:>12} collecting tests in {builder}",
+                    "ERROR".style(self.styles.fail)
+                )?;
+                self.write_test_output(message.as_bytes(), writer)?;
+                writeln!(writer)?;
+            }
             TestEvent::SetupFinished {
                 test_instance,
                 duration,
@@ -671,101 +1439,192 @@ impl<'a> TestReporterImpl {
                     self.write_status_line(test_instance, describe, writer)?;
                 }
             }
+            TestEvent::TestAttemptFailedWillRetry {
+                test_instance,
+                run_status,
+            } => {
+                let describe = ExecutionDescription::Retry { status: run_status };
+                if self.status_level >= describe.status_level() {
+                    self.write_status_line(test_instance, describe, writer)?;
+                    if self.cancel_status < Some(CancelReason::Signal) {
+                        self.write_stdout_stderr(test_instance, run_status, true, writer)?;
+                    }
+                }
+            }
             TestEvent::TestSkipped {
                 test_instance,
                 reason,
+                message,
             } => {
-                if self.status_level >= StatusLevel::Skip {
-                    self.write_skip_line(test_instance, writer)?;
+                // Tests that skip themselves because of their own requirements
+                // are as interesting as passing ones, and so are tests that
+                // ran out of time. Filtered tests are not.
+                let required_status_level = match reason {
+                    MismatchReason::Cfg
+                    | MismatchReason::Unavailable
+                    | MismatchReason::Offline
+                    | MismatchReason::Budget
+                    | MismatchReason::FailFast
+                    | MismatchReason::RunTimeout
+                    | MismatchReason::Canceled
+                    | MismatchReason::Interrupted => StatusLevel::Pass,
+                    _ => StatusLevel::Skip,
+                };
+                if self.status_level >= required_status_level {
+                    self.write_skip_line(test_instance, *reason, message.as_deref(), writer)?;
                 }
                 if self.final_status_level >= FinalStatusLevel::Skip {
-                    self.final_outputs
-                        .push((test_instance.clone(), FinalOutput::Skipped(*reason)));
+                    self.final_outputs.push((
+                        test_instance.clone(),
+                        FinalOutput::Skipped(*reason, message.clone()),
+                    ));
                 }
             }
-            TestEvent::RunBeginCancel { running, reason } => {
+            TestEvent::RunBeginCancel {
+                running,
+                reason,
+                message,
+            } => {
                 self.cancel_status = self.cancel_status.max(Some(*reason));
 
                 write!(writer, "{:>12} ", "Canceling".style(self.styles.fail))?;
-                let reason_str = match reason {
-                    // CancelReason::TestFailure => "test failure",
-                    // CancelReason::ReportError => "error",
-                    CancelReason::Signal => "signal",
-                    // CancelReason::Interrupt => "interrupt",
-                };
-
+                write!(writer, "due to {}", reason.as_str().style(self.styles.fail))?;
+                if let Some(message) = message {
+                    write!(writer, " ({message})")?;
+                }
                 writeln!(
                     writer,
-                    "due to {}: {} tests still running",
-                    reason_str.style(self.styles.fail),
+                    ": {} tests still running",
                     running.style(self.styles.count)
                 )?;
             }
-            TestEvent::RunPaused { running } => {
+            TestEvent::RunPaused { running, reason } => {
                 writeln!(
                     writer,
                     "{:>12} {} running tests due to {}",
                     "Pausing".style(self.styles.pass),
                     running.style(self.styles.count),
-                    "signal".style(self.styles.count),
+                    reason.as_str().style(self.styles.count),
                 )?;
             }
-            TestEvent::RunContinued { running } => {
+            TestEvent::RunContinued { running, reason } => {
                 writeln!(
                     writer,
                     "{:>12} {} running tests due to {}",
                     "Continuing".style(self.styles.pass),
                     running.style(self.styles.count),
-                    "signal".style(self.styles.count),
+                    reason.as_str().style(self.styles.count),
                 )?;
             }
+            TestEvent::ConcurrencyReduced { reduction } => {
+                writeln!(
+                    writer,
+                    "{:>12} from {} to {} tasks after {} failed with {}, retrying",
+                    "Reducing".style(self.styles.skip),
+                    reduction.from.style(self.styles.count),
+                    reduction.to.style(self.styles.count),
+                    reduction.test,
+                    reduction.reason,
+                )?;
+            }
+            TestEvent::RunCompared { comparison } => {
+                writeln!(
+                    writer,
+                    "{:>12} with {}: {} newly failing, {} newly passing, {} newly slow, \
+                    {} added, {} removed",
+                    "Compared".style(self.styles.pass),
+                    comparison.baseline,
+                    comparison.newly_failing.len().style(self.styles.count),
+                    comparison.newly_passing.len().style(self.styles.count),
+                    comparison.newly_slow.len().style(self.styles.count),
+                    comparison.added.len().style(self.styles.count),
+                    comparison.removed.len().style(self.styles.count),
+                )?;
+                for change in &comparison.newly_failing {
+                    self.write_change_line("NEW FAIL", self.styles.fail, change, writer)?;
+                }
+                for change in &comparison.newly_passing {
+                    self.write_change_line("NEW PASS", self.styles.pass, change, writer)?;
+                }
+                for change in &comparison.newly_slow {
+                    self.write_change_line("NEW SLOW", self.styles.skip, change, writer)?;
+                }
+                for name in &comparison.added {
+                    writeln!(writer, "{:>12} {name}", "ADDED".style(self.styles.pass))?;
+                }
+                for name in &comparison.removed {
+                    writeln!(writer, "{:>12} {name}", "REMOVED".style(self.styles.skip))?;
+                }
+            }
+            TestEvent::FixturesUsed { fixtures } => {
+                if self.report_fixtures {
+                    for fixture in fixtures {
+                        self.write_fixture_line(fixture, writer)?;
+                    }
+                }
+            }
+            TestEvent::RunProgress { elapsed, run_stats } => {
+                let style = if run_stats.any_failed() {
+                    self.styles.fail
+                } else {
+                    self.styles.pass
+                };
+                self.write_run_line("Progress", style, *elapsed, run_stats, writer)?;
+            }
             TestEvent::RunFinished {
-                start_time: _start_time,
+                start_time,
                 elapsed,
                 run_stats,
-                ..
+                durations,
             } => {
                 let summary_style = if run_stats.any_failed() {
                     self.styles.fail
                 } else {
                     self.styles.pass
                 };
-                write!(
-                    writer,
-                    "------------\n{:>12} ",
-                    "Summary".style(summary_style)
-                )?;
+                writeln!(writer, "------------")?;
+                self.write_run_line("Summary", summary_style, *elapsed, run_stats, writer)?;
 
-                // Next, print the total time taken.
-                // * > means right-align.
-                // * 8 is the number of characters to pad to.
-                // * .3 means print two digits after the decimal point.
-                write!(writer, "[{:>8.3?}s] ", elapsed.as_secs_f64())?;
-
-                write!(
-                    writer,
-                    "{}",
-                    run_stats.finished_count.style(self.styles.count)
-                )?;
-                if run_stats.finished_count != run_stats.initial_run_count {
-                    write!(
+                if run_stats.queue_wait >= QUEUE_NOISE {
+                    write!(writer, "{:>12} ", "Queued".style(self.styles.skip))?;
+                    self.write_duration(run_stats.queue_wait, writer)?;
+                    writeln!(
                         writer,
-                        "/{}",
-                        run_stats.initial_run_count.style(self.styles.count)
+                        "in total waiting for a free task (--test-tasks {})",
+                        self.tasks.style(self.styles.count),
                     )?;
                 }
 
-                let tests_str = if run_stats.finished_count == 1 && run_stats.initial_run_count == 1
-                {
-                    "test"
-                } else {
-                    "tests"
-                };
+                if let (true, Some(durations)) = (self.verbose, durations) {
+                    self.write_durations(durations, writer)?;
+                }
 
-                let mut summary_str = String::new();
-                // Writing to a string is infallible.
-                let _ = write_summary_str(run_stats, &self.styles, &mut summary_str);
-                writeln!(writer, " {tests_str} run: {summary_str}")?;
+                if let Some(seed) = self.sample_seed {
+                    writeln!(
+                        writer,
+                        "{:>12} ran {} of {} tests with seed {} (reproduce with --sample-seed {seed})",
+                        "Sample".style(self.styles.skip),
+                        run_stats.initial_run_count.style(self.styles.count),
+                        (run_stats.initial_run_count + run_stats.sampled_out)
+                            .style(self.styles.count),
+                        seed.style(self.styles.count),
+                    )?;
+                    for name in &self.sampled {
+                        writeln!(writer, "{:>12} {name}", "")?;
+                    }
+                }
+
+                if let Some(tag_summary) = &self.tag_summary {
+                    // Without any tags, the breakdown would only repeat the summary.
+                    if !tag_summary.counts.is_empty() {
+                        for (tag, counts) in &tag_summary.counts {
+                            self.write_tag_line(tag, *counts, writer)?;
+                        }
+                        if tag_summary.untagged != (0, 0) {
+                            self.write_tag_line("(untagged)", tag_summary.untagged, writer)?;
+                        }
+                    }
+                }
 
                 // // Don't print out final outputs if canceled due to Ctrl-C.
                 // if self.cancel_status < Some(CancelReason::Signal) {
@@ -782,8 +1641,13 @@ impl<'a> TestReporterImpl {
                 for (test_instance, final_output) in &*self.final_outputs {
                     let final_status_level = final_output.final_status_level();
                     match final_output {
-                        FinalOutput::Skipped(_) => {
-                            self.write_skip_line(test_instance, writer)?;
+                        FinalOutput::Skipped(reason, message) => {
+                            self.write_skip_line(
+                                test_instance,
+                                *reason,
+                                message.as_deref(),
+                                writer,
+                            )?;
                         }
                         FinalOutput::Executed {
                             run_status,
@@ -809,22 +1673,264 @@ impl<'a> TestReporterImpl {
                     }
                 }
                 // }
+
+                if !self.over_expected.is_empty() {
+                    self.over_expected
+                        .sort_by(|(a, _, _), (b, _, _)| a.name.cmp(&b.name));
+                    writeln!(
+                        writer,
+                        "{:>12} {} {} exceeded their expected duration:",
+                        "Expected".style(self.styles.skip),
+                        self.over_expected.len().style(self.styles.count),
+                        match self.over_expected.len() {
+                            1 => "test",
+                            _ => "tests",
+                        },
+                    )?;
+                    for (test_instance, taken, expected) in &self.over_expected {
+                        write!(writer, "{:>12} ", "OVER".style(self.styles.skip))?;
+                        self.write_duration(*taken, writer)?;
+                        self.write_instance(test_instance, writer)?;
+                        writeln!(writer, " (expected {:.3}s)", expected.as_secs_f64())?;
+                    }
+                }
+
+                if !self.nondeterministic.is_empty() {
+                    self.nondeterministic
+                        .sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+                    writeln!(
+                        writer,
+                        "{:>12} {} {} had different outcomes across runs:",
+                        "Determinism".style(self.styles.fail),
+                        self.nondeterministic.len().style(self.styles.count),
+                        match self.nondeterministic.len() {
+                            1 => "test",
+                            _ => "tests",
+                        },
+                    )?;
+                    for (test_instance, determinism) in &self.nondeterministic {
+                        write!(
+                            writer,
+                            "{:>12} ",
+                            format!("{}/{} failed", determinism.failed, determinism.runs)
+                                .style(self.styles.fail)
+                        )?;
+                        self.write_instance(test_instance, writer)?;
+                        writeln!(writer)?;
+                    }
+                }
+
+                if let Some(gaps) = &self.gaps {
+                    let analysis = gaps.analyze(self.tasks, *start_time, *elapsed);
+                    self.write_gaps(&analysis, writer)?;
+                }
+                for disabled in &self.disabled_sinks {
+                    writeln!(
+                        writer,
+                        "{:>12} {disabled}",
+                        "Disabled".style(self.styles.fail)
+                    )?;
+                }
             }
         }
 
         Ok(())
     }
 
+    fn write_change_line(
+        &self,
+        label: &str,
+        style: Style,
+        change: &report::TestChange,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        write!(writer, "{:>12} ", label.style(style))?;
+        self.write_duration(Duration::from_secs_f64(change.duration_secs), writer)?;
+        writeln!(
+            writer,
+            "{} ({:+.3}s)",
+            change.name,
+            change.duration_secs - change.previous_duration_secs
+        )
+    }
+
+    fn write_gaps(&self, analysis: &gaps::Analysis, writer: &mut impl Write) -> io::Result<()> {
+        let capacity = analysis.capacity.as_secs_f64();
+        let share = match capacity {
+            capacity if capacity > 0.0 => analysis.idle.as_secs_f64() / capacity,
+            _ => 0.0,
+        };
+        write!(writer, "{:>12} ", "Gaps".style(self.styles.skip))?;
+        self.write_duration(analysis.idle, writer)?;
+        write!(
+            writer,
+            "of {capacity:.3}s of task time idle ({:.0}%)",
+            share * 100.0
+        )?;
+        match analysis.causes.is_empty() {
+            true => writeln!(writer)?,
+            false => writeln!(writer, ", top causes:")?,
+        }
+        for cause in &analysis.causes {
+            let label = match cause.kind {
+                gaps::CauseKind::Queued => "QUEUED",
+                _ => "IDLE",
+            };
+            write!(writer, "{:>12} ", label.style(self.styles.skip))?;
+            self.write_duration(cause.cost, writer)?;
+            writeln!(writer, "{}", cause.describe(self.tasks))?;
+        }
+        Ok(())
+    }
+
+    fn write_fixture_line(
+        &self,
+        fixture: &report::FixtureUsage,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        write!(writer, "{:>12} ", "FIXTURE".style(self.styles.task))?;
+        match fixture.init_secs {
+            Some(secs) => self.write_duration(Duration::from_secs_f64(secs), writer)?,
+            None => write!(writer, "[{:>9}] ", "-")?,
+        }
+        writeln!(
+            writer,
+            "{}: used by {} tests, {} waiting at most",
+            fixture.name,
+            fixture.tests.style(self.styles.count),
+            fixture.peak_waiters.style(self.styles.count),
+        )
+    }
+
+    /// Writes the time taken and the counts of the run so far, after `label`.
+    fn write_run_line(
+        &self,
+        label: &str,
+        label_style: Style,
+        elapsed: Duration,
+        run_stats: &RunStats,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        write!(writer, "{:>12} ", label.style(label_style))?;
+
+        // Next, print the total time taken.
+        // * > means right-align.
+        // * 8 is the number of characters to pad to.
+        // * .3 means print two digits after the decimal point.
+        write!(writer, "[{:>8.3?}s] ", elapsed.as_secs_f64())?;
+
+        write!(
+            writer,
+            "{}",
+            run_stats.finished_count.style(self.styles.count)
+        )?;
+        if run_stats.finished_count != run_stats.initial_run_count {
+            write!(
+                writer,
+                "/{}",
+                run_stats.initial_run_count.style(self.styles.count)
+            )?;
+        }
+
+        let tests_str = if run_stats.finished_count == 1 && run_stats.initial_run_count == 1 {
+            "test"
+        } else {
+            "tests"
+        };
+
+        let mut summary_str = String::new();
+        // Writing to a string is infallible.
+        let _ = write_summary_str(run_stats, &self.styles, &mut summary_str);
+        writeln!(writer, " {tests_str} run: {summary_str}")
+    }
+
+    /// Writes the percentiles of the test durations, and a histogram of them
+    /// with a bar per bucket.
+    fn write_durations(
+        &self,
+        durations: &report::DurationSummary,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        const BAR_WIDTH: usize = 40;
+
+        write!(writer, "{:>12}", "Durations".style(self.styles.pass))?;
+        for (label, secs) in [
+            ("p50", durations.p50_secs),
+            ("p90", durations.p90_secs),
+            ("p99", durations.p99_secs),
+            ("max", durations.max_secs),
+        ] {
+            write!(
+                writer,
+                " {label} {}",
+                format!("{secs:.3}s").style(self.styles.count)
+            )?;
+        }
+        writeln!(writer)?;
+
+        let most = durations.histogram.iter().map(|bucket| bucket.count).max();
+        let most = most.unwrap_or(0).max(1);
+        for bucket in &durations.histogram {
+            // A bucket with any tests gets at least one character.
+            let width = (bucket.count * BAR_WIDTH + most - 1) / most;
+            writeln!(
+                writer,
+                "{:>12} {:<BAR_WIDTH$} {}",
+                crate::durations::bucket_label(bucket),
+                "#".repeat(width),
+                bucket.count.style(self.styles.count),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_tag_line(
+        &self,
+        tag: &str,
+        (passed, failed): (usize, usize),
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        write!(
+            writer,
+            "{:>12} {tag}: {} {}",
+            "Tag".style(self.styles.pass),
+            passed.style(self.styles.count),
+            "passed".style(self.styles.pass),
+        )?;
+        if failed > 0 {
+            write!(
+                writer,
+                ", {} {}",
+                failed.style(self.styles.count),
+                "failed".style(self.styles.fail),
+            )?;
+        }
+        writeln!(writer)
+    }
+
     fn write_skip_line(
         &self,
         test_instance: &TestInstance,
+        reason: MismatchReason,
+        message: Option<&str>,
         writer: &mut impl Write,
     ) -> io::Result<()> {
-        write!(writer, "{:>12} ", "SKIP".style(self.styles.skip))?;
+        let label = match reason {
+            MismatchReason::Budget
+            | MismatchReason::FailFast
+            | MismatchReason::RunTimeout
+            | MismatchReason::Canceled
+            | MismatchReason::Interrupted => "NOT RUN",
+            _ => "SKIP",
+        };
+        write!(writer, "{:>12} ", label.style(self.styles.skip))?;
         // same spacing [   0.034s]
         write!(writer, "[         ] ")?;
 
         self.write_instance(test_instance, writer)?;
+        if let Some(message) = message {
+            write!(writer, " ({message})")?;
+        }
         writeln!(writer)?;
 
         Ok(())
@@ -838,7 +1944,11 @@ impl<'a> TestReporterImpl {
     ) -> io::Result<()> {
         let time_taken = match describe {
             ExecutionDescription::Success { status } => {
-                write!(writer, "{:>12} ", "PASS".style(self.styles.pass))?;
+                write!(
+                    writer,
+                    "{:>12} ",
+                    attempt_status_str(status).style(self.styles.pass)
+                )?;
                 status.time_taken
             }
             ExecutionDescription::Setup { duration } => {
@@ -849,17 +1959,74 @@ impl<'a> TestReporterImpl {
                 write!(
                     writer,
                     "{:>12} ",
-                    status_str(status.result).style(self.styles.fail)
+                    attempt_status_str(status).style(self.styles.fail)
+                )?;
+                status.time_taken
+            }
+            ExecutionDescription::Retry { status } => {
+                write!(
+                    writer,
+                    "{:>12} ",
+                    format!("TRY {} {}", status.attempts, status_str(status.result))
+                        .style(self.styles.retry)
                 )?;
                 status.time_taken
             }
         };
 
-        // Next, print the time taken.
-        self.write_duration(time_taken, writer)?;
+        // Next, print the time taken, with the time spent waiting for
+        // fixtures next to it rather than in it.
+        let setup_wait = match describe {
+            ExecutionDescription::Success { status } | ExecutionDescription::Failure { status }
+                if status.setup_wait >= QUEUE_NOISE =>
+            {
+                Some(status.setup_wait)
+            }
+            _ => None,
+        };
+        let level = match (self.report_time, describe) {
+            (
+                Some(report_time),
+                ExecutionDescription::Success { .. }
+                | ExecutionDescription::Failure { .. }
+                | ExecutionDescription::Retry { .. },
+            ) => report_time.level(time_taken),
+            _ => TimeLevel::Ok,
+        };
+        let time = format!("{:>8.3?}s", time_taken.as_secs_f64());
+        let time = match level {
+            TimeLevel::Ok => time.style(Style::new()),
+            TimeLevel::Warn => time.style(self.styles.skip),
+            TimeLevel::Critical => time.style(self.styles.fail),
+        };
+        match setup_wait {
+            Some(setup_wait) => write!(
+                writer,
+                "[{time} + {:.3}s setup-wait] ",
+                setup_wait.as_secs_f64()
+            )?,
+            None => write!(writer, "[{time}] ")?,
+        }
 
         // Print the name of the test.
-        self.write_instance(test_instance, writer)?;
+        self.write_short_instance(test_instance, writer)?;
+        if let (Some(report_time), TimeLevel::Warn | TimeLevel::Critical) =
+            (self.report_time, level)
+        {
+            let (kind, time) = match level {
+                TimeLevel::Critical => ("critical", report_time.critical),
+                _ => ("warning", report_time.warn),
+            };
+            write!(writer, " (over the {kind} time of {}s)", time.as_secs_f64())?;
+        }
+        if let ExecutionDescription::Success { status } | ExecutionDescription::Failure { status } =
+            describe
+        {
+            self.write_iterations(status, writer)?;
+            self.write_suspended(status, writer)?;
+            self.write_queued(status, writer)?;
+            self.write_meta(status, writer)?;
+        }
         writeln!(writer)?;
 
         Ok(())
@@ -873,12 +2040,20 @@ impl<'a> TestReporterImpl {
     ) -> io::Result<()> {
         let time_taken = match describe {
             ExecutionDescription::Success { status } => match (status.is_slow, status.result) {
+                _ if status.attempts > 1 => {
+                    write!(writer, "{:>12} ", "FLAKY".style(self.styles.flaky))?;
+                    status.time_taken
+                }
                 (true, _) => {
                     write!(writer, "{:>12} ", "SLOW".style(self.styles.skip))?;
                     status.time_taken
                 }
                 (false, _) => {
-                    write!(writer, "{:>12} ", "PASS".style(self.styles.pass))?;
+                    write!(
+                        writer,
+                        "{:>12} ",
+                        attempt_status_str(status).style(self.styles.pass)
+                    )?;
                     status.time_taken
                 }
             },
@@ -886,11 +2061,11 @@ impl<'a> TestReporterImpl {
                 write!(writer, "{:>12} ", "TASK".style(self.styles.task))?;
                 duration
             }
-            ExecutionDescription::Failure { status } => {
+            ExecutionDescription::Failure { status } | ExecutionDescription::Retry { status } => {
                 write!(
                     writer,
                     "{:>12} ",
-                    status_str(status.result).style(self.styles.fail)
+                    attempt_status_str(status).style(self.styles.fail)
                 )?;
                 status.time_taken
             }
@@ -901,15 +2076,92 @@ impl<'a> TestReporterImpl {
 
         // Print the name of the test.
         self.write_instance(test_instance, writer)?;
+        if let ExecutionDescription::Success { status } = describe {
+            if status.attempts > 1 {
+                write!(
+                    writer,
+                    " (passed on try {})",
+                    status.attempts.style(self.styles.count)
+                )?;
+            }
+        }
         writeln!(writer)?;
 
         Ok(())
     }
 
+    fn write_iterations(&self, status: &ExecuteStatus, writer: &mut impl Write) -> io::Result<()> {
+        let Some(iterations) = &status.iterations else {
+            return Ok(());
+        };
+        if status.result.is_success() {
+            write!(
+                writer,
+                " ({} iterations, mean {:.3}s, max {:.3}s)",
+                iterations.count.style(self.styles.count),
+                iterations.mean_secs,
+                iterations.max_secs,
+            )
+        } else {
+            write!(
+                writer,
+                " (failed on iteration {})",
+                iterations.count.style(self.styles.count)
+            )
+        }
+    }
+
+    fn write_suspended(&self, status: &ExecuteStatus, writer: &mut impl Write) -> io::Result<()> {
+        let Some(suspended) = status.suspended else {
+            return Ok(());
+        };
+        write!(
+            writer,
+            " ({}: suspended for {:.3}s, not counted)",
+            "warning".style(self.styles.skip),
+            suspended.as_secs_f64(),
+        )
+    }
+
+    fn write_queued(&self, status: &ExecuteStatus, writer: &mut impl Write) -> io::Result<()> {
+        if !self.verbose || status.queued < QUEUE_NOISE {
+            return Ok(());
+        }
+        write!(writer, " (queued for {:.3}s)", status.queued.as_secs_f64())
+    }
+
+    fn write_meta(&self, status: &ExecuteStatus, writer: &mut impl Write) -> io::Result<()> {
+        if !self.verbose || status.meta.is_empty() {
+            return Ok(());
+        }
+        write!(writer, " (")?;
+        for (i, (key, value)) in status.meta.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ", ")?;
+            }
+            write!(writer, "{key}={}", value.style(self.styles.count))?;
+        }
+        write!(writer, ")")
+    }
+
     fn write_instance(&self, instance: &TestInstance, writer: &mut impl Write) -> io::Result<()> {
         write_test_name(&instance.name, &self.styles.list_styles, writer)
     }
 
+    /// Like `write_instance`, but shortened to the name width, for lines that
+    /// are printed while the run is in progress.
+    fn write_short_instance(
+        &self,
+        instance: &TestInstance,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let name = match self.name_width {
+            Some(width) => truncate_middle(&instance.name, width),
+            None => Cow::Borrowed(instance.name.as_str()),
+        };
+        write_test_name(&name, &self.styles.list_styles, writer)
+    }
+
     fn write_duration(&self, duration: Duration, writer: &mut impl Write) -> io::Result<()> {
         // * > means right-align.
         // * 8 is the number of characters to pad to.
@@ -941,30 +2193,116 @@ impl<'a> TestReporterImpl {
     ) -> io::Result<()> {
         let (header_style, _output_style) = if is_retry {
             (self.styles.retry, self.styles.retry_output)
-        } else if run_status.result == ExecutionResult::Pass {
+        } else if run_status.result.is_success() {
             (self.styles.pass, self.styles.pass_output)
         } else {
             (self.styles.fail, self.styles.fail_output)
         };
 
         if let Some(output) = &run_status.output {
-            write!(writer, "\n{}", "--- ".style(header_style))?;
-            // let out_len = self.write_attempt(run_status, header_style, writer)?;
+            // Collapsed in the log of the job, the annotations point at it.
+            let group = self.github && !is_retry && !run_status.result.is_success();
+            writeln!(writer)?;
+            if group {
+                write!(writer, "::group::")?;
+            }
+            write!(writer, "{}", "--- ".style(header_style))?;
+            let out_len = self.write_attempt(run_status, is_retry, header_style, writer)?;
             // The width is to align test instances.
             write!(
                 writer,
                 "{:width$}",
                 "ERROR:".style(header_style),
-                width = 21
+                width = 21 - out_len
             )?;
             self.write_instance(test_instance, writer)?;
             writeln!(writer, "{}", " ---".style(header_style))?;
+            if let Some(description) = &test_instance.description {
+                writeln!(writer, "{}\n", description.style(header_style))?;
+            }
+            for link in &test_instance.links {
+                write!(writer, "{}: ", link.label.style(header_style))?;
+                self.write_url(&link.url, writer)?;
+                writeln!(writer)?;
+            }
+            if !test_instance.links.is_empty() {
+                writeln!(writer)?;
+            }
 
-            self.write_test_output(output.as_bytes(), writer)?;
+            if run_status.failures.len() > 1 {
+                let count = run_status.failures.len();
+                for (i, failure) in run_status.failures.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(writer)?;
+                    }
+                    write!(
+                        writer,
+                        "{} ",
+                        format!("[{}/{count}]", i + 1).style(header_style)
+                    )?;
+                    self.write_test_output(failure.message.as_bytes(), writer)?;
+                }
+            } else {
+                self.write_test_output(output.as_bytes(), writer)?;
+            }
+            if group {
+                writeln!(writer, "::endgroup::")?;
+            }
         }
         writeln!(writer)
     }
 
+    /// Writes an `::error` workflow command for every failure of a test, so
+    /// that GitHub shows them at their location in the diff.
+    fn write_annotations(
+        &self,
+        test_instance: &TestInstance,
+        run_status: &ExecuteStatus,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let title = escape_property(&test_instance.name);
+        let fallback = [report::Failure {
+            message: run_status.output.clone().unwrap_or_default(),
+            location: None,
+        }];
+        let failures = match run_status.failures.is_empty() {
+            true => &fallback[..],
+            false => &run_status.failures[..],
+        };
+        for failure in failures {
+            write!(writer, "::error ")?;
+            let location = failure.location.as_deref().and_then(|location| {
+                let mut parts = location.rsplitn(3, ':');
+                let (column, line, file) = (parts.next()?, parts.next()?, parts.next()?);
+                Some((file, line, column))
+            });
+            if let Some((file, line, column)) = location {
+                write!(
+                    writer,
+                    "file={},line={line},col={column},",
+                    escape_property(file)
+                )?;
+            }
+            // The backtrace is in the group of the output.
+            let message = match failure.message.split_once("\nstack backtrace:") {
+                Some((message, _)) => message,
+                None => &failure.message,
+            };
+            writeln!(writer, "title={title}::{}", escape_data(message.trim_end()))?;
+        }
+        Ok(())
+    }
+
+    /// Writes `url` as an OSC 8 hyperlink if the output is colorized.
+    /// Terminals that do not support hyperlinks just show the URL.
+    fn write_url(&self, url: &str, writer: &mut impl Write) -> io::Result<()> {
+        if self.styles.is_colorized {
+            write!(writer, "\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\")
+        } else {
+            write!(writer, "{url}")
+        }
+    }
+
     fn write_test_output(&self, output: &[u8], writer: &mut impl Write) -> io::Result<()> {
         if self.styles.is_colorized {
             const RESET_COLOR: &[u8] = b"\x1b[0m";
@@ -980,28 +2318,29 @@ impl<'a> TestReporterImpl {
         writeln!(writer)
     }
 
-    // // Returns the number of characters written out to the screen.
-    // fn write_attempt(
-    //     &self,
-    //     run_status: &ExecuteStatus,
-    //     style: Style,
-    //     writer: &mut impl Write,
-    // ) -> io::Result<usize> {
-    //     if run_status.retry_data.total_attempts > 1 {
-    //         // 3 for 'TRY' + 1 for ' ' + length of the current attempt + 1 for following space.
-    //         let attempt_str = format!("{}", run_status.retry_data.attempt);
-    //         let out_len = 3 + 1 + attempt_str.len() + 1;
-    //         write!(
-    //             writer,
-    //             "{} {} ",
-    //             "TRY".style(style),
-    //             attempt_str.style(style)
-    //         )?;
-    //         Ok(out_len)
-    //     } else {
-    //         Ok(0)
-    //     }
-    // }
+    // Returns the number of characters written out to the screen.
+    fn write_attempt(
+        &self,
+        run_status: &ExecuteStatus,
+        is_retry: bool,
+        style: Style,
+        writer: &mut impl Write,
+    ) -> io::Result<usize> {
+        if is_retry || run_status.attempts > 1 {
+            // 3 for 'TRY' + 1 for ' ' + length of the current attempt + 1 for following space.
+            let attempt_str = format!("{}", run_status.attempts);
+            let out_len = 3 + 1 + attempt_str.len() + 1;
+            write!(
+                writer,
+                "{} {} ",
+                "TRY".style(style),
+                attempt_str.style(style)
+            )?;
+            Ok(out_len)
+        } else {
+            Ok(0)
+        }
+    }
 
     fn success_output(&self, test_setting: TestOutputDisplay) -> TestOutputDisplay {
         self.force_success_output.unwrap_or(test_setting)
@@ -1039,12 +2378,53 @@ fn write_test_name(name: &str, style: &ListStyles, mut writer: impl Write) -> io
     Ok(())
 }
 
+/// Shortens `name` to `width` characters by replacing its middle with `…`.
+/// The end of a name is usually the most telling part, so it keeps the
+/// larger half. Below 2 columns, there is no room for the `…`.
+fn truncate_middle(name: &str, width: usize) -> Cow<'_, str> {
+    let len = name.chars().count();
+    if len <= width {
+        return Cow::Borrowed(name);
+    }
+    if width < 2 {
+        return Cow::Owned(name.chars().take(width).collect());
+    }
+    let keep = width.saturating_sub(1);
+    let head: String = name.chars().take(keep / 2).collect();
+    let tail: String = name.chars().skip(len - (keep - keep / 2)).collect();
+    Cow::Owned(format!("{head}…{tail}"))
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property of a workflow command, like `file` or `title`.
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// [`status_str`], with the attempt if the test was retried.
+fn attempt_status_str(status: &ExecuteStatus) -> Cow<'static, str> {
+    match status.attempts {
+        1 => status_str(status.result),
+        attempts => format!("TRY {attempts} {}", status_str(status.result)).into(),
+    }
+}
+
 fn status_str(result: ExecutionResult) -> Cow<'static, str> {
     // Max 12 characters here.
     match result {
         ExecutionResult::Fail => "FAIL".into(),
         ExecutionResult::Pass => "PASS".into(),
         ExecutionResult::Timeout => "TIMEOUT".into(),
+        ExecutionResult::ExpectedFail => "XFAIL".into(),
+        ExecutionResult::UnexpectedPass => "XPASS".into(),
     }
 }
 
@@ -1068,14 +2448,30 @@ pub(crate) enum TestEvent<'a> {
         ///
         /// The methods on the test list indicate the number of tests that will be run.
         test_list: &'a TestList,
+
+        /// The effective configuration of the run.
+        config: &'a RunConfig,
+
+        /// Warnings and notes emitted while collecting tests.
+        diagnostics: &'a [Diagnostic],
         // /// The UUID for this run.
         // run_id: Uuid,
     },
 
+    /// A [`tests!`](crate::tests) function panicked while collecting tests.
+    CollectionError {
+        /// The path of the function that panicked.
+        builder: String,
+
+        /// The panic message.
+        message: String,
+    },
+
     /// A test started running.
     TestStarted {
-        // /// The test instance that was started.
-        // test_instance: TestInstance,
+        /// The test instance that was started.
+        test_instance: TestInstance,
+
         /// Current run statistics so far.
         current_stats: RunStats,
 
@@ -1100,11 +2496,23 @@ pub(crate) enum TestEvent<'a> {
         will_terminate: bool,
     },
 
+    /// Several tests were slow within the same reporting interval.
+    TestsSlow {
+        /// The number of tests that were slow.
+        count: usize,
+
+        /// The slowest of those tests, with the time elapsed since each started.
+        slowest: Vec<(TestInstance, Duration)>,
+    },
+
     /// A test finished running.
     SetupFinished {
         /// The test instance that finished running.
         test_instance: TestInstance,
 
+        /// The time at which the setup started.
+        start_time: SystemTime,
+
         duration: Duration,
 
         /// Current statistics for number of tests so far.
@@ -1144,12 +2552,23 @@ pub(crate) enum TestEvent<'a> {
         cancel_state: Option<CancelReason>,
     },
 
+    /// An attempt of a test with retries failed, and the test runs again.
+    TestAttemptFailedWillRetry {
+        /// The test instance that is retried.
+        test_instance: TestInstance,
+
+        /// The failed attempt, with its number in `attempts`.
+        run_status: ExecuteStatus,
+    },
+
     /// A test was skipped.
     TestSkipped {
         /// The test instance that was skipped.
         test_instance: TestInstance,
         /// The reason this test was skipped.
         reason: MismatchReason,
+        /// A human readable explanation, if there is more to say than `reason`.
+        message: Option<String>,
     },
 
     /// A cancellation notice was received.
@@ -1159,18 +2578,50 @@ pub(crate) enum TestEvent<'a> {
 
         /// The reason this run was canceled.
         reason: CancelReason,
+
+        /// What the canceler said about it, e.g. the reason given to
+        /// `RunHandle::cancel`.
+        message: Option<String>,
     },
 
-    /// A SIGTSTP event was received and the run was paused.
+    /// A SIGTSTP event was received or a pause requested, and the run was
+    /// paused.
     RunPaused {
         /// The number of tests currently running.
         running: usize,
+
+        /// What paused the run.
+        reason: CancelReason,
     },
 
-    /// A SIGCONT event was received and the run is being continued.
+    /// A SIGCONT event was received or a resume requested, and the run is
+    /// being continued.
     RunContinued {
         /// The number of tests that will be started up again.
         running: usize,
+
+        /// What continued the run.
+        reason: CancelReason,
+    },
+
+    /// A test ran out of resources, and fewer tests run at once from now on.
+    ConcurrencyReduced {
+        reduction: report::ConcurrencyReduction,
+    },
+
+    /// The differences to an earlier report, with `--compare-with`.
+    RunCompared { comparison: report::Comparison },
+
+    /// How the fixtures were used.
+    FixturesUsed { fixtures: Vec<report::FixtureUsage> },
+
+    /// An interim summary, with `--summary-interval`.
+    RunProgress {
+        /// The time since the run started.
+        elapsed: Duration,
+
+        /// Statistics for the tests so far.
+        run_stats: RunStats,
     },
 
     /// The test run finished.
@@ -1185,6 +2636,9 @@ pub(crate) enum TestEvent<'a> {
 
         /// Statistics for the run.
         run_stats: RunStats,
+
+        /// How the durations of the finished tests are distributed.
+        durations: Option<report::DurationSummary>,
     },
 }
 
@@ -1192,15 +2646,35 @@ pub(crate) enum TestEvent<'a> {
 /// The reason why a test run is being cancelled.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum CancelReason {
-    // /// A test failed and --no-fail-fast wasn't specified.
-    // TestFailure,
+    /// A test failed with `--fail-fast`.
+    TestFailure,
+
+    /// `--run-timeout` passed.
+    RunTimeout,
 
     // /// An error occurred while reporting results.
     // ReportError,
+    /// An embedder asked for it through a `RunHandle`.
+    Requested,
+
     /// A termination signal (on Unix, SIGTERM or SIGHUP) was received.
     Signal,
-    // /// An interrupt (on Unix, Ctrl-C) was received.
-    // Interrupt,
+
+    /// An interrupt (Ctrl-C) was received.
+    Interrupt,
+}
+
+impl CancelReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            CancelReason::TestFailure => "test failure",
+            CancelReason::RunTimeout => "run timeout",
+            // CancelReason::ReportError => "error",
+            CancelReason::Requested => "request",
+            CancelReason::Signal => "signal",
+            CancelReason::Interrupt => "interrupt",
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -1210,6 +2684,7 @@ struct Styles {
     task: Style,
     pass: Style,
     retry: Style,
+    flaky: Style,
     fail: Style,
     pass_output: Style,
     retry_output: Style,
@@ -1225,6 +2700,7 @@ impl Styles {
         self.pass = Style::new().green().bold();
         self.task = Style::new().cyan().bold();
         self.retry = Style::new().magenta().bold();
+        self.flaky = Style::new().yellow().bold();
         self.fail = Style::new().red().bold();
         self.pass_output = Style::new().green();
         self.retry_output = Style::new().magenta();
@@ -0,0 +1,147 @@
+//! Rate limiting for slow-test reports.
+//!
+//! Every running test emits a tick once per slow period. With hundreds of
+//! slow tests that is hundreds of `SLOW` lines at once, so ticks are collected
+//! here and flushed at most once per interval. A single pending test is still
+//! reported as a regular [`TestEvent::TestSlow`]; more than one is summarised
+//! as a [`TestEvent::TestsSlow`] naming the slowest few.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::TestEvent;
+use crate::{clock::Clock, nextest::TestInstance};
+
+/// How many of the slowest tests are named in a summary line.
+const SHOW_SLOWEST: usize = 3;
+
+#[derive(Debug)]
+pub(crate) struct SlowBatcher {
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    /// The latest elapsed time of every test that ticked since the last flush.
+    pending: HashMap<String, Duration>,
+    last_flush: Option<Instant>,
+}
+
+impl SlowBatcher {
+    /// A batcher that reports at most once per `interval`.
+    ///
+    /// A zero interval reports every tick as it arrives.
+    pub(crate) fn new(interval: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            interval,
+            clock,
+            pending: HashMap::new(),
+            last_flush: None,
+        }
+    }
+
+    /// Records that the test called `name` has been running for `elapsed`.
+    pub(crate) fn tick(&mut self, name: String, elapsed: Duration) {
+        self.pending.insert(name, elapsed);
+    }
+
+    /// Forgets about a test that has finished before it was reported.
+    pub(crate) fn finish(&mut self, name: &str) {
+        self.pending.remove(name);
+    }
+
+    /// When the pending ticks should be flushed, if there are any.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(match self.last_flush {
+            Some(last) => last + self.interval,
+            None => self.clock.now(),
+        })
+    }
+
+    /// Returns the event to report if the deadline has passed.
+    pub(crate) fn poll(&mut self) -> Option<TestEvent<'static>> {
+        let now = self.clock.now();
+        if self.pending.is_empty()
+            || self
+                .last_flush
+                .is_some_and(|last| last + self.interval > now)
+        {
+            return None;
+        }
+        self.last_flush = Some(now);
+
+        let mut pending: Vec<_> = self.pending.drain().collect();
+        // Slowest first, then by name so that ties print in a stable order.
+        pending.sort_by(|(a, a_elapsed), (b, b_elapsed)| {
+            b_elapsed.cmp(a_elapsed).then_with(|| a.cmp(b))
+        });
+
+        let count = pending.len();
+        let mut slowest = pending
+            .into_iter()
+            .take(SHOW_SLOWEST)
+            .map(|(name, elapsed)| {
+                let test_instance = TestInstance {
+                    name,
+                    description: None,
+                    links: vec![],
+                    expected_failure: None,
+                };
+                (test_instance, elapsed)
+            });
+
+        Some(match count {
+            1 => {
+                let (test_instance, elapsed) = slowest.next().unwrap();
+                TestEvent::TestSlow {
+                    test_instance,
+                    elapsed,
+                    will_terminate: false,
+                }
+            }
+            _ => TestEvent::TestsSlow {
+                count,
+                slowest: slowest.collect(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn batches_ticks() {
+        let secs = Duration::from_secs;
+        let clock = MockClock::new();
+        let mut batcher = SlowBatcher::new(secs(15), Arc::new(clock.clone()));
+        assert!(batcher.poll().is_none());
+
+        batcher.tick("a".to_owned(), secs(15));
+        assert!(matches!(
+            batcher.poll(),
+            Some(TestEvent::TestSlow { test_instance, .. }) if test_instance.name == "a"
+        ));
+
+        for (name, elapsed) in [("a", 30), ("b", 15), ("c", 45), ("d", 15), ("e", 15)] {
+            batcher.tick(name.to_owned(), secs(elapsed));
+        }
+        batcher.finish("e");
+        assert_eq!(batcher.deadline(), Some(clock.now() + secs(15)));
+        assert!(batcher.poll().is_none());
+
+        clock.advance(secs(15));
+        let Some(TestEvent::TestsSlow { count, slowest }) = batcher.poll() else {
+            panic!("expected a summary");
+        };
+        assert_eq!(count, 4);
+        let names: Vec<_> = slowest.iter().map(|(t, _)| t.name.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+        assert!(batcher.deadline().is_none());
+    }
+}
@@ -0,0 +1,175 @@
+//! A timeline of the run in the Chrome trace event format.
+//!
+//! With `--trace-timeline`, every test and fixture setup is written as a span
+//! once the run finishes, so that the file can be opened in
+//! `chrome://tracing` or Perfetto to see where tests waited and how many ran
+//! at once. Spans are laid out on one lane per slot: a span goes to the first
+//! lane that is free when it starts, so there are as many lanes as there were
+//! tests and setups running at the same time.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use serde_json::{json, Value};
+
+use super::{aggregator::WriteEventError, checkpoint::write_atomically, TestEvent};
+use crate::nextest::ExecutionResult;
+
+#[derive(Debug)]
+pub(crate) struct Timeline {
+    path: PathBuf,
+    /// The tests and setups that finished, in the order they did.
+    spans: Vec<Span>,
+}
+
+/// A test or fixture setup that finished.
+#[derive(Debug)]
+pub(super) struct Span {
+    pub(super) name: String,
+    pub(super) kind: SpanKind,
+    pub(super) start: SystemTime,
+    pub(super) duration: Duration,
+}
+
+#[derive(Debug)]
+pub(super) enum SpanKind {
+    Test {
+        result: ExecutionResult,
+        attempts: usize,
+        /// The time the test waited for its fixtures, and then for a slot,
+        /// before `start`.
+        setup_wait: Duration,
+        queued: Duration,
+    },
+    Fixture,
+}
+
+impl Span {
+    pub(super) fn from_event(event: &TestEvent<'_>) -> Option<Self> {
+        match event {
+            TestEvent::SetupFinished {
+                test_instance,
+                start_time,
+                duration,
+                ..
+            } => Some(Span {
+                name: test_instance.name.clone(),
+                kind: SpanKind::Fixture,
+                start: *start_time,
+                duration: *duration,
+            }),
+            TestEvent::TestFinished {
+                test_instance,
+                run_status,
+                ..
+            } => Some(Span {
+                name: test_instance.name.clone(),
+                kind: SpanKind::Test {
+                    result: run_status.result,
+                    attempts: run_status.attempts,
+                    setup_wait: run_status.setup_wait,
+                    queued: run_status.queued,
+                },
+                start: run_status.start_time,
+                duration: run_status.time_taken,
+            }),
+            _ => None,
+        }
+    }
+
+    pub(super) fn end(&self) -> SystemTime {
+        self.start + self.duration
+    }
+}
+
+impl Timeline {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            spans: vec![],
+        }
+    }
+
+    /// Adds `event` to the timeline, and writes it once the run finishes.
+    pub(crate) fn push(&mut self, event: &TestEvent<'_>) -> Result<(), WriteEventError> {
+        if let Some(span) = Span::from_event(event) {
+            self.spans.push(span);
+        }
+        if let TestEvent::RunFinished { start_time, .. } = event {
+            let trace = self.render(*start_time);
+            write_atomically(&self.path, trace.to_string().as_bytes())?
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, run_start: SystemTime) -> Value {
+        let micros = |time: SystemTime| {
+            time.duration_since(run_start)
+                .unwrap_or_default()
+                .as_micros() as u64
+        };
+        self.spans
+            .sort_by(|a, b| a.start.cmp(&b.start).then(a.name.cmp(&b.name)));
+
+        // The time each lane is free from.
+        let mut lanes: Vec<SystemTime> = vec![];
+        let mut events = vec![json!({
+            "name": "process_name",
+            "ph": "M",
+            "pid": 1,
+            "args": { "name": "async-test" },
+        })];
+        for span in &self.spans {
+            let end = span.end();
+            let lane = match lanes.iter().position(|&free| free <= span.start) {
+                Some(lane) => {
+                    lanes[lane] = end;
+                    lane
+                }
+                None => {
+                    lanes.push(end);
+                    events.push(json!({
+                        "name": "thread_name",
+                        "ph": "M",
+                        "pid": 1,
+                        "tid": lanes.len(),
+                        "args": { "name": format!("slot {}", lanes.len()) },
+                    }));
+                    lanes.len() - 1
+                }
+            };
+            let (category, args) = match span.kind {
+                SpanKind::Test {
+                    result, attempts, ..
+                } => (
+                    "test",
+                    json!({ "result": result_str(result), "attempts": attempts }),
+                ),
+                SpanKind::Fixture => ("fixture", json!({})),
+            };
+            events.push(json!({
+                "name": span.name,
+                "cat": category,
+                "ph": "X",
+                "ts": micros(span.start),
+                "dur": span.duration.as_micros() as u64,
+                "pid": 1,
+                "tid": lane + 1,
+                "args": args,
+            }));
+        }
+        json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+    }
+}
+
+fn result_str(result: ExecutionResult) -> &'static str {
+    match result {
+        ExecutionResult::Pass => "passed",
+        ExecutionResult::Fail => "failed",
+        ExecutionResult::Timeout => "timed out",
+        ExecutionResult::ExpectedFail => "expected failure",
+        ExecutionResult::UnexpectedPass => "unexpected pass",
+    }
+}
@@ -0,0 +1,124 @@
+//! Detects stalls while writing reporter output.
+//!
+//! All events are written by a single reporter. If its output blocks (a full
+//! pipe, a logfile on a hung network share), nothing else gets reported and
+//! the run looks frozen. The [`Watchdog`] runs on its own thread and warns on
+//! an alternate sink when a single write takes longer than the configured
+//! timeout. Events keep being buffered in the meantime.
+
+use std::{
+    io::{self, Write},
+    sync::{Arc, Condvar, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// Where the watchdog prints its warnings.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum AlternateSink {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Default)]
+struct State {
+    /// When the current write started, if one is in progress.
+    writing_since: Option<Instant>,
+    /// Incremented for every write so that each stall is reported once.
+    generation: u64,
+    done: bool,
+}
+
+pub(crate) struct Watchdog {
+    shared: Arc<(Mutex<State>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub(crate) fn spawn(timeout: Duration, sink: AlternateSink) -> Self {
+        let shared = Arc::new((Mutex::new(State::default()), Condvar::new()));
+        let thread = std::thread::Builder::new()
+            .name("async-test-watchdog".to_owned())
+            .spawn({
+                let shared = shared.clone();
+                move || watch(&shared, timeout, sink)
+            })
+            .expect("failed to spawn reporter watchdog thread");
+
+        Self {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Marks the start of a write. The write ends when the guard is dropped.
+    pub(crate) fn guard(&self) -> WriteGuard {
+        let (state, condvar) = &*self.shared;
+        let mut state = state.lock().unwrap();
+        state.writing_since = Some(Instant::now());
+        state.generation += 1;
+        condvar.notify_one();
+        WriteGuard {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        let (state, condvar) = &*self.shared;
+        state.lock().unwrap().done = true;
+        condvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            _ = thread.join();
+        }
+    }
+}
+
+pub(crate) struct WriteGuard {
+    shared: Arc<(Mutex<State>, Condvar)>,
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        let (state, _) = &*self.shared;
+        state.lock().unwrap().writing_since = None;
+    }
+}
+
+fn watch(shared: &(Mutex<State>, Condvar), timeout: Duration, sink: AlternateSink) {
+    let (state, condvar) = shared;
+    let mut state = state.lock().unwrap();
+    let mut warned = None;
+
+    while !state.done {
+        state = match state.writing_since {
+            None => condvar.wait(state).unwrap(),
+            Some(since) => {
+                let elapsed = since.elapsed();
+                if elapsed < timeout {
+                    condvar.wait_timeout(state, timeout - elapsed).unwrap().0
+                } else {
+                    if warned != Some(state.generation) {
+                        warned = Some(state.generation);
+                        warn(sink, elapsed);
+                    }
+                    condvar.wait(state).unwrap()
+                }
+            }
+        };
+    }
+}
+
+fn warn(sink: AlternateSink, elapsed: Duration) {
+    let msg = format!(
+        "async-test: writing test output has been blocked for {:.0}s; \
+        test events are buffered until it resumes",
+        elapsed.as_secs_f64()
+    );
+    // Nothing sensible can be done if the alternate sink fails as well.
+    _ = match sink {
+        AlternateSink::Stdout => writeln!(io::stdout(), "{msg}"),
+        AlternateSink::Stderr => writeln!(io::stderr(), "{msg}"),
+    };
+}
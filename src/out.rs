@@ -0,0 +1,98 @@
+//! Output from concurrent tests that stays readable, see [`println!`].
+//!
+//! The name of the current test is kept in a task-local that lives as long
+//! as the test, like the values of [`TestMeta`](crate::TestMeta).
+
+use std::{
+    fmt,
+    future::Future,
+    io::{self, Write},
+};
+
+tokio::task_local! {
+    static TEST_NAME: String;
+}
+
+/// Prints to stdout like [`std::println!`], with every line prefixed with
+/// the name of the current test.
+///
+/// The whole text is written at once under the lock of stdout, so the lines
+/// of tests that run concurrently do not interleave. Outside of a test, e.g.
+/// in a task it spawned, the lines have no prefix.
+///
+/// ```no_run
+/// async_test::test!(
+///     async fn connects() {
+///         async_test::out::println!("connecting to {}", "localhost:5432");
+///         // Prints `[connects] connecting to localhost:5432`.
+///     }
+/// );
+/// ```
+pub use crate::__out_println as println;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __out_println {
+    () => {
+        $crate::out::__println(::core::format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::out::__println(::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+pub fn __println(args: fmt::Arguments<'_>) {
+    let text = args.to_string();
+    let prefixed = match TEST_NAME.try_with(|name| prefixed(name, &text)) {
+        Ok(prefixed) => prefixed,
+        Err(_) => text + "\n",
+    };
+    // Like `std::println!`, but without panicking if stdout is closed.
+    let mut stdout = io::stdout().lock();
+    _ = stdout.write_all(prefixed.as_bytes());
+    _ = stdout.flush();
+}
+
+/// Prefixes every line of `text` with `name`.
+fn prefixed(name: &str, text: &str) -> String {
+    let mut prefixed = String::with_capacity(text.len() + name.len() + 4);
+    for line in text.split('\n') {
+        prefixed.push('[');
+        prefixed.push_str(name);
+        prefixed.push(']');
+        if !line.is_empty() {
+            prefixed.push(' ');
+            prefixed.push_str(line);
+        }
+        prefixed.push('\n');
+    }
+    prefixed
+}
+
+/// Runs the test called `name`, so that its output is prefixed with it.
+pub(crate) async fn scoped<T>(name: String, test: impl Future<Output = T>) -> T {
+    TEST_NAME.scope(name, test).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix() {
+        assert_eq!(
+            prefixed("db::query", "connecting"),
+            "[db::query] connecting\n"
+        );
+        assert_eq!(prefixed("a", "one\n\ntwo"), "[a] one\n[a]\n[a] two\n");
+        assert_eq!(prefixed("a", ""), "[a]\n");
+    }
+
+    #[tokio::test]
+    async fn name() {
+        let name = scoped("a".to_owned(), async { TEST_NAME.with(Clone::clone) }).await;
+        assert_eq!(name, "a");
+        assert!(TEST_NAME.try_with(|_| ()).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! `--partition`: splits the tests into disjoint parts, so that a run can be
+//! spread across several CI machines that each run the same binary.
+//!
+//! With `count:N/M`, the tests that pass the filters are sorted by name and
+//! dealt out to the M parts in turn, which balances the parts. With
+//! `hash:N/M`, a test's part only depends on its name, so that adding or
+//! removing a test does not move the others to another part.
+
+use crate::{chaos::fnv1a, PartitionSetting};
+
+/// Returns whether each of the tests called `names` is in the part of
+/// `setting`.
+pub(crate) fn select(setting: PartitionSetting, names: &[&str]) -> Vec<bool> {
+    match setting {
+        PartitionSetting::Count { shard, total } => {
+            let mut order: Vec<usize> = (0..names.len()).collect();
+            order.sort_by_key(|&i| names[i]);
+            let mut selected = vec![false; names.len()];
+            for (position, i) in order.into_iter().enumerate() {
+                selected[i] = position % total + 1 == shard;
+            }
+            selected
+        }
+        PartitionSetting::Hash { shard, total } => names
+            .iter()
+            .map(|name| fnv1a(name.as_bytes()) % total as u64 + 1 == shard as u64)
+            .collect(),
+    }
+}
@@ -0,0 +1,66 @@
+//! Messages of panic payloads.
+//!
+//! `panic!` and `assert!` panic with a `&str` or a `String`. Other payloads,
+//! from `std::panic::panic_any` or `resume_unwind`, are shown with the
+//! formatter registered for their type with [`register_payload_formatter`],
+//! e.g. one that prints the cause chain and backtrace of an `anyhow::Error`.
+//! A `Box<dyn Error + Send + Sync>` is shown with its sources without one.
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    error::Error,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::returned;
+
+type Formatter = Box<dyn Fn(&(dyn Any + Send)) -> Option<String> + Send + Sync>;
+
+static FORMATTERS: Mutex<Vec<Formatter>> = Mutex::new(vec![]);
+
+/// Shows panics with a payload of type `T` with `format`, instead of as
+/// "test panicked".
+///
+/// Register formatters before the tests run, e.g. in a
+/// [`tests!`](crate::tests) function:
+///
+/// ```ignore
+/// async_test::register_payload_formatter(|error: &anyhow::Error| format!("{error:?}"));
+/// ```
+///
+/// The formatter that was registered last for a type is used.
+pub fn register_payload_formatter<T: Any>(format: fn(&T) -> String) {
+    formatters().push(Box::new(move |payload| {
+        payload.downcast_ref::<T>().map(format)
+    }));
+}
+
+fn formatters() -> MutexGuard<'static, Vec<Formatter>> {
+    // A formatter that panicked leaves nothing half-done.
+    FORMATTERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the message of a panic if it has one.
+pub(crate) fn message(payload: &(dyn Any + Send)) -> Option<Cow<'_, str>> {
+    // The `panic` information is just an `Any` object representing the
+    // value the panic was invoked with. For most panics (which use
+    // `panic!` like `println!`), this is either `&str` or `String`.
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return Some(Cow::Borrowed(message));
+    }
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return Some(Cow::Borrowed(message));
+    }
+    if let Some(message) = formatters().iter().rev().find_map(|format| format(payload)) {
+        return Some(Cow::Owned(message));
+    }
+    let error = payload.downcast_ref::<Box<dyn Error + Send + Sync>>()?;
+    let sources = std::iter::successors(error.source(), |&error| error.source());
+    Some(Cow::Owned(returned::with_sources(
+        error.to_string(),
+        sources,
+    )))
+}
@@ -5,16 +5,27 @@
 //! - `color`
 //! - `format` (and `quiet`)
 //! - `logfile`
+//! - `verbose` (for `--list`)
 
 use std::{
     fs::File,
     io::{self, stdout},
 };
 
-use crate::{Arguments, Trial};
+use clap::{CommandFactory, ValueEnum};
+use serde_json::Value;
+
+use crate::{
+    registration::{Kind, Registration},
+    report::{self, HarnessInfo, ListedTest, TestListReport, SCHEMA_VERSION},
+    Arguments, CollectionError, Context, FormatSetting, Trial,
+};
 
 pub(crate) struct Printer {
     out: Box<dyn io::Write>,
+    json: bool,
+    /// Whether JSON is indented.
+    pretty: bool,
 }
 
 impl Printer {
@@ -29,17 +40,169 @@ impl Printer {
             Box::new(stdout()) as Box<_>
         };
 
-        Self { out }
+        Self {
+            out,
+            json: matches!(
+                args.format,
+                Some(FormatSetting::Json | FormatSetting::JsonPretty)
+            ),
+            pretty: args.format == Some(FormatSetting::JsonPretty),
+        }
+    }
+
+    /// Prints a list of all tests, followed by the [`tests!`](crate::tests)
+    /// functions that panicked. Used if `--list` is set.
+    pub(crate) fn print_list(
+        &mut self,
+        tests: &[Trial],
+        context: &Context,
+        errors: &[CollectionError],
+        ignored: bool,
+        verbose: bool,
+    ) {
+        if self.json {
+            Self::write_list_json(tests, errors, ignored, self.pretty, &mut self.out).unwrap();
+            return;
+        }
+
+        Self::write_list(tests, context, ignored, verbose, &mut self.out).unwrap();
+        for error in errors {
+            writeln!(
+                self.out,
+                "error: collecting tests in {} panicked\n{}",
+                error.builder, error.message
+            )
+            .unwrap();
+        }
+    }
+
+    /// Prints every registered `tests!` function and setup. Used if
+    /// `--explain-registration` is set.
+    pub(crate) fn print_registrations(&mut self, registrations: &[Registration]) {
+        for registration in registrations {
+            let kind = match registration.kind {
+                Kind::TestBuilder => "tests!",
+                Kind::Setup => "setup!",
+            };
+            write!(self.out, "{}: {kind}", registration.path).unwrap();
+            if let Some(suite) = registration.suite {
+                write!(self.out, " in suite {suite}").unwrap();
+            }
+            if let Some(reason) = &registration.ignored {
+                write!(self.out, " (ignored: {reason})").unwrap();
+            }
+            writeln!(self.out).unwrap();
+        }
+    }
+
+    /// Prints the version, formats and flags of the harness as JSON. Used if
+    /// `--harness-info` is set.
+    pub(crate) fn print_harness_info(&mut self) {
+        let info = HarnessInfo {
+            schema_version: SCHEMA_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            formats: FormatSetting::value_variants()
+                .iter()
+                .filter_map(|format| Some(format.to_possible_value()?.get_name().to_owned()))
+                .collect(),
+            flags: Arguments::command()
+                .get_arguments()
+                .filter_map(|arg| {
+                    let flag = match (arg.get_long(), arg.get_short()) {
+                        (Some(long), _) => format!("--{long}"),
+                        (None, Some(short)) => format!("-{short}"),
+                        (None, None) => return None,
+                    };
+                    Some(flag)
+                })
+                .collect(),
+        };
+        report::write_json(&mut self.out, &info, self.pretty).unwrap();
     }
 
-    /// Prints a list of all tests. Used if `--list` is set.
-    pub(crate) fn print_list(&mut self, tests: &[Trial], ignored: bool) {
-        Self::write_list(tests, ignored, &mut self.out).unwrap();
+    /// Prints the results of a test read from a report. Used if
+    /// `--extract-output` is set.
+    pub(crate) fn print_stored_results(&mut self, name: &str, results: &[Value]) {
+        Self::write_stored_results(name, results, &mut self.out).unwrap();
+    }
+
+    fn write_stored_results(
+        name: &str,
+        results: &[Value],
+        mut out: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let attempts = results.len();
+        for (i, result) in results.iter().enumerate() {
+            let status = result["status"].as_str().unwrap_or("skipped");
+            write!(out, "--- {name}: {status}")?;
+            if let Some(secs) = result["duration_secs"].as_f64() {
+                write!(out, " after {secs:.3}s")?;
+            }
+            if let Some(message) = result["message"].as_str() {
+                write!(out, " ({message})")?;
+            }
+            if attempts > 1 {
+                write!(out, " (attempt {} of {attempts})", i + 1)?;
+            }
+            writeln!(out, " ---")?;
+
+            if let Some(description) = result["description"].as_str() {
+                writeln!(out, "{description}\n")?;
+            }
+            if let Some(links) = result["links"].as_array() {
+                for link in links {
+                    if let (Some(label), Some(url)) = (link["label"].as_str(), link["url"].as_str())
+                    {
+                        writeln!(out, "{label}: {url}")?;
+                    }
+                }
+            }
+            if let Some(output) = result["output"].as_str() {
+                writeln!(out, "{output}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_list_json(
+        tests: &[Trial],
+        errors: &[CollectionError],
+        ignored: bool,
+        pretty: bool,
+        out: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let report = TestListReport {
+            schema_version: SCHEMA_VERSION,
+            tests: tests
+                .iter()
+                .filter(|test| !ignored || test.info.is_ignored)
+                .map(|test| ListedTest {
+                    name: test.info.name.clone(),
+                    ignored: test.info.is_ignored,
+                    tags: test.info.tags.clone(),
+                    requires: test.requires.iter().map(|(ty, _)| ty.to_string()).collect(),
+                    unmet_cfg: test.info.unmet_cfg.clone(),
+                    services: test
+                        .info
+                        .capabilities
+                        .iter()
+                        .map(|c| c.as_str().to_owned())
+                        .collect(),
+                    links: test.info.links.clone(),
+                    expected_failure: test.info.expected_failure.clone(),
+                    ignore_reason: test.info.ignore_reason.clone(),
+                })
+                .collect(),
+            collection_errors: errors.to_vec(),
+        };
+        report::write_json(out, &report, pretty)
     }
 
     pub(crate) fn write_list(
         tests: &[Trial],
+        context: &Context,
         ignored: bool,
+        verbose: bool,
         mut out: impl std::io::Write,
     ) -> std::io::Result<()> {
         for test in tests {
@@ -51,6 +214,44 @@ impl Printer {
             }
 
             writeln!(out, "{}: test", test.info.name)?;
+
+            if verbose {
+                if !test.info.tags.is_empty() {
+                    writeln!(out, "    tags: {}", test.info.tags.join(", "))?;
+                }
+                if let Some(reason) = &test.info.ignore_reason {
+                    writeln!(out, "    ignored: {reason}")?;
+                }
+                if let Some(cfg) = &test.info.unmet_cfg {
+                    writeln!(out, "    skipped: requires cfg({cfg})")?;
+                }
+                if !test.info.capabilities.is_empty() {
+                    let services: Vec<_> =
+                        test.info.capabilities.iter().map(|c| c.as_str()).collect();
+                    writeln!(out, "    requires: {}", services.join(", "))?;
+                }
+                if test.info.main_thread {
+                    writeln!(out, "    runs on the main thread")?;
+                }
+                for link in &test.info.links {
+                    writeln!(out, "    {}: {}", link.label, link.url)?;
+                }
+                if let Some(reason) = &test.info.expected_failure {
+                    writeln!(out, "    expected to fail: {reason}")?;
+                }
+                for (ty, id) in &test.requires {
+                    // Every setup runs at most once per run and its value is
+                    // shared by all tests that require it.
+                    match context.values.get(id) {
+                        Some(setup) => writeln!(
+                            out,
+                            "    requires {ty} (shared, set up by {}::{})",
+                            setup.module, setup.function
+                        )?,
+                        None => writeln!(out, "    requires {ty} (missing setup)")?,
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -0,0 +1,182 @@
+//! What `inventory` registered: every [`tests!`](crate::tests) function and
+//! [`setup!`](crate::setup), for `--explain-registration`.
+//!
+//! Registrations are collected from everything linked into the binary. If a
+//! library that is linked into several test binaries registers tests or
+//! setups itself, they show up in each of them. With `--own-crate-only`,
+//! only the registrations of the crate of the binary are collected. That
+//! crate is the name of the executable, without the hash that cargo appends
+//! to test binaries. The fixtures of the harness itself, like
+//! [`TestArgs`](crate::TestArgs), are always collected.
+//!
+//! Registrations can also be put in a suite with `suite = "name"`. Those are
+//! only collected with `--suite name`, which leaves out the tests registered
+//! without a suite. Setups without a suite are collected either way, so that
+//! suites can share them.
+
+use std::{any::TypeId, collections::HashMap, process};
+
+use crate::builder::{SetupInit, TestBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    TestBuilder,
+    Setup,
+}
+
+/// A registered `tests!` function or setup.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    pub(crate) kind: Kind,
+    /// The path of the function, e.g. `my_tests::database`.
+    pub(crate) path: String,
+    pub(crate) suite: Option<&'static str>,
+    /// Why it is not collected, if it isn't.
+    pub(crate) ignored: Option<String>,
+}
+
+/// The crate of the running binary, exiting if it is unknown.
+pub(crate) fn own_crate() -> String {
+    let exe = std::env::current_exe().unwrap_or_else(|e| {
+        eprintln!("error: failed to find the path of the test binary: {e}");
+        process::exit(1)
+    });
+    let name = exe
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    crate_of_binary(name).replace('-', "_")
+}
+
+/// Strips the `-0123456789abcdef` that cargo appends to test binaries.
+fn crate_of_binary(name: &str) -> &str {
+    match name.rsplit_once('-') {
+        Some((krate, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            krate
+        }
+        _ => name,
+    }
+}
+
+/// Returns whether `module` is in `krate` or the harness, or in any crate if
+/// `krate` is `None`.
+pub(crate) fn in_scope(module: &str, krate: Option<&str>) -> bool {
+    let Some(krate) = krate else {
+        return true;
+    };
+    let top = module.split("::").next().unwrap_or(module);
+    top == krate || top == env!("CARGO_CRATE_NAME")
+}
+
+/// Which registrations are collected.
+#[derive(Debug, Default)]
+pub(crate) struct Scope {
+    /// The crate of the binary, with `--own-crate-only`.
+    pub(crate) krate: Option<String>,
+    /// The suite of `--suite`.
+    pub(crate) suite: Option<String>,
+}
+
+impl Scope {
+    /// Returns why a registration in `module` and `suite` is not collected,
+    /// if it isn't.
+    pub(crate) fn excludes(&self, kind: Kind, module: &str, suite: Option<&str>) -> Option<String> {
+        if !in_scope(module, self.krate.as_deref()) {
+            return Some("outside of this crate, with --own-crate-only".to_owned());
+        }
+        match (suite, self.suite.as_deref()) {
+            (Some(suite), Some(selected)) if suite != selected => {
+                Some(format!("in suite {suite}, not {selected}"))
+            }
+            (Some(suite), None) => Some(format!("in suite {suite}, without --suite")),
+            (None, Some(selected)) if kind == Kind::TestBuilder => {
+                Some(format!("not in suite {selected}"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lists every registration, in the order they are collected.
+pub(crate) fn registrations(scope: &Scope) -> Vec<Registration> {
+    let mut registrations = vec![];
+
+    for builder in inventory::iter::<TestBuilder>() {
+        registrations.push(Registration {
+            kind: Kind::TestBuilder,
+            path: format!("{}::{}", builder.module, builder.function),
+            suite: builder.suite,
+            ignored: scope.excludes(Kind::TestBuilder, builder.module, builder.suite),
+        });
+    }
+
+    // A later setup for the same type replaces an earlier one.
+    let mut used: HashMap<TypeId, String> = HashMap::new();
+    let setups: Vec<_> = inventory::iter::<SetupInit>()
+        .map(|setup| {
+            let path = format!("{}::{}", setup.module, setup.function);
+            let excluded = scope.excludes(Kind::Setup, setup.module, setup.suite);
+            if excluded.is_none() {
+                used.insert((setup.type_id)(), path.clone());
+            }
+            (setup, path, excluded)
+        })
+        .collect();
+    for (setup, path, excluded) in setups {
+        let ignored = match used.get(&(setup.type_id)()) {
+            _ if excluded.is_some() => excluded,
+            Some(winner) if *winner != path => Some(format!(
+                "{winner} sets up the same type and is used instead"
+            )),
+            _ => None,
+        };
+        registrations.push(Registration {
+            kind: Kind::Setup,
+            path,
+            suite: setup.suite,
+            ignored,
+        });
+    }
+    registrations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crate_of_test_binary() {
+        assert_eq!(crate_of_binary("mixed_bag-52058838b6e24a97"), "mixed_bag");
+        assert_eq!(crate_of_binary("my-tool"), "my-tool");
+        assert!(in_scope("mixed_bag::inner", Some("mixed_bag")));
+        assert!(!in_scope("mixed_bag_helpers", Some("mixed_bag")));
+        assert!(in_scope("async_test", Some("mixed_bag")));
+    }
+
+    #[test]
+    fn suites() {
+        let default = Scope::default();
+        assert_eq!(default.excludes(Kind::TestBuilder, "a", None), None);
+        assert_eq!(
+            default
+                .excludes(Kind::TestBuilder, "a", Some("db"))
+                .unwrap(),
+            "in suite db, without --suite"
+        );
+
+        let db = Scope {
+            krate: None,
+            suite: Some("db".to_owned()),
+        };
+        assert_eq!(db.excludes(Kind::TestBuilder, "a", Some("db")), None);
+        assert_eq!(db.excludes(Kind::Setup, "a", None), None);
+        assert_eq!(
+            db.excludes(Kind::TestBuilder, "a", None).unwrap(),
+            "not in suite db"
+        );
+        assert_eq!(
+            db.excludes(Kind::Setup, "a", Some("ui")).unwrap(),
+            "in suite ui, not db"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! `--repeat` and `--repeat-until-failure`: run each test many times.
+//!
+//! The iterations of a test run one after another in the same task, each
+//! with a fresh future from the test's runner. The test is reported once,
+//! with the outcome of the first failing iteration if there is one.
+//!
+//! The first iteration uses the fixtures of the run, like without `--repeat`.
+//! Every further iteration sets up the fixtures of the test again, so that it
+//! does not see the state earlier iterations left behind, and their setup
+//! counts towards its duration. Like the fixtures of the run, they are never
+//! dropped, since tests hold `&'static` references to them.
+
+use std::{future::Future, num::NonZeroUsize, time::Duration};
+
+use crate::{clock::Clock, report::Iterations, Arguments, Outcome};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Repeat {
+    /// Run every test this many times.
+    Times(NonZeroUsize),
+    /// Run every test until it fails or has been repeated for this long.
+    UntilFailure(Duration),
+}
+
+impl Repeat {
+    pub(crate) fn new(args: &Arguments) -> Option<Self> {
+        match (
+            args.repeat.and_then(NonZeroUsize::new),
+            args.repeat_until_failure,
+        ) {
+            (Some(times), _) => Some(Self::Times(times)),
+            (None, Some(duration)) => Some(Self::UntilFailure(duration)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Runs the futures returned by `iteration` until `repeat` is done or one of
+/// them fails.
+pub(crate) async fn repeated<F>(
+    repeat: Option<Repeat>,
+    clock: &dyn Clock,
+    mut iteration: impl FnMut() -> F,
+) -> (Outcome, Option<Iterations>)
+where
+    F: Future<Output = Outcome>,
+{
+    let Some(repeat) = repeat else {
+        return (iteration().await, None);
+    };
+
+    let started = clock.now();
+    let mut durations = vec![];
+    loop {
+        let start = clock.now();
+        let outcome = iteration().await;
+        durations.push(clock.now() - start);
+
+        let done = match repeat {
+            Repeat::Times(times) => durations.len() == times.get(),
+            Repeat::UntilFailure(duration) => clock.now() - started >= duration,
+        };
+        if done || matches!(outcome, Outcome::Failed(_)) {
+            let total: Duration = durations.iter().sum();
+            let iterations = Iterations {
+                count: durations.len(),
+                min_secs: durations.iter().min().unwrap().as_secs_f64(),
+                mean_secs: total.as_secs_f64() / durations.len() as f64,
+                max_secs: durations.iter().max().unwrap().as_secs_f64(),
+            };
+            return (outcome, Some(iterations));
+        }
+    }
+}
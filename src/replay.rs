@@ -0,0 +1,58 @@
+//! `--extract-output`: shows the stored results of a single test.
+//!
+//! A run with `--format json --logfile PATH` stores every result, including
+//! the failure output and backtrace. Reading one failure back from there is
+//! much quicker than running the whole suite again.
+
+use std::process;
+
+use serde_json::Value;
+
+use crate::{printer::Printer, Arguments, Conclusion};
+
+pub(crate) fn extract_output(args: &Arguments, name: &str) -> Conclusion {
+    let path = args
+        .from_report
+        .as_deref()
+        .expect("--extract-output requires --from-report");
+    let report = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("error: failed to read report {path}: {e}");
+        process::exit(1)
+    });
+
+    let results = find_results(&report, name);
+    if results.is_empty() {
+        eprintln!("error: no results for test `{name}` in {path}");
+        process::exit(1)
+    }
+
+    let mut printer = Printer::new(args);
+    printer.print_stored_results(name, &results);
+
+    // Replay the outcome of the last attempt.
+    let mut conclusion = Conclusion::empty();
+    match results.last().unwrap()["status"].as_str() {
+        Some("passed") => conclusion.num_passed = 1,
+        Some(_) => conclusion.num_failed = 1,
+        None => conclusion.num_filtered_out = 1,
+    }
+    conclusion
+}
+
+/// Returns the `test-finished` and `test-skipped` records of the test called
+/// `name`, in the order they were written.
+///
+/// Lines that are not JSON objects are skipped, so that a report can be read
+/// even if something else was written to the same file.
+fn find_results(report: &str, name: &str) -> Vec<Value> {
+    report
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|record| {
+            matches!(
+                record["type"].as_str(),
+                Some("test-finished" | "test-skipped")
+            ) && record["name"] == name
+        })
+        .collect()
+}
@@ -0,0 +1,745 @@
+//! Machine-readable output.
+//!
+//! With `--format json`, every line of output is a single JSON object. Each
+//! object carries a `schema_version` next to its contents:
+//!
+//! - a run prints one [`Event`] per line, wrapped in an [`EventRecord`]. The
+//!   last event of a run is [`Event::RunFinished`] with the [`RunSummary`].
+//! - `--list` prints a single [`TestListReport`].
+//!
+//! `--format json-pretty` prints the same objects indented over several lines,
+//! for reading them while debugging an integration.
+//!
+//! A [`Reporter`] passed to [`run_with_reporter`](crate::run_with_reporter)
+//! receives the same events instead, for output formats of its own.
+//!
+//! # Stability
+//!
+//! The schema only evolves additively: new fields, event types and enum
+//! values may be added in any release, but existing ones are never removed,
+//! renamed or given a different meaning. Consumers should ignore fields and
+//! event types they do not know about. A change that cannot be made this way
+//! bumps [`SCHEMA_VERSION`].
+//!
+//! # Consuming reports
+//!
+//! With the `report-types` feature, all types here also implement
+//! `Deserialize`, and
+#![cfg_attr(feature = "report-types", doc = "[`RunReport`]")]
+#![cfg_attr(not(feature = "report-types"), doc = "`RunReport`")]
+//! assembles a whole run from the event stream. Values added in later
+//! versions deserialize as the `Unknown` variants, so tools keep working with
+//! newer harnesses.
+
+use std::{collections::BTreeMap, fmt, io};
+
+#[cfg(feature = "report-types")]
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The version of the schema described by this module.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A line of the event stream.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct EventRecord {
+    /// Always [`SCHEMA_VERSION`] for output of this version of the crate.
+    pub schema_version: u32,
+
+    /// The test binary that the event came from, in a report combined by
+    /// [`merge_reports`](crate::merge_reports). Events without it add up
+    /// those of all binaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+
+    /// The event, tagged with its `type`.
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// Receives the events of a run in place of the regular output, see
+/// [`run_with_reporter`](crate::run_with_reporter).
+///
+/// Events are reported from a thread of the harness, in the order of the
+/// JSON stream. Closures that take an `&Event` are reporters, too.
+pub trait Reporter: Send {
+    /// Called for every event, the last one being [`Event::RunFinished`].
+    fn report(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event) + Send> Reporter for F {
+    fn report(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+/// Writes `value` followed by a newline, indented if `pretty` is set.
+///
+/// All JSON output goes through here, so that both formats serialize the
+/// same schema.
+pub(crate) fn write_json(
+    mut out: impl io::Write,
+    value: &impl Serialize,
+    pretty: bool,
+) -> io::Result<()> {
+    match pretty {
+        true => serde_json::to_writer_pretty(&mut out, value)?,
+        false => serde_json::to_writer(&mut out, value)?,
+    }
+    writeln!(out)
+}
+
+impl EventRecord {
+    /// Creates a record of the current schema version.
+    ///
+    /// Only useful for tools that produce reports themselves, e.g. to merge
+    /// the results of several runs.
+    pub fn new(event: Event) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            binary: None,
+            event,
+        }
+    }
+}
+
+/// Something that happened during a run.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[serde(tag = "type", rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Event {
+    /// The run started. This is always the first event.
+    RunStarted {
+        /// The number of tests that will be run.
+        test_count: usize,
+        /// The number of tests that were skipped before the run started.
+        skip_count: usize,
+        /// Warnings and notes emitted while collecting tests.
+        diagnostics: Vec<Diagnostic>,
+        /// The seed of `--sample` or `--sample-percent`, if only a sample of
+        /// the tests runs.
+        #[serde(default)]
+        sample_seed: Option<u64>,
+        /// The tests picked by the sample, in the order they were collected.
+        /// Empty without `--sample` or `--sample-percent`.
+        #[serde(default)]
+        sampled: Vec<String>,
+        /// The seed of `--shuffle` or `--shuffle-seed`, if the tests run in
+        /// a random order.
+        #[serde(default)]
+        shuffle_seed: Option<u64>,
+        /// The variables set by `--env-file`. Only their keys, as the
+        /// values may be secrets.
+        #[serde(default)]
+        env_file_keys: Vec<String>,
+    },
+
+    /// A `tests!` function panicked while collecting tests.
+    CollectionError(CollectionError),
+
+    /// A `setup!` function finished.
+    SetupFinished {
+        /// The name of the setup function.
+        name: String,
+        duration_secs: f64,
+    },
+
+    /// A test started running. Comes with every [`Event::TestFinished`],
+    /// unless the run was killed before the test finished.
+    TestStarted { name: String },
+
+    /// A test has been running for longer than the slow period. Reported on
+    /// every tick, without the batching applied to human-readable output.
+    TestSlow {
+        name: String,
+        /// The time since the test started.
+        elapsed_secs: f64,
+    },
+
+    /// A test was not run.
+    TestSkipped(SkippedTest),
+
+    /// A test finished running.
+    TestFinished(TestResult),
+
+    /// The run was canceled, e.g. with `RunHandle::cancel`. No more tests
+    /// are started, but running ones still report their results, except
+    /// after Ctrl-C or another signal for those that take too long to finish.
+    RunCanceled {
+        /// Why the run was canceled: the reason given to the handle, the
+        /// test that failed with `--fail-fast`, `interrupt` after Ctrl-C, or
+        /// the name of the signal, `SIGTERM` or `SIGHUP`.
+        reason: String,
+    },
+
+    /// The number of tests that run at once was lowered, with
+    /// `--adaptive-concurrency`.
+    ConcurrencyReduced(ConcurrencyReduction),
+
+    /// The differences to an earlier report, with `--compare-with`. Reported
+    /// right before [`Event::RunFinished`].
+    Comparison(Comparison),
+
+    /// How the fixtures were used, if any tests required one. Reported right
+    /// before [`Event::RunFinished`].
+    FixtureUsage {
+        /// The fixtures, sorted by name.
+        fixtures: Vec<FixtureUsage>,
+    },
+
+    /// The run finished. This is always the last event.
+    RunFinished(RunSummary),
+
+    /// An event type added in a later schema version.
+    #[serde(other, skip_serializing)]
+    Unknown,
+}
+
+/// A warning or note emitted while collecting tests.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    /// The path of the `tests!` function that emitted it.
+    pub builder: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum DiagnosticLevel {
+    Warning,
+    Note,
+    /// A level added in a later schema version.
+    #[serde(other, skip_serializing)]
+    Unknown,
+}
+
+/// Why a test was not run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The test is ignored, or only ignored tests were requested.
+    Ignored,
+    /// The test does not match the name filters or the filter expressions.
+    Filter,
+    /// The test does not match the tag filters.
+    Tag,
+    /// The test is in another part of `--partition`.
+    Partition,
+    /// The test requires a `cfg` that does not hold on this platform.
+    Cfg,
+    /// The test requires a service, like the network, that is unavailable.
+    Unavailable,
+    /// The test requires a service, like the network, and `--offline` is
+    /// set.
+    Offline,
+    /// The test was not started because `--time-budget` ran out.
+    Budget,
+    /// The test was not started because another test failed with
+    /// `--fail-fast`.
+    FailFast,
+    /// The test was not started because `--run-timeout` passed.
+    RunTimeout,
+    /// The test was not started because the run was canceled.
+    Canceled,
+    /// The test was not started, or did not finish in time, because the run
+    /// was interrupted by a signal.
+    Interrupted,
+    /// The test was skipped for another reason, or for one added in a later
+    /// schema version.
+    #[serde(other)]
+    Other,
+}
+
+/// A test that was not run.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct SkippedTest {
+    pub name: String,
+    pub reason: SkipReason,
+    /// Details about the reason, e.g. the `cfg` the test requires.
+    pub message: Option<String>,
+}
+
+/// The outcome of a single test.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct TestResult {
+    pub name: String,
+    pub status: TestStatus,
+    pub duration_secs: f64,
+    /// Whether the test ran for longer than the slow period.
+    pub slow: bool,
+    /// The failure message, including the backtrace if one was captured.
+    /// With several failures, they are numbered.
+    pub output: Option<String>,
+    /// Every reason the test failed, in the order they happened: failed
+    /// [`check!`](crate::check)s, then the panic if there was one.
+    #[serde(default)]
+    pub failures: Vec<Failure>,
+    /// What the test checks, see `Trial::with_description`.
+    pub description: Option<String>,
+    /// See `Trial::with_link`.
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Why the test is expected to fail, see `Trial::with_expected_failure`.
+    /// A `passed` test with this set failed as expected, a `failed` one
+    /// passed unexpectedly.
+    #[serde(default)]
+    pub expected_failure: Option<String>,
+    /// Set if the test was repeated with `--repeat` or
+    /// `--repeat-until-failure`.
+    #[serde(default)]
+    pub iterations: Option<Iterations>,
+    /// Set if the test ran with `--verify-deterministic`.
+    #[serde(default)]
+    pub determinism: Option<Determinism>,
+    /// How many times the test ran. More than 1 if it failed and was
+    /// retried, see `TrialHandle::retries`.
+    #[serde(default = "one")]
+    pub attempts: usize,
+    /// Set if the harness was suspended while the test ran, e.g. by the
+    /// terminal or because the machine slept. This time is not included in
+    /// `duration_secs`.
+    #[serde(default)]
+    pub suspended_secs: Option<f64>,
+    /// How long the test waited for one of the `--test-tasks` before it
+    /// started. Not included in `duration_secs`.
+    #[serde(default)]
+    pub queued_secs: f64,
+    /// How long the test waited for its fixtures to be set up before it
+    /// started. Not included in `duration_secs`, so that the cost of a
+    /// fixture is not attributed to the tests that happen to wait for it.
+    #[serde(default)]
+    pub setup_wait_secs: f64,
+    /// How long the test was expected to take, if it says, see
+    /// `Trial::with_expected_duration`.
+    #[serde(default)]
+    pub expected_duration_secs: Option<f64>,
+    /// The values the test recorded with `TestMeta::record`.
+    #[serde(default)]
+    pub meta: BTreeMap<String, MetaValue>,
+}
+
+fn one() -> usize {
+    1
+}
+
+/// A value recorded with `TestMeta::record`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[serde(untagged)]
+pub enum MetaValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for MetaValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => value.fmt(f),
+            Self::Int(value) => value.fmt(f),
+            Self::Float(value) => value.fmt(f),
+            Self::String(value) => value.fmt(f),
+        }
+    }
+}
+
+macro_rules! meta_value_from {
+    ($($ty:ty => $variant:ident,)*) => {
+        $(
+            impl From<$ty> for MetaValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+meta_value_from! {
+    bool => Bool,
+    i8 => Int,
+    i16 => Int,
+    i32 => Int,
+    i64 => Int,
+    u8 => Int,
+    u16 => Int,
+    u32 => Int,
+    f32 => Float,
+    f64 => Float,
+    &str => String,
+    String => String,
+}
+
+// Counts beyond `i64::MAX` don't happen in practice, but saturate if they do.
+impl From<u64> for MetaValue {
+    fn from(value: u64) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<usize> for MetaValue {
+    fn from(value: usize) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+/// The concurrent runs of a test under `--verify-deterministic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Determinism {
+    pub runs: usize,
+    pub failed: usize,
+}
+
+impl Determinism {
+    /// Returns true if some, but not all, runs failed.
+    pub fn is_nondeterministic(&self) -> bool {
+        self.failed > 0 && self.failed < self.runs
+    }
+}
+
+/// The iterations of a repeated test.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Iterations {
+    /// The number of iterations that ran. If the test failed, the last one
+    /// is the one that failed.
+    pub count: usize,
+    pub min_secs: f64,
+    pub mean_secs: f64,
+    pub max_secs: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Failure {
+    /// The message, including the backtrace of a panic if one was captured.
+    pub message: String,
+    /// Where the test failed, as `file:line:column`.
+    pub location: Option<String>,
+}
+
+/// A link attached to a test, e.g. to its tracking issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Link {
+    /// What the link points to, e.g. `issue`.
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    /// A status added in a later schema version.
+    #[serde(other, skip_serializing)]
+    Unknown,
+}
+
+/// Totals for a whole run.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct RunSummary {
+    pub duration_secs: f64,
+    /// The number of tests that were expected to run.
+    pub test_count: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// The number of `tests!` functions that panicked.
+    pub collection_errors: usize,
+    /// The number of tests left out by filters, tags or `--sample`. Included
+    /// in `skipped`.
+    #[serde(default)]
+    pub filtered_out: usize,
+    /// The number of ignored tests. Included in `skipped`.
+    #[serde(default)]
+    pub ignored: usize,
+    /// The number of tests left out by `--sample`. Included in `skipped` and
+    /// `filtered_out`.
+    #[serde(default)]
+    pub sampled_out: usize,
+    /// The number of tests that require an external service, skipped by
+    /// `--offline`. Included in `skipped`.
+    #[serde(default)]
+    pub offline: usize,
+    /// The number of tests that were not started because `--time-budget`
+    /// ran out, a test failed with `--fail-fast`, `--run-timeout` passed or
+    /// the run was canceled or interrupted. Not included in `skipped`.
+    #[serde(default)]
+    pub not_run: usize,
+    /// The number of tests that ran for longer than their timeout. Not
+    /// included in `failed`.
+    #[serde(default)]
+    pub timed_out: usize,
+    /// The number of tests that passed on a retry. Included in `passed`.
+    #[serde(default)]
+    pub flaky: usize,
+    /// The total time that tests waited for one of the `--test-tasks`. If
+    /// this is a large part of the run, more tasks may speed it up.
+    #[serde(default)]
+    pub queue_wait_secs: f64,
+    /// How the durations of the finished tests are distributed. `None` if no
+    /// test finished.
+    #[serde(default)]
+    pub durations: Option<DurationSummary>,
+}
+
+/// The distribution of the durations of the tests in a run, to follow how it
+/// shifts over time and not only the total.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct DurationSummary {
+    pub p50_secs: f64,
+    pub p90_secs: f64,
+    pub p99_secs: f64,
+    pub max_secs: f64,
+    /// The number of tests per duration, from the shortest to the longest
+    /// bucket. The buckets are the same in every run.
+    pub histogram: Vec<HistogramBucket>,
+}
+
+/// The tests whose durations are below `below_secs`, and not below the
+/// bound of the previous bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct HistogramBucket {
+    /// `None` for the last bucket, which has no upper bound.
+    pub below_secs: Option<f64>,
+    pub count: usize,
+}
+
+/// The output of `--list --format json`.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct TestListReport {
+    /// Always [`SCHEMA_VERSION`] for output of this version of the crate.
+    pub schema_version: u32,
+    pub tests: Vec<ListedTest>,
+    /// The `tests!` functions that panicked, with their panic messages.
+    pub collection_errors: Vec<CollectionError>,
+}
+
+/// The output of `--harness-info`, for tools that wrap the harness to find
+/// out what it supports.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct HarnessInfo {
+    /// Always [`SCHEMA_VERSION`] for output of this version of the crate.
+    pub schema_version: u32,
+    /// The version of the `async-test` crate, e.g. `0.3.0`.
+    pub version: String,
+    /// The values of `--format`.
+    pub formats: Vec<String>,
+    /// Every flag that is understood, with its dashes, e.g. `--exact`.
+    pub flags: Vec<String>,
+}
+
+/// The differences between a run and an earlier report.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct Comparison {
+    /// The path of the earlier report.
+    pub baseline: String,
+    /// Tests that passed before and failed now.
+    pub newly_failing: Vec<TestChange>,
+    /// Tests that failed before and passed now.
+    pub newly_passing: Vec<TestChange>,
+    /// Tests that are slow now but were not before.
+    pub newly_slow: Vec<TestChange>,
+    /// Tests that ran now but were not in the earlier report.
+    pub added: Vec<String>,
+    /// Tests in the earlier report that were not collected now.
+    pub removed: Vec<String>,
+}
+
+/// A lowered number of tasks, see [`Event::ConcurrencyReduced`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct ConcurrencyReduction {
+    /// The test whose failure lowered it. It is retried.
+    pub test: String,
+    /// The resource that ran out, e.g. `too many open files`.
+    pub reason: String,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// How a fixture was used in a run.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct FixtureUsage {
+    /// The `setup!` function of the fixture.
+    pub name: String,
+    /// The number of tests that required it.
+    pub tests: usize,
+    /// How long it took to set up. `None` if the run ended before it was.
+    #[serde(default)]
+    pub init_secs: Option<f64>,
+    /// The most tests that waited for it to be set up at the same time.
+    #[serde(default)]
+    pub peak_waiters: usize,
+}
+
+/// A test in a [`Comparison`], with its duration in both runs.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct TestChange {
+    pub name: String,
+    pub duration_secs: f64,
+    pub previous_duration_secs: f64,
+}
+
+/// A test in a [`TestListReport`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct ListedTest {
+    pub name: String,
+    pub ignored: bool,
+    pub tags: Vec<String>,
+    /// The types of the fixtures the test requires.
+    pub requires: Vec<String>,
+    /// Set if the test will be skipped because it requires a `cfg` that does
+    /// not hold on this platform.
+    pub unmet_cfg: Option<String>,
+    /// The services the test requires, like `network` and `docker`.
+    #[serde(default)]
+    pub services: Vec<String>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+    #[serde(default)]
+    pub expected_failure: Option<String>,
+    /// Why the test is ignored, if it says.
+    #[serde(default)]
+    pub ignore_reason: Option<String>,
+}
+
+/// A `tests!` function that panicked while collecting tests.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "report-types", derive(Deserialize))]
+#[non_exhaustive]
+pub struct CollectionError {
+    /// The path of the function, e.g. `tidy::collect`.
+    pub builder: String,
+    /// The panic message.
+    pub message: String,
+}
+
+/// A whole run, assembled from the lines of a `--format json` event stream.
+#[cfg(feature = "report-types")]
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct RunReport {
+    /// The highest schema version seen in the stream.
+    pub schema_version: u32,
+    pub diagnostics: Vec<Diagnostic>,
+    pub collection_errors: Vec<CollectionError>,
+    /// The tests that ran, in the order they finished.
+    pub tests: Vec<TestResult>,
+    pub skipped: Vec<SkippedTest>,
+    /// Set if the run was compared with an earlier report.
+    pub comparison: Option<Comparison>,
+    /// How the fixtures were used, empty if no test required one.
+    pub fixtures: Vec<FixtureUsage>,
+    /// The times the number of tasks was lowered, in order.
+    pub concurrency_reductions: Vec<ConcurrencyReduction>,
+    /// `None` if the run did not finish, e.g. because it crashed.
+    pub summary: Option<RunSummary>,
+    /// Tests that started but did not finish, in the order they started.
+    /// After a crash, these were running when the harness died.
+    pub unfinished: Vec<String>,
+    /// Why the run was canceled, if it was.
+    pub canceled: Option<String>,
+}
+
+#[cfg(feature = "report-types")]
+impl RunReport {
+    /// Parses an event stream. Empty lines are ignored, and so is a last line
+    /// without a newline that is not valid JSON, e.g. of a `--checkpoint` that
+    /// is still being written to.
+    pub fn parse(json_lines: &str) -> serde_json::Result<Self> {
+        let (complete, last) = match json_lines.rsplit_once('\n') {
+            Some((complete, last)) => (complete, last),
+            None => ("", json_lines),
+        };
+        let mut records = complete
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<EventRecord>>>()?;
+        if let Ok(record) = serde_json::from_str(last) {
+            records.push(record);
+        }
+        Ok(Self::from_records(records))
+    }
+
+    /// Assembles a report from already parsed records.
+    pub fn from_records(records: impl IntoIterator<Item = EventRecord>) -> Self {
+        let mut report = Self::default();
+        for record in records {
+            report.schema_version = report.schema_version.max(record.schema_version);
+            match record.event {
+                // The totals of a combined report stand for those of every
+                // binary.
+                Event::RunStarted { .. } | Event::RunFinished(_) if record.binary.is_some() => {}
+                Event::RunStarted { diagnostics, .. } => report.diagnostics.extend(diagnostics),
+                Event::CollectionError(error) => report.collection_errors.push(error),
+                Event::TestSkipped(skipped) => report.skipped.push(skipped),
+                Event::TestStarted { name } => report.unfinished.push(name),
+                Event::TestFinished(result) => {
+                    if let Some(i) = report.unfinished.iter().position(|n| *n == result.name) {
+                        report.unfinished.remove(i);
+                    }
+                    report.tests.push(result);
+                }
+                Event::RunCanceled { reason } => report.canceled = Some(reason),
+                Event::Comparison(comparison) => report.comparison = Some(comparison),
+                Event::FixtureUsage { fixtures } => report.fixtures = fixtures,
+                Event::ConcurrencyReduced(reduction) => {
+                    report.concurrency_reductions.push(reduction)
+                }
+                Event::RunFinished(summary) => report.summary = Some(summary),
+                Event::SetupFinished { .. } | Event::TestSlow { .. } | Event::Unknown => {}
+            }
+        }
+        report
+    }
+
+    /// Looks up the result of the test called `name`.
+    pub fn test(&self, name: &str) -> Option<&TestResult> {
+        self.tests.iter().find(|test| test.name == name)
+    }
+}
@@ -0,0 +1,93 @@
+//! `--report-time` and `--ensure-time`, like in libtest: durations over a
+//! warning or critical time are highlighted, and with `--ensure-time`, tests
+//! over the critical time fail.
+//!
+//! The times are read from `RUST_TEST_TIME_INTEGRATION` as
+//! `WARN_MS,CRITICAL_MS`, the variable that libtest uses for integration
+//! tests. Without it, they are 500ms and 1s.
+
+use std::{process, time::Duration};
+
+use crate::report::Failure;
+
+/// The variable with the times, in milliseconds.
+const VAR: &str = "RUST_TEST_TIME_INTEGRATION";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TimeThresholds {
+    pub(crate) warn: Duration,
+    pub(crate) critical: Duration,
+    /// Whether tests over the critical time fail, with `--ensure-time`.
+    pub(crate) ensure: bool,
+}
+
+/// How a duration compares to the thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeLevel {
+    Ok,
+    Warn,
+    Critical,
+}
+
+impl TimeThresholds {
+    /// Reads the times from the environment, exiting if they are malformed.
+    pub(crate) fn from_env(ensure: bool) -> Self {
+        let (warn, critical) = match std::env::var(VAR) {
+            Ok(value) => parse(&value).unwrap_or_else(|| {
+                eprintln!("error: {VAR} must be WARN_MS,CRITICAL_MS, e.g. 500,1000, not `{value}`");
+                process::exit(1)
+            }),
+            Err(_) => (Duration::from_millis(500), Duration::from_secs(1)),
+        };
+        Self {
+            warn,
+            critical,
+            ensure,
+        }
+    }
+
+    pub(crate) fn level(&self, duration: Duration) -> TimeLevel {
+        if duration >= self.critical {
+            TimeLevel::Critical
+        } else if duration >= self.warn {
+            TimeLevel::Warn
+        } else {
+            TimeLevel::Ok
+        }
+    }
+
+    /// Returns the failure of a test that ran for `duration`, if it is over
+    /// the critical time with `--ensure-time`.
+    pub(crate) fn check(&self, duration: Duration) -> Option<Failure> {
+        (self.ensure && self.level(duration) == TimeLevel::Critical).then(|| Failure {
+            message: format!(
+                "test ran for {:.3}s, longer than the critical time of {}s (--ensure-time)",
+                duration.as_secs_f64(),
+                self.critical.as_secs_f64()
+            ),
+            location: None,
+        })
+    }
+}
+
+fn parse(value: &str) -> Option<(Duration, Duration)> {
+    let (warn, critical) = value.split_once(',')?;
+    let warn = Duration::from_millis(warn.trim().parse().ok()?);
+    let critical = Duration::from_millis(critical.trim().parse().ok()?);
+    (warn <= critical).then_some((warn, critical))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_times() {
+        assert_eq!(
+            parse("50, 100"),
+            Some((Duration::from_millis(50), Duration::from_millis(100)))
+        );
+        assert_eq!(parse("100,50"), None);
+        assert_eq!(parse("100"), None);
+    }
+}
@@ -0,0 +1,229 @@
+//! `--retention`: how long the per-test artifacts of earlier runs are kept.
+//!
+//! The screens of failing tests are removed once the test passes, but not if
+//! the test is renamed, deleted or no longer selected. So every run of a test
+//! binary is recorded under `async-test/runs`, and at the start of a run, the
+//! artifacts of the binary that no recent run wrote are removed.
+//!
+//! The file uses a small subset of TOML, like `--thresholds`, and may be the
+//! same file. The `retention` entry of the `[artifacts]` table keeps the
+//! artifacts of the last `runs` runs, those of the last `days` days, or only
+//! those that both keep:
+//!
+//! ```toml
+//! [artifacts]
+//! retention = { runs = 5 }
+//! ```
+//!
+//! Without the option, the artifacts of the last 10 runs are kept. Fixtures
+//! from `CachedArtifact` and `cache::derived` are not per test: they keep a
+//! single version per name, and are left alone.
+
+use std::{
+    fs,
+    path::Path,
+    process,
+    time::{Duration, SystemTime},
+};
+
+use crate::toml_subset::{self, split_unquoted, Entry};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Retention {
+    runs: Option<usize>,
+    days: Option<u64>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            runs: Some(10),
+            days: None,
+        }
+    }
+}
+
+impl Retention {
+    /// Reads the retention from the file at `path`. Exits if the file cannot
+    /// be read or parsed.
+    pub(crate) fn load(path: &str) -> Self {
+        let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: failed to read retention {path}: {e}");
+            process::exit(1)
+        });
+        Self::parse(&contents).unwrap_or_else(|(line, message)| {
+            eprintln!("error: {path}:{line}: {message}");
+            process::exit(1)
+        })
+    }
+
+    /// Parses the retention, or returns the line number and a description of
+    /// the first error. A file without one keeps the default.
+    fn parse(contents: &str) -> Result<Self, (usize, String)> {
+        let mut retention = Self::default();
+        for Entry { line, key, value } in toml_subset::entries(contents, "artifacts")? {
+            let error = |message: &str| (line, message.to_owned());
+            if key != "retention" {
+                return Err(error(&format!("unknown key `{key}`")));
+            }
+            let inner = value
+                .strip_prefix('{')
+                .and_then(|value| value.strip_suffix('}'))
+                .ok_or_else(|| error("expected an inline table like { runs = N, days = N }"))?;
+            let (mut runs, mut days) = (None, None);
+            for limit in split_unquoted(inner, ',')
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+            {
+                let (name, number) = limit
+                    .split_once('=')
+                    .ok_or_else(|| error("expected `runs = N` or `days = N`"))?;
+                let number = number.trim();
+                let number: u64 = match number.parse() {
+                    Ok(number) if number > 0 => number,
+                    _ => return Err(error(&format!("`{number}` is not a positive number"))),
+                };
+                match name.trim() {
+                    "runs" => runs = Some(number as usize),
+                    "days" => days = Some(number),
+                    name => return Err(error(&format!("unknown limit `{name}`"))),
+                }
+            }
+            if runs.is_none() && days.is_none() {
+                return Err(error("expected `runs = N` or `days = N`"));
+            }
+            retention = Self { runs, days };
+        }
+        Ok(retention)
+    }
+
+    /// Records a run that starts `now` in the file `runs`, and removes the
+    /// files in `artifacts` that were written before the oldest run or day
+    /// that is kept.
+    ///
+    /// Best effort: an artifact that is left behind is only taking space.
+    pub(crate) fn prune(&self, runs: &Path, artifacts: &Path, now: SystemTime) {
+        let since_epoch = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        };
+        let mut starts: Vec<u128> = fs::read_to_string(runs)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.parse().ok())
+            .collect();
+        starts.push(since_epoch(now));
+        if let Some(keep) = self.runs {
+            starts.drain(..starts.len().saturating_sub(keep));
+        }
+        let contents: String = starts.iter().map(|start| format!("{start}\n")).collect();
+        _ = runs
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(runs, contents));
+
+        // Only once there were as many runs as are kept.
+        let oldest_run = self
+            .runs
+            .filter(|&keep| starts.len() >= keep)
+            .map(|_| starts[0]);
+        let oldest_day = self
+            .days
+            .and_then(|days| now.checked_sub(Duration::from_secs(days * 24 * 60 * 60)))
+            .map(since_epoch);
+        let Some(cutoff) = oldest_run.max(oldest_day) else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(artifacts) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let written = entry.metadata().and_then(|metadata| metadata.modified());
+            if written.is_ok_and(|written| since_epoch(written) < cutoff) {
+                _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Retention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.runs, self.days) {
+            (Some(runs), Some(days)) => write!(f, "{runs}runs,{days}days"),
+            (Some(runs), None) => write!(f, "{runs}runs"),
+            (None, Some(days)) => write!(f, "{days}days"),
+            (None, None) => write!(f, "all"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Retention::parse("").unwrap(), Retention::default());
+        assert_eq!(
+            Retention::parse(
+                "[metrics]\np99_ms = { max = 50 }\n[artifacts]\nretention = { runs = 5 }"
+            )
+            .unwrap(),
+            Retention {
+                runs: Some(5),
+                days: None,
+            }
+        );
+        assert_eq!(
+            Retention::parse("[artifacts]\nretention = { days = 7 }").unwrap(),
+            Retention {
+                runs: None,
+                days: Some(7),
+            }
+        );
+        assert_eq!(
+            Retention::parse("[artifacts]\nretention = { runs = 0 }").unwrap_err(),
+            (2, "`0` is not a positive number".to_owned())
+        );
+        assert_eq!(
+            Retention::parse("[artifacts]\nretention = {}").unwrap_err(),
+            (2, "expected `runs = N` or `days = N`".to_owned())
+        );
+    }
+
+    #[test]
+    fn prunes_older_runs() {
+        let dir = std::env::temp_dir().join(format!("async-test-retention-{}", process::id()));
+        let (runs, artifacts) = (dir.join("runs"), dir.join("screens"));
+        fs::create_dir_all(&artifacts).unwrap();
+        let retention = Retention {
+            runs: Some(2),
+            days: None,
+        };
+        let start = SystemTime::now();
+        let run = |n: u64| {
+            let now = start + Duration::from_secs(n * 60);
+            retention.prune(&runs, &artifacts, now);
+            let file = artifacts.join(format!("{n}.txt"));
+            fs::write(&file, "").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&file)
+                .unwrap()
+                .set_modified(now)
+                .unwrap();
+        };
+        run(0);
+        run(1);
+        // Kept by the run that starts now, and the one before it.
+        run(2);
+        let mut left: Vec<_> = fs::read_dir(&artifacts)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["1.txt", "2.txt"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+//! Tests that return a `Result`, see [`Trial::fallible`](crate::Trial::fallible).
+//!
+//! An `Err` fails the test like a failed [`check!`](crate::check). It is
+//! rendered with the `--error-format` of the run, followed by the chain of its
+//! sources.
+
+use std::error::Error;
+
+use crate::{report::Failure, ErrorFormatSetting};
+
+/// What a test can return: `()`, or a `Result` whose `Err` fails the test.
+pub trait TestOutput: Send + 'static {
+    /// The error that fails the test, if any.
+    fn into_error(self) -> Option<Box<dyn Error + Send + Sync>>;
+}
+
+impl TestOutput for () {
+    fn into_error(self) -> Option<Box<dyn Error + Send + Sync>> {
+        None
+    }
+}
+
+impl<E> TestOutput for Result<(), E>
+where
+    E: Into<Box<dyn Error + Send + Sync>> + Send + 'static,
+{
+    fn into_error(self) -> Option<Box<dyn Error + Send + Sync>> {
+        self.err().map(Into::into)
+    }
+}
+
+/// Renders an error returned by a test.
+pub(crate) fn render(error: &(dyn Error + 'static), format: ErrorFormatSetting) -> Failure {
+    let sources = std::iter::successors(error.source(), |&error| error.source());
+    let message = match format {
+        ErrorFormatSetting::Display => {
+            with_sources(format!("test returned an error: {error:#}"), sources)
+        }
+        ErrorFormatSetting::Debug => {
+            with_sources(format!("test returned an error: {error:?}"), sources)
+        }
+        // A single line, so that tools can parse the message as it is.
+        ErrorFormatSetting::Json => serde_json::json!({
+            "error": error.to_string(),
+            "debug": format!("{error:?}"),
+            "sources": sources.map(ToString::to_string).collect::<Vec<_>>(),
+        })
+        .to_string(),
+    };
+    Failure {
+        message,
+        location: None,
+    }
+}
+
+pub(crate) fn with_sources<'a>(
+    mut message: String,
+    sources: impl Iterator<Item = &'a (dyn Error + 'static)>,
+) -> String {
+    for (i, source) in sources.enumerate() {
+        if i == 0 {
+            message += "\n\nCaused by:";
+        }
+        message += &format!("\n    {i}: {source}");
+    }
+    message
+}
@@ -0,0 +1,66 @@
+//! `--sample` and `--sample-percent`: run a random subset of the tests.
+//!
+//! Every test gets a key derived from the run seed and its name, and the
+//! tests with the smallest keys are picked. A test is thus picked or not
+//! independently of the order in which tests were collected, and adding a
+//! test to the suite changes the sample by at most one test.
+
+use crate::{chaos::fnv1a, Arguments};
+
+#[derive(Debug, Clone, Copy)]
+enum Size {
+    Count(usize),
+    Percent(f64),
+}
+
+/// The resolved sample configuration for a single run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SampleConfig {
+    seed: u64,
+    size: Size,
+}
+
+impl SampleConfig {
+    /// Resolves the CLI arguments, picking a random seed if none was given.
+    ///
+    /// In deterministic mode the fallback seed is fixed instead.
+    pub(crate) fn new(args: &Arguments) -> Option<Self> {
+        let size = match (args.sample, args.sample_percent) {
+            (Some(count), _) => Size::Count(count),
+            (None, Some(percent)) => Size::Percent(percent.clamp(0.0, 100.0)),
+            (None, None) => return None,
+        };
+        let seed = args
+            .sample_seed
+            .unwrap_or_else(|| match args.deterministic {
+                true => 0,
+                false => fastrand::u64(..),
+            });
+        Some(Self { seed, size })
+    }
+
+    /// The seed used for this run.
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns whether each of the tests called `names` is in the sample.
+    pub(crate) fn select(&self, names: &[&str]) -> Vec<bool> {
+        let count = match self.size {
+            Size::Count(count) => count,
+            Size::Percent(percent) => (names.len() as f64 * percent / 100.0).ceil() as usize,
+        };
+
+        let mut order: Vec<usize> = (0..names.len()).collect();
+        order.sort_by_key(|&i| {
+            let key = fastrand::Rng::with_seed(self.seed ^ fnv1a(names[i].as_bytes())).u64(..);
+            (key, names[i])
+        });
+
+        let mut selected = vec![false; names.len()];
+        for &i in order.iter().take(count) {
+            selected[i] = true;
+        }
+        selected
+    }
+}
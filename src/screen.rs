@@ -0,0 +1,150 @@
+//! Screens of terminal UIs at the point a test failed, see [`Screen`].
+//!
+//! The last captured screen is kept in a task-local that lives as long as an
+//! attempt of the test, like failed checks. If the attempt fails, the screen
+//! is saved to `async-test/screens/<binary>` in the cargo target directory,
+//! and its first lines are added to the failure. Otherwise the screen of an
+//! earlier failure is removed, so that only the screens of failing tests are
+//! left. Screens of tests that no longer run are removed by `--retention`.
+
+use std::{cell::RefCell, fmt, fs, future::Future, path::PathBuf};
+
+use crate::{
+    cache::{artifacts_root, binary_name},
+    chaos::fnv1a,
+    report::Failure,
+    Outcome,
+};
+
+tokio::task_local! {
+    static CAPTURED: RefCell<Option<String>>;
+}
+
+/// The rendered screen of a terminal UI, shown when the test fails.
+///
+/// Tests of terminal UIs capture the buffer they render into after each
+/// frame. Only the last screen is kept, and only if the test fails: it is
+/// then saved as a text file, with its first lines shown in the failure, as
+/// many as `--screen-lines` (default 20).
+///
+/// ```no_run
+/// use async_test::Screen;
+///
+/// async_test::test!(
+///     async fn opens_menu() {
+///         let mut screen = vec![" File  Edit  View ".to_owned()];
+///         // ...
+///         Screen::capture(screen.join("\n"));
+///         assert!(screen.len() > 1, "menu did not open");
+///     }
+/// );
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Screen;
+
+impl Screen {
+    /// Captures `screen` for the current test, replacing the one captured
+    /// before.
+    ///
+    /// Screens are only captured on the task of the test itself: outside of
+    /// a test, e.g. in a task it spawned, this does nothing.
+    pub fn capture(screen: impl fmt::Display) {
+        _ = CAPTURED.try_with(|captured| {
+            *captured.borrow_mut() = Some(screen.to_string());
+        });
+    }
+}
+
+/// Runs an attempt of the test called `name`, adding the screen it captured
+/// last to its failures, with the first `lines` lines inline, or removing
+/// the screen of an earlier failure.
+pub(crate) async fn captured(
+    name: String,
+    lines: usize,
+    test: impl Future<Output = Outcome>,
+) -> Outcome {
+    let (outcome, screen) = CAPTURED
+        .scope(RefCell::default(), async {
+            let outcome = test.await;
+            (outcome, CAPTURED.with(RefCell::take))
+        })
+        .await;
+    let path = path(&name);
+    match (outcome, screen) {
+        (Outcome::Failed(mut failures), Some(screen)) => {
+            let saved = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(&path, &screen));
+            let saved = match saved {
+                Ok(()) => format!("saved to {}", path.display()),
+                Err(e) => format!("could not be saved to {}: {e}", path.display()),
+            };
+            failures.push(Failure {
+                message: format!("screen at failure, {saved}:\n{}", inline(&screen, lines)),
+                location: None,
+            });
+            Outcome::Failed(failures)
+        }
+        (outcome, _) => {
+            // Best effort, a stale screen is only misleading.
+            _ = fs::remove_file(&path);
+            outcome
+        }
+    }
+}
+
+/// The directory that the screens of the tests of this binary are saved to.
+pub(crate) fn dir() -> PathBuf {
+    artifacts_root().join("screens").join(binary_name())
+}
+
+/// The file that the screen of the test called `name` is saved to. The name
+/// is made safe for file names, with its hash to tell apart names like
+/// `a::b` and `a__b`.
+fn path(name: &str) -> PathBuf {
+    let file: String = name
+        .chars()
+        .map(
+            |c| match c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
+    let hash = fnv1a(name.as_bytes());
+    dir().join(format!("{file}-{hash:016x}.txt"))
+}
+
+/// The first `lines` lines of `screen`, with a note of how many were left
+/// out.
+fn inline(screen: &str, lines: usize) -> String {
+    let mut inline: Vec<_> = screen
+        .lines()
+        .take(lines)
+        .map(|line| format!("  | {line}"))
+        .collect();
+    match screen.lines().count().saturating_sub(lines) {
+        0 => {}
+        1 => inline.push("  ... 1 more line".to_owned()),
+        more => inline.push(format!("  ... {more} more lines")),
+    }
+    inline.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_lines() {
+        assert_eq!(inline("a\nb\nc", 2), "  | a\n  | b\n  ... 1 more line");
+        assert_eq!(inline("a\nb", 2), "  | a\n  | b");
+        assert_eq!(inline("a\nb\nc", 0), "  ... 3 more lines");
+        let file = path("ui::menu <1>");
+        let file = file.file_name().unwrap().to_str().unwrap();
+        assert!(file.starts_with("ui__menu__1_-"), "{file}");
+        assert_ne!(path("a::b"), path("a__b"));
+    }
+}
@@ -0,0 +1,40 @@
+//! What a [`setup!`](crate::setup) function knows about the run it is part
+//! of.
+//!
+//! Setups are plain async functions without arguments, spawned by the
+//! `setup!` macro. The run they belong to is passed along in a task-local
+//! [`SetupEnv`]: set around the call that spawns a setup, and carried into
+//! the spawned task by [`in_setup`].
+
+use std::{future::Future, sync::Arc};
+
+use crate::{cache::ScratchDir, FixtureCacheSetting, TestArgs};
+
+tokio::task_local! {
+    static ENV: SetupEnv;
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SetupEnv {
+    pub(crate) fixture_cache: FixtureCacheSetting,
+    pub(crate) test_args: Arc<TestArgs>,
+    pub(crate) scratch: Arc<ScratchDir>,
+}
+
+impl SetupEnv {
+    /// The environment of the running setup, if any.
+    pub(crate) fn current() -> Option<Self> {
+        ENV.try_with(Clone::clone).ok()
+    }
+
+    /// Makes this environment available to the setup that `spawn` starts.
+    pub(crate) fn scope<R>(self, spawn: impl FnOnce() -> R) -> R {
+        ENV.sync_scope(self, spawn)
+    }
+}
+
+/// Runs a setup in the environment of the run that starts it.
+#[doc(hidden)]
+pub fn in_setup<F: Future>(setup: F) -> impl Future<Output = F::Output> {
+    ENV.scope(SetupEnv::current().unwrap_or_default(), setup)
+}
@@ -0,0 +1,46 @@
+//! `--shuffle` and `--shuffle-seed`: run the tests in a random order, to find
+//! tests that depend on each other.
+//!
+//! Like with `--sample`, every test gets a key derived from the run seed and
+//! its name, and the tests start in the order of their keys. The order thus
+//! doesn't depend on the order in which tests were collected.
+
+use crate::{chaos::fnv1a, Arguments, Trial};
+
+/// The resolved shuffle configuration for a single run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShuffleConfig {
+    seed: u64,
+}
+
+impl ShuffleConfig {
+    /// Resolves the CLI arguments, picking a random seed if none was given.
+    ///
+    /// In deterministic mode the fallback seed is fixed instead.
+    pub(crate) fn new(args: &Arguments) -> Option<Self> {
+        if !args.shuffle && args.shuffle_seed.is_none() {
+            return None;
+        }
+        let seed = args
+            .shuffle_seed
+            .unwrap_or_else(|| match args.deterministic {
+                true => 0,
+                false => fastrand::u64(..),
+            });
+        Some(Self { seed })
+    }
+
+    /// The seed used for this run.
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Puts `tests` in the order of this seed.
+    pub(crate) fn shuffle(&self, tests: &mut [Trial]) {
+        tests.sort_by_cached_key(|test| {
+            let name = &test.info.name;
+            let key = fastrand::Rng::with_seed(self.seed ^ fnv1a(name.as_bytes())).u64(..);
+            (key, name.clone())
+        });
+    }
+}
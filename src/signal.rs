@@ -0,0 +1,190 @@
+//! Cancels the run on Ctrl-C and, on Unix, on SIGTERM and SIGHUP, e.g. when
+//! CI stops a job, instead of ending the process halfway through a line of
+//! output and without the reports of the tests that did finish.
+//!
+//! Once tokio listens for a signal, the default handler that ends the process
+//! is gone for good: tokio never removes its handlers. So the signals are
+//! received once per process, on a thread of their own, and passed on to the
+//! runs in progress. Between runs, e.g. after the run in an embedding process
+//! or before a second one, a signal ends the process like the default handler
+//! would.
+
+use std::{
+    process,
+    sync::{mpsc, Mutex, Once},
+    task::Poll,
+};
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::nextest::reporter::CancelReason;
+
+/// A signal that canceled the run, see
+/// [`Conclusion::signal`](crate::Conclusion::signal).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Signal {
+    /// Ctrl-C, or SIGINT.
+    Interrupt,
+    /// SIGTERM, on Unix.
+    Terminate,
+    /// SIGHUP, on Unix.
+    Hangup,
+}
+
+impl Signal {
+    pub(crate) fn cancel_reason(self) -> CancelReason {
+        match self {
+            Signal::Interrupt => CancelReason::Interrupt,
+            Signal::Terminate | Signal::Hangup => CancelReason::Signal,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Signal::Interrupt => "SIGINT",
+            Signal::Terminate => "SIGTERM",
+            Signal::Hangup => "SIGHUP",
+        }
+    }
+
+    /// The code the process exits with, 128 plus the number of the signal,
+    /// like a shell reports for a process that the signal ended.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Signal::Interrupt => 130,
+            Signal::Terminate => 143,
+            Signal::Hangup => 129,
+        }
+    }
+}
+
+/// The runs in progress, which the listener passes signals to.
+static RUNS: Mutex<Vec<UnboundedSender<Signal>>> = Mutex::new(Vec::new());
+
+static LISTENER: Once = Once::new();
+
+/// The signals that reach a run, for as long as it lives.
+pub(crate) struct Signals {
+    receiver: UnboundedReceiver<Signal>,
+}
+
+impl Signals {
+    /// Passes the signals to this run instead of ending the process, until it
+    /// is dropped.
+    pub(crate) fn install() -> Self {
+        // The listener is ready once this returns, so that no signal ends
+        // the process after a run started.
+        LISTENER.call_once(|| {
+            let (ready, is_ready) = mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name("async-test-signals".to_owned())
+                .spawn(move || listen(ready));
+            if spawned.is_ok() {
+                // Dropped without a message if no runtime could be built.
+                _ = is_ready.recv();
+            }
+        });
+        let (sender, receiver) = unbounded_channel();
+        RUNS.lock().unwrap().push(sender);
+        Self { receiver }
+    }
+
+    /// Waits for the next signal. Never completes without handlers.
+    pub(crate) async fn recv(&mut self) -> Signal {
+        match self.receiver.recv().await {
+            Some(signal) => signal,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+/// Receives the signals of the process and passes them to the runs in
+/// progress, or ends the process if there are none.
+fn listen(ready: mpsc::Sender<()>) {
+    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    else {
+        return;
+    };
+    runtime.block_on(async {
+        let mut handlers = Handlers::install();
+        _ = ready.send(());
+        loop {
+            let signal = handlers.recv().await;
+            let mut runs = RUNS.lock().unwrap();
+            // Runs that are over dropped their receiver.
+            runs.retain(|run| run.send(signal).is_ok());
+            if runs.is_empty() {
+                process::exit(signal.exit_code());
+            }
+        }
+    })
+}
+
+/// The handlers of the signals that cancel the run.
+struct Handlers {
+    #[cfg(unix)]
+    streams: Vec<(Signal, tokio::signal::unix::Signal)>,
+    #[cfg(windows)]
+    ctrl_c: Option<tokio::signal::windows::CtrlC>,
+}
+
+impl Handlers {
+    /// Replaces the default handlers, which end the process at once. Must be
+    /// called within the runtime.
+    ///
+    /// A signal whose handler cannot be installed still ends the process as
+    /// usual.
+    fn install() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let streams = [
+                (Signal::Interrupt, SignalKind::interrupt()),
+                (Signal::Terminate, SignalKind::terminate()),
+                (Signal::Hangup, SignalKind::hangup()),
+            ];
+            Self {
+                streams: streams
+                    .into_iter()
+                    .filter_map(|(signal_, kind)| Some((signal_, signal(kind).ok()?)))
+                    .collect(),
+            }
+        }
+        #[cfg(windows)]
+        {
+            Self {
+                ctrl_c: tokio::signal::windows::ctrl_c().ok(),
+            }
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            Self {}
+        }
+    }
+
+    /// Waits for the next signal. Never completes without handlers.
+    async fn recv(&mut self) -> Signal {
+        std::future::poll_fn(|cx| {
+            #[cfg(unix)]
+            for (signal, stream) in &mut self.streams {
+                if stream.poll_recv(cx).is_ready() {
+                    return Poll::Ready(*signal);
+                }
+            }
+            #[cfg(windows)]
+            if let Some(ctrl_c) = &mut self.ctrl_c {
+                if ctrl_c.poll_recv(cx).is_ready() {
+                    return Poll::Ready(Signal::Interrupt);
+                }
+            }
+            #[cfg(not(any(unix, windows)))]
+            let _ = cx;
+            Poll::Pending
+        })
+        .await
+    }
+}
@@ -0,0 +1,166 @@
+//! Scripted runs for testing the harness itself.
+//!
+//! A [`Script`] describes tests by nothing but their duration and outcome.
+//! Its trials are added from a [`tests!`](crate::tests) function like any
+//! others, so a run goes through the real collection, scheduling and
+//! reporting. Afterwards, [`Script::check`] verifies the invariants that every
+//! run has to uphold, whatever the scheduling:
+//!
+//! - the statistics add up to the scripted outcomes,
+//! - every test reports exactly one result, with the scripted outcome,
+//! - no more tests run at once than there are tasks.
+//!
+//! Leaked task permits show up as a run that never finishes.
+//!
+//! This module is meant for the crate's own tests and for forks of the crate,
+//! and is hidden from the docs. Only [`Script`] and [`ScriptedTest`] are
+//! public, and neither is covered by semver.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{Conclusion, Tester, Trial};
+
+/// The most tests in a [`Script::random`] script.
+const MAX_TESTS: usize = 40;
+
+/// A test that sleeps for `duration`, then passes or panics.
+#[derive(Debug, Clone)]
+pub struct ScriptedTest {
+    /// The name of the trial.
+    pub name: String,
+    /// How long the test sleeps before it finishes.
+    pub duration: Duration,
+    /// Whether the test passes, or panics with "scripted failure".
+    pub passes: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    started: AtomicUsize,
+    running: AtomicUsize,
+    max_running: AtomicUsize,
+}
+
+/// A set of scripted tests, and what was observed while they ran.
+#[derive(Debug, Clone)]
+pub struct Script {
+    tests: Arc<[ScriptedTest]>,
+    state: Arc<State>,
+}
+
+impl Script {
+    /// A script of the given tests, which are added in this order.
+    pub fn new(tests: Vec<ScriptedTest>) -> Self {
+        Self {
+            tests: tests.into(),
+            state: Arc::default(),
+        }
+    }
+
+    /// A script of up to 40 tests of a few milliseconds each, about a fifth
+    /// of which fail. The same seed always gives the same script.
+    pub fn random(seed: u64) -> Self {
+        let rng = fastrand::Rng::with_seed(seed);
+        let tests = (0..rng.usize(1..=MAX_TESTS))
+            .map(|i| ScriptedTest {
+                name: format!("sim_{i}"),
+                duration: Duration::from_micros(rng.u64(..5_000)),
+                passes: rng.u8(..5) != 0,
+            })
+            .collect();
+        Self::new(tests)
+    }
+
+    /// The scripted tests.
+    pub fn tests(&self) -> &[ScriptedTest] {
+        &self.tests
+    }
+
+    /// Adds a trial for every scripted test.
+    pub fn add_to(&self, tester: &Tester) {
+        for test in self.tests.iter().cloned() {
+            let state = self.state.clone();
+            tester.add(Trial::test(test.name, move || async move {
+                let running = state.running.fetch_add(1, Ordering::SeqCst) + 1;
+                state.started.fetch_add(1, Ordering::SeqCst);
+                state.max_running.fetch_max(running, Ordering::SeqCst);
+
+                tokio::time::sleep(test.duration).await;
+
+                state.running.fetch_sub(1, Ordering::SeqCst);
+                assert!(test.passes, "scripted failure");
+            }));
+        }
+    }
+
+    /// Checks a finished run of this script.
+    ///
+    /// `tasks` is the task limit of the run, and `report` its output with
+    /// `--format json`.
+    pub fn check(&self, conclusion: &Conclusion, tasks: usize, report: &str) -> Result<(), String> {
+        let passes = self.tests.iter().filter(|test| test.passes).count();
+        let fails = self.tests.len() - passes;
+        if (conclusion.num_passed, conclusion.num_failed) != (passes, fails) {
+            return Err(format!(
+                "expected {passes} passed and {fails} failed, got {} and {}",
+                conclusion.num_passed, conclusion.num_failed
+            ));
+        }
+
+        let started = self.state.started.load(Ordering::SeqCst);
+        let running = self.state.running.load(Ordering::SeqCst);
+        if started != self.tests.len() || running != 0 {
+            return Err(format!(
+                "{started} of {} tests started and {running} are still running",
+                self.tests.len()
+            ));
+        }
+
+        let max_running = self.state.max_running.load(Ordering::SeqCst);
+        if max_running > tasks {
+            return Err(format!(
+                "{max_running} tests ran at once with {tasks} tasks"
+            ));
+        }
+
+        let mut results = HashMap::<&str, Vec<bool>>::new();
+        for record in report
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|record| record["type"] == "test-finished")
+        {
+            let Some(name) = record["name"].as_str() else {
+                return Err(format!("result without a name: {record}"));
+            };
+            let Some(test) = self.tests.iter().find(|test| test.name == name) else {
+                return Err(format!("result for unknown test {name}"));
+            };
+            results
+                .entry(&test.name)
+                .or_default()
+                .push(record["status"] == "passed");
+        }
+        for test in self.tests.iter() {
+            match results.get(test.name.as_str()).map(Vec::as_slice) {
+                Some([passed]) if *passed == test.passes => {}
+                Some([_]) => return Err(format!("{} has the wrong status", test.name)),
+                other => {
+                    return Err(format!(
+                        "{} reported {} results",
+                        test.name,
+                        other.map_or(0, <[_]>::len)
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! `--slow-history`: slow periods per test, from the durations of earlier
+//! runs.
+//!
+//! A fixed slow period misses a test that usually takes 100ms and suddenly
+//! takes 5s. With a history, a test is slow once it runs for `--slow-factor`
+//! times the median of its earlier durations. Tests without a history keep
+//! the fixed period.
+//!
+//! The history is read from reports written with `--format json --logfile
+//! PATH` or from a `--run-log`, which keeps the events of every run.
+
+use std::{collections::HashMap, process, time::Duration};
+
+use serde_json::Value;
+
+/// The factor used without `--slow-factor`.
+pub(crate) const DEFAULT_FACTOR: f64 = 3.0;
+
+/// The shortest slow period taken from a history, so that tests of a few
+/// milliseconds are not slow because of scheduling noise.
+const MIN_PERIOD: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub(crate) struct SlowHistory {
+    /// The median duration of every test in the history.
+    medians: HashMap<String, Duration>,
+    factor: f64,
+}
+
+impl SlowHistory {
+    /// Reads the history at `path`, exiting if it cannot be read.
+    pub(crate) fn load(path: &str, factor: f64) -> Self {
+        let history = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: failed to read slow history {path}: {e}");
+            process::exit(1)
+        });
+        Self::parse(&history, factor)
+    }
+
+    fn parse(history: &str, factor: f64) -> Self {
+        let mut durations: HashMap<String, Vec<f64>> = HashMap::new();
+        // Lines that are not JSON objects are skipped, like in `--compare-with`.
+        for record in history
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|record| record["type"] == "test-finished")
+        {
+            if let (Some(name), Some(duration)) =
+                (record["name"].as_str(), record["duration_secs"].as_f64())
+            {
+                durations.entry(name.to_owned()).or_default().push(duration);
+            }
+        }
+
+        let medians = durations
+            .into_iter()
+            .map(|(name, mut durations)| {
+                durations.sort_by(f64::total_cmp);
+                let mid = durations.len() / 2;
+                let median = match durations.len() % 2 {
+                    0 => (durations[mid - 1] + durations[mid]) / 2.0,
+                    _ => durations[mid],
+                };
+                (name, Duration::from_secs_f64(median.max(0.0)))
+            })
+            .collect();
+        Self { medians, factor }
+    }
+
+    /// The slow period of the test called `name`, if it has a history.
+    pub(crate) fn period(&self, name: &str) -> Option<Duration> {
+        let median = self.medians.get(name)?;
+        Some(median.mul_f64(self.factor).max(MIN_PERIOD))
+    }
+
+    /// The number of tests with a history.
+    pub(crate) fn len(&self) -> usize {
+        self.medians.len()
+    }
+
+    pub(crate) fn factor(&self) -> f64 {
+        self.factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_earlier_runs() {
+        let history = SlowHistory::parse(
+            r#"
+{"type":"test-finished","name":"a","duration_secs":1.0}
+{"type":"test-started","name":"a"}
+{"type":"test-finished","name":"a","duration_secs":9.0}
+{"type":"test-finished","name":"a","duration_secs":2.0}
+{"type":"test-finished","name":"b","duration_secs":0.001}
+not json
+"#,
+            3.0,
+        );
+        assert_eq!(history.period("a"), Some(Duration::from_secs(6)));
+        assert_eq!(history.period("b"), Some(MIN_PERIOD));
+        assert_eq!(history.period("c"), None);
+    }
+}
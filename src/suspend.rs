@@ -0,0 +1,158 @@
+//! Detection of time during which the process did not run.
+//!
+//! When the terminal suspends the harness (SIGTSTP) or the machine sleeps,
+//! the monotonic clock may keep going while no test makes progress. Without
+//! correction, every running test would look slow or time out on resume.
+//!
+//! A dedicated thread wakes up every [`CHECK_INTERVAL`]. If it wakes up much
+//! later than that, the process was not running in between, and the excess
+//! counts as suspended. The thread uses real time like the reporter stall
+//! warning, as suspensions are about the real system.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::clock::Clock;
+
+/// How often the detector thread wakes up.
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How late a wake-up has to be to count as a suspension, so that a busy
+/// machine is not mistaken for a suspended one.
+const SLACK: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub(crate) struct SuspendDetector {
+    base: Instant,
+    /// The last wake-up of the thread, in nanoseconds since `base`.
+    last_check: AtomicU64,
+    /// The suspended time seen so far, in nanoseconds.
+    suspended: AtomicU64,
+}
+
+impl SuspendDetector {
+    /// Starts the detector thread, which stops once the detector is dropped.
+    pub(crate) fn start() -> Arc<Self> {
+        let detector = Arc::new(Self {
+            base: Instant::now(),
+            last_check: AtomicU64::new(0),
+            suspended: AtomicU64::new(0),
+        });
+        let weak = Arc::downgrade(&detector);
+        std::thread::Builder::new()
+            .name("async-test-suspend".to_owned())
+            .spawn(move || run(weak))
+            .expect("failed to spawn suspend detector thread");
+        detector
+    }
+
+    /// The total suspended time so far.
+    ///
+    /// A suspension that just ended is included even if the thread has not
+    /// woken up yet, so that a test that resumes first does not see it as
+    /// elapsed time.
+    pub(crate) fn total(&self) -> Duration {
+        let recorded = Duration::from_nanos(self.suspended.load(Ordering::Acquire));
+        let last_check = self.base + Duration::from_nanos(self.last_check.load(Ordering::Acquire));
+        recorded + excess(last_check.elapsed())
+    }
+
+    /// Completes once `clock` has moved by `duration` since `start`, not
+    /// counting time suspended since `suspended_at_start`, the [`total`] at
+    /// `start`.
+    ///
+    /// [`total`]: Self::total
+    pub(crate) async fn sleep_active(
+        &self,
+        clock: &dyn Clock,
+        (start, suspended_at_start): (Instant, Duration),
+        duration: Duration,
+    ) {
+        loop {
+            let active = self.active_since(clock, (start, suspended_at_start));
+            if active >= duration {
+                return;
+            }
+            clock.sleep(duration - active).await;
+        }
+    }
+
+    /// The time that `clock` has moved since `start`, not counting time
+    /// suspended since `suspended_at_start`.
+    pub(crate) fn active_since(
+        &self,
+        clock: &dyn Clock,
+        (start, suspended_at_start): (Instant, Duration),
+    ) -> Duration {
+        let suspended = self.total().saturating_sub(suspended_at_start);
+        (clock.now() - start).saturating_sub(suspended)
+    }
+}
+
+/// The part of a gap between two wake-ups that counts as suspended.
+fn excess(gap: Duration) -> Duration {
+    match gap > CHECK_INTERVAL + SLACK {
+        true => gap - CHECK_INTERVAL,
+        false => Duration::ZERO,
+    }
+}
+
+fn run(detector: Weak<SuspendDetector>) {
+    loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let Some(detector) = detector.upgrade() else {
+            return;
+        };
+        let now = detector.base.elapsed();
+        let last_check = Duration::from_nanos(detector.last_check.load(Ordering::Acquire));
+        let suspended = excess(now - last_check);
+        // The excess is added before the last check moves, so that `total`
+        // may briefly count it twice, but never misses it.
+        detector
+            .suspended
+            .fetch_add(suspended.as_nanos() as u64, Ordering::AcqRel);
+        detector
+            .last_check
+            .store(now.as_nanos() as u64, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn suspended_time_is_not_active() {
+        let detector = SuspendDetector {
+            base: Instant::now(),
+            last_check: AtomicU64::new(0),
+            suspended: AtomicU64::new(0),
+        };
+        let clock = MockClock::new();
+        let start = (clock.now(), detector.total());
+
+        clock.advance(Duration::from_secs(40));
+        detector
+            .suspended
+            .store(Duration::from_secs(30).as_nanos() as u64, Ordering::Release);
+        assert_eq!(
+            detector.active_since(&clock, start),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn late_wake_ups_count_as_suspended() {
+        assert_eq!(excess(CHECK_INTERVAL + SLACK / 2), Duration::ZERO);
+        assert_eq!(
+            excess(CHECK_INTERVAL + Duration::from_secs(5)),
+            Duration::from_secs(5)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! `--log-events`: the start and end of the run and of every test, logged to
+//! syslog or systemd-journald, for suites whose output nobody watches.
+//!
+//! Messages are sent as datagrams to the local socket of the logger, so no
+//! daemon-specific library is needed. Syslog gets RFC 5424 messages with the
+//! details as structured data, journald gets them as fields of its native
+//! protocol, e.g. `TEST_NAME` and `TEST_STATUS`. A message that cannot be sent
+//! is dropped, logging never fails the run.
+
+use std::process;
+
+use crate::{
+    report::{Event, Reporter, TestStatus},
+    LogEventsSetting,
+};
+
+/// The name that messages are logged under.
+const IDENTIFIER: &str = "async-test";
+
+/// Longer messages are cut off, to stay below the datagram limits of common
+/// syslog daemons.
+const MAX_MESSAGE: usize = 4096;
+
+// Severities of RFC 5424, which journald uses as well.
+const ERR: u8 = 3;
+const NOTICE: u8 = 5;
+const INFO: u8 = 6;
+
+/// The `user` facility.
+const FACILITY: u8 = 1;
+
+pub(crate) struct SystemLog {
+    setting: LogEventsSetting,
+    #[cfg(unix)]
+    socket: std::os::unix::net::UnixDatagram,
+}
+
+impl SystemLog {
+    /// Connects to the socket of the logger, or to `socket` if it is given.
+    /// Exits if it is not there.
+    pub(crate) fn connect(setting: LogEventsSetting, socket: Option<&str>) -> Self {
+        #[cfg(unix)]
+        {
+            let path = socket.unwrap_or(match setting {
+                LogEventsSetting::Syslog => "/dev/log",
+                LogEventsSetting::Journald => "/run/systemd/journal/socket",
+            });
+            let connected = std::os::unix::net::UnixDatagram::unbound()
+                .and_then(|socket| socket.connect(path).map(|()| socket));
+            match connected {
+                Ok(socket) => Self { setting, socket },
+                Err(e) => {
+                    eprintln!("error: failed to connect to {path} for --log-events: {e}");
+                    process::exit(1)
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            _ = (setting, socket);
+            eprintln!("error: --log-events is only supported on Unix");
+            process::exit(1)
+        }
+    }
+
+    fn send(&self, severity: u8, message: &str, fields: &[(&str, String)]) {
+        let message = truncate(message);
+        let datagram = match self.setting {
+            LogEventsSetting::Syslog => syslog_message(severity, message, fields),
+            LogEventsSetting::Journald => journald_message(severity, message, fields),
+        };
+        #[cfg(unix)]
+        {
+            _ = self.socket.send(&datagram);
+        }
+        #[cfg(not(unix))]
+        {
+            _ = datagram;
+        }
+    }
+}
+
+impl Reporter for SystemLog {
+    fn report(&mut self, event: &Event) {
+        match event {
+            Event::RunStarted { test_count, .. } => self.send(
+                INFO,
+                &format!("starting {test_count} tests"),
+                &[("TEST_COUNT", test_count.to_string())],
+            ),
+            Event::TestStarted { name } => self.send(
+                INFO,
+                &format!("{name} started"),
+                &[("TEST_NAME", name.clone())],
+            ),
+            Event::TestFinished(result) => {
+                let (severity, status) = match result.status {
+                    TestStatus::Passed => (INFO, "passed"),
+                    _ => (ERR, "failed"),
+                };
+                let mut message =
+                    format!("{} {status} in {:.3}s", result.name, result.duration_secs);
+                if let Some(output) = &result.output {
+                    message = format!("{message}\n{output}");
+                }
+                self.send(
+                    severity,
+                    &message,
+                    &[
+                        ("TEST_NAME", result.name.clone()),
+                        ("TEST_STATUS", status.to_owned()),
+                        ("TEST_DURATION_SECS", format!("{:.3}", result.duration_secs)),
+                    ],
+                );
+            }
+            Event::RunFinished(summary) => {
+                let severity = match summary.failed + summary.timed_out {
+                    0 => NOTICE,
+                    _ => ERR,
+                };
+                self.send(
+                    severity,
+                    &format!(
+                        "finished in {:.3}s: {} passed, {} failed, {} skipped",
+                        summary.duration_secs,
+                        summary.passed,
+                        summary.failed + summary.timed_out,
+                        summary.skipped
+                    ),
+                    &[
+                        ("TESTS_PASSED", summary.passed.to_string()),
+                        (
+                            "TESTS_FAILED",
+                            (summary.failed + summary.timed_out).to_string(),
+                        ),
+                        ("TESTS_SKIPPED", summary.skipped.to_string()),
+                    ],
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Cuts `message` off after [`MAX_MESSAGE`] bytes, at a character boundary.
+fn truncate(message: &str) -> &str {
+    if message.len() <= MAX_MESSAGE {
+        return message;
+    }
+    let mut end = MAX_MESSAGE;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    &message[..end]
+}
+
+/// An RFC 5424 message, without timestamp and hostname, which the daemon
+/// fills in.
+fn syslog_message(severity: u8, message: &str, fields: &[(&str, String)]) -> Vec<u8> {
+    let mut data = format!("[{IDENTIFIER}@32473");
+    for (key, value) in fields {
+        // `"`, `\` and `]` are escaped in parameter values.
+        let value = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace(']', "\\]");
+        data += &format!(" {}=\"{value}\"", key.to_lowercase());
+    }
+    data += "]";
+    format!(
+        "<{}>1 - - {IDENTIFIER} {} - {data} {message}",
+        FACILITY * 8 + severity,
+        process::id()
+    )
+    .into_bytes()
+}
+
+/// A message of the native journald protocol.
+fn journald_message(severity: u8, message: &str, fields: &[(&str, String)]) -> Vec<u8> {
+    let mut datagram = vec![];
+    let mut field = |key: &str, value: &str| {
+        datagram.extend_from_slice(key.as_bytes());
+        // Values with newlines are sent with their length instead of `=`.
+        if value.contains('\n') {
+            datagram.push(b'\n');
+            datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            datagram.push(b'=');
+        }
+        datagram.extend_from_slice(value.as_bytes());
+        datagram.push(b'\n');
+    };
+    field("MESSAGE", message);
+    field("PRIORITY", &severity.to_string());
+    field("SYSLOG_IDENTIFIER", IDENTIFIER);
+    for (key, value) in fields {
+        field(key, value);
+    }
+    datagram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_formats() {
+        let fields = [("TEST_NAME", "a \"b\"".to_owned())];
+        let syslog = String::from_utf8(syslog_message(ERR, "a failed", &fields)).unwrap();
+        assert_eq!(
+            syslog,
+            format!(
+                "<11>1 - - async-test {} - [async-test@32473 test_name=\"a \\\"b\\\"\"] a failed",
+                process::id()
+            )
+        );
+
+        let journald = journald_message(INFO, "line\nbreak", &fields);
+        assert_eq!(
+            journald,
+            [
+                &b"MESSAGE\n"[..],
+                &10u64.to_le_bytes(),
+                b"line\nbreak\nPRIORITY=6\nSYSLOG_IDENTIFIER=async-test\nTEST_NAME=a \"b\"\n",
+            ]
+            .concat()
+        );
+    }
+}
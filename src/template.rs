@@ -0,0 +1,178 @@
+//! Configuration shared by many trials, see [`TrialTemplate`].
+
+use std::time::Duration;
+
+use crate::{capability::Capability, report, ShouldPanic, TestFn, TestInfo, Trial};
+
+/// The configuration of a trial without its name and runner, to stamp out
+/// many trials that are configured the same way.
+///
+/// Generated suites often add hundreds of trials that only differ in their
+/// name and runner. A template keeps their tags, timeout and other settings
+/// in one place:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use async_test::{Tester, Trial};
+/// # fn collect(tester: Tester, cases: Vec<String>) {
+/// let template = Trial::template()
+///     .with_tags(["conformance"])
+///     .with_timeout(Duration::from_secs(30))
+///     .with_retries(2);
+/// for case in cases {
+///     tester.add(template.test(case, || async {}));
+/// }
+/// # }
+/// ```
+///
+/// Settings of a stamped trial can still be changed, with the `with_*`
+/// methods of [`Trial`] or with the [`TrialHandle`](crate::TrialHandle)
+/// returned by [`Tester::add`](crate::Tester::add).
+#[derive(Debug, Clone)]
+pub struct TrialTemplate {
+    info: TestInfo,
+}
+
+impl TrialTemplate {
+    pub(crate) fn new() -> Self {
+        Self {
+            info: TestInfo::new(String::new()),
+        }
+    }
+
+    /// Creates a trial like [`Trial::test`], configured like this template.
+    pub fn test<T, F>(&self, name: impl Into<String>, runner: F) -> Trial
+    where
+        T: 'static,
+        F: TestFn<T, Output = ()>,
+    {
+        self.stamp(Trial::test(name, runner))
+    }
+
+    /// Creates a trial like [`Trial::fallible`], configured like this
+    /// template.
+    pub fn fallible<T, E, F>(&self, name: impl Into<String>, runner: F) -> Trial
+    where
+        T: 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+        F: TestFn<T, Output = Result<(), E>>,
+    {
+        self.stamp(Trial::fallible(name, runner))
+    }
+
+    fn stamp(&self, trial: Trial) -> Trial {
+        Trial {
+            info: TestInfo {
+                name: trial.info.name,
+                ..self.info.clone()
+            },
+            ..trial
+        }
+    }
+
+    /// See [`Trial::with_ignored_flag`].
+    pub fn with_ignored_flag(mut self, is_ignored: bool) -> Self {
+        self.info.is_ignored = is_ignored;
+        self
+    }
+
+    /// See [`Trial::with_ignore_reason`].
+    pub fn with_ignore_reason(mut self, reason: impl Into<String>) -> Self {
+        self.info.is_ignored = true;
+        self.info.ignore_reason = Some(reason.into());
+        self
+    }
+
+    /// See [`Trial::with_tags`].
+    pub fn with_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.info.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// See [`Trial::with_required_cfg`].
+    pub fn with_required_cfg(mut self, enabled: bool, cfg: impl Into<String>) -> Self {
+        if !enabled && self.info.unmet_cfg.is_none() {
+            self.info.unmet_cfg = Some(cfg.into());
+        }
+        self
+    }
+
+    /// See [`Trial::with_requires_network`].
+    pub fn with_requires_network(mut self) -> Self {
+        self.info.require(Capability::Network);
+        self
+    }
+
+    /// See [`Trial::with_requires_docker`].
+    pub fn with_requires_docker(mut self) -> Self {
+        self.info.require(Capability::Docker);
+        self
+    }
+
+    /// See [`Trial::with_main_thread`].
+    pub fn with_main_thread(mut self, main_thread: bool) -> Self {
+        self.info.main_thread = main_thread;
+        self
+    }
+
+    /// See [`Trial::with_description`].
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.info.description = Some(description.into());
+        self
+    }
+
+    /// See [`Trial::with_link`].
+    pub fn with_link(mut self, label: impl Into<String>, url: impl Into<String>) -> Self {
+        self.info.links.push(report::Link {
+            label: label.into(),
+            url: url.into(),
+        });
+        self
+    }
+
+    /// See [`Trial::with_should_panic`].
+    pub fn with_should_panic(mut self) -> Self {
+        self.info.should_panic = ShouldPanic::Yes;
+        self
+    }
+
+    /// See [`Trial::with_should_panic_expected`].
+    pub fn with_should_panic_expected(mut self, expected: impl Into<String>) -> Self {
+        self.info.should_panic = ShouldPanic::YesWithMessage(expected.into());
+        self
+    }
+
+    /// See [`Trial::with_expected_failure`].
+    pub fn with_expected_failure(mut self, reason: impl Into<String>) -> Self {
+        self.info.expected_failure = Some(reason.into());
+        self
+    }
+
+    /// See [`TrialHandle::timeout`](crate::TrialHandle::timeout).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.info.timeout = Some(timeout);
+        self
+    }
+
+    /// See [`Trial::with_expected_duration`].
+    pub fn with_expected_duration(mut self, expected: Duration) -> Self {
+        self.info.expected_duration = Some(expected);
+        self
+    }
+
+    /// See [`TrialHandle::retries`](crate::TrialHandle::retries).
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.info.retries = Some(retries);
+        self
+    }
+
+    /// See [`TrialHandle::group`](crate::TrialHandle::group).
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.info.group = Some(group.into());
+        self
+    }
+}
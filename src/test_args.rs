@@ -0,0 +1,68 @@
+//! The [`TestArgs`] fixture.
+
+use std::{any::TypeId, collections::BTreeMap, sync::Arc};
+
+use crate::{
+    builder::SetupInit,
+    setup_env::{in_setup, SetupEnv},
+    AnySharedVal,
+};
+
+/// Values given on the command line with `--test-arg KEY=VALUE`, e.g. the URL
+/// of the server to test against.
+///
+/// This is a fixture that is always available: tests take it as a
+/// `&TestArgs` argument.
+///
+/// ```no_run
+/// use async_test::TestArgs;
+///
+/// async_test::test!(
+///     async fn health_check(args: &TestArgs) {
+///         let url = args.get("url").unwrap_or("http://localhost:8080");
+///         // ...
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TestArgs {
+    values: BTreeMap<String, String>,
+}
+
+impl TestArgs {
+    /// Collects `KEY=VALUE` pairs. A later value for a key replaces an
+    /// earlier one.
+    pub(crate) fn new(pairs: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            values: pairs.into_iter().collect(),
+        }
+    }
+
+    /// The value given for `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// All keys and values, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// All keys, sorted.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+}
+
+inventory::submit! {
+    SetupInit {
+        type_id: TypeId::of::<TestArgs>,
+        module: "async_test",
+        function: "test_args",
+        suite: None,
+        setup: || tokio::spawn(in_setup(async {
+            let test_args: Arc<TestArgs> = SetupEnv::current().unwrap_or_default().test_args;
+            test_args as AnySharedVal
+        })),
+    }
+}
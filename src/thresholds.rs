@@ -0,0 +1,144 @@
+//! `--thresholds`: limits for the values that tests record with
+//! [`TestMeta::record`](crate::TestMeta::record).
+//!
+//! The file uses a small subset of TOML. Every entry of the `[metrics]`
+//! table is a key that tests record, with an inline table of its limits:
+//!
+//! ```toml
+//! [metrics]
+//! p99_ms = { max = 50 }
+//! rows_verified = { min = 1000 }
+//! ```
+//!
+//! Once a test completes, each value it recorded under a key with limits is
+//! compared with them, and each limit it breaks is a failure of the test.
+//! Tests that don't record a key are not checked against it.
+
+use std::{collections::BTreeMap, process};
+
+use crate::{
+    report::{Failure, MetaValue},
+    toml_subset::{self, split_unquoted, Entry},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Limits {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Thresholds {
+    limits: BTreeMap<String, Limits>,
+}
+
+impl Thresholds {
+    /// Reads the thresholds from the file at `path`. Exits if the file
+    /// cannot be read or parsed.
+    pub(crate) fn load(path: &str) -> Self {
+        let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("error: failed to read thresholds {path}: {e}");
+            process::exit(1)
+        });
+        Self::parse(&contents).unwrap_or_else(|(line, message)| {
+            eprintln!("error: {path}:{line}: {message}");
+            process::exit(1)
+        })
+    }
+
+    /// Parses the thresholds, or returns the line number and a description
+    /// of the first error.
+    fn parse(contents: &str) -> Result<Self, (usize, String)> {
+        let mut limits = BTreeMap::new();
+        for Entry { line, key, value } in toml_subset::entries(contents, "metrics")? {
+            let error = |message: &str| (line, message.to_owned());
+            let inner = value
+                .strip_prefix('{')
+                .and_then(|value| value.strip_suffix('}'))
+                .ok_or_else(|| error("expected an inline table like { min = N, max = N }"))?;
+            let mut key_limits = Limits::default();
+            for limit in split_unquoted(inner, ',')
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+            {
+                let (name, number) = limit
+                    .split_once('=')
+                    .ok_or_else(|| error("expected `min = N` or `max = N`"))?;
+                let number: f64 = number
+                    .trim()
+                    .parse()
+                    .map_err(|_| error(&format!("`{}` is not a number", number.trim())))?;
+                match name.trim() {
+                    "min" => key_limits.min = Some(number),
+                    "max" => key_limits.max = Some(number),
+                    name => return Err(error(&format!("unknown limit `{name}`"))),
+                }
+            }
+            limits.insert(key.to_owned(), key_limits);
+        }
+        Ok(Self { limits })
+    }
+
+    /// The keys that have limits, sorted.
+    pub(crate) fn keys(&self) -> Vec<String> {
+        self.limits.keys().cloned().collect()
+    }
+
+    /// Returns a failure for every limit that `meta` breaks.
+    pub(crate) fn check(&self, meta: &BTreeMap<String, MetaValue>) -> Vec<Failure> {
+        let mut failures = vec![];
+        for (key, value) in meta {
+            let Some(limits) = self.limits.get(key) else {
+                continue;
+            };
+            let number = match value {
+                MetaValue::Int(value) => *value as f64,
+                MetaValue::Float(value) => *value,
+                value => {
+                    failures.push(failure(format!(
+                        "metric {key} = {value} has a threshold, but is not a number"
+                    )));
+                    continue;
+                }
+            };
+            if let Some(min) = limits.min.filter(|&min| number < min) {
+                failures.push(failure(format!(
+                    "metric {key} = {value} is below its minimum of {min}"
+                )));
+            }
+            if let Some(max) = limits.max.filter(|&max| number > max) {
+                failures.push(failure(format!(
+                    "metric {key} = {value} is above its maximum of {max}"
+                )));
+            }
+        }
+        failures
+    }
+}
+
+fn failure(message: String) -> Failure {
+    Failure {
+        message,
+        location: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Thresholds::parse("[metrics]\np99_ms = 50").unwrap_err(),
+            (
+                2,
+                "expected an inline table like { min = N, max = N }".to_owned()
+            )
+        );
+        assert_eq!(
+            Thresholds::parse("[metrics]\np99_ms = { avg = 50 }").unwrap_err(),
+            (2, "unknown limit `avg`".to_owned())
+        );
+    }
+}
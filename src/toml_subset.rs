@@ -0,0 +1,128 @@
+//! The small subset of TOML that `--thresholds` and `--env-requirements`
+//! read, possibly from the same file: `[table]` headers, `key = value` lines
+//! and `#` comments. Values are left to the caller, which can split them with
+//! [`split_unquoted`].
+//!
+//! `#`, `=` and `,` only count outside of quoted strings, so that e.g. an
+//! environment variable called `"A#B"` is not cut off as a comment.
+
+/// A `key = value` line of a table.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Entry<'a> {
+    /// The line number, starting at 1.
+    pub(crate) line: usize,
+    /// The key, without quotes.
+    pub(crate) key: &'a str,
+    pub(crate) value: &'a str,
+}
+
+/// The entries of the table called `table`, in the order of the file, or the
+/// line number and a description of the first error. Other tables are left
+/// for other tools that share the file.
+pub(crate) fn entries<'a>(
+    contents: &'a str,
+    table: &str,
+) -> Result<Vec<Entry<'a>>, (usize, String)> {
+    let mut entries = vec![];
+    let mut in_table = false;
+    for (i, line) in contents.lines().enumerate() {
+        let error = |message: &str| (i + 1, message.to_owned());
+        let line = match find_unquoted(line, '#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| error("expected `]`"))?;
+            in_table = header.trim() == table;
+            continue;
+        }
+        if !in_table {
+            continue;
+        }
+        let (key, value) = find_unquoted(line, '=')
+            .map(|eq| (&line[..eq], &line[eq + 1..]))
+            .ok_or_else(|| error("expected KEY = VALUE"))?;
+        entries.push(Entry {
+            line: i + 1,
+            key: key.trim().trim_matches('"'),
+            value: value.trim(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Splits `s` at every `separator` outside of quoted strings.
+pub(crate) fn split_unquoted(mut s: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        match find_unquoted(s, separator) {
+            Some(i) => {
+                let part = &s[..i];
+                s = &s[i + separator.len_utf8()..];
+                Some(part)
+            }
+            None => {
+                done = true;
+                Some(s)
+            }
+        }
+    })
+}
+
+/// The index of the first `c` outside of a quoted string.
+fn find_unquoted(s: &str, c: char) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, ch) in s.char_indices() {
+        match quote {
+            // Only basic strings have escapes, literal strings end at `'`.
+            Some('"') if escaped => escaped = false,
+            Some('"') if ch == '\\' => escaped = true,
+            Some(q) if ch == q => quote = None,
+            Some(_) => {}
+            None if ch == c => return Some(i),
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted() {
+        let contents = r#"
+            [other]
+            ignored = 1
+            [environment] # comment
+            "a=b" = ["C#D", "E,F"] # comment
+            "#;
+        assert_eq!(
+            entries(contents, "environment").unwrap(),
+            [Entry {
+                line: 5,
+                key: "a=b",
+                value: r#"["C#D", "E,F"]"#,
+            }]
+        );
+        let items: Vec<_> = split_unquoted(r#""C#D", "E,F""#, ',').collect();
+        assert_eq!(items, [r#""C#D""#, r#" "E,F""#]);
+        assert_eq!(find_unquoted(r##""a\"#" # b"##, '#'), Some(7));
+        assert_eq!(
+            entries("[t]\nkey", "t").unwrap_err(),
+            (2, "expected KEY = VALUE".to_owned())
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! `--verify-deterministic`: run each test several times at once and check
+//! that every run has the same outcome.
+//!
+//! The runs are polled concurrently by the task of the test, so they
+//! interleave at their await points like tests under `--test-tasks` do.
+
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    report::{Determinism, Failure},
+    Outcome,
+};
+
+/// Runs `runs` futures returned by `iteration` concurrently.
+///
+/// If the outcomes differ, the test fails with the failures of the first
+/// failing run, after a note on how many runs failed.
+pub(crate) async fn verified<F>(
+    runs: NonZeroUsize,
+    mut iteration: impl FnMut() -> F,
+) -> (Outcome, Determinism)
+where
+    F: Future<Output = Outcome>,
+{
+    let outcomes = JoinAll {
+        futures: (0..runs.get())
+            .map(|_| Some(Box::pin(iteration())))
+            .collect(),
+        outcomes: (0..runs.get()).map(|_| None).collect(),
+    }
+    .await;
+
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, Outcome::Failed(_)))
+        .count();
+    let determinism = Determinism {
+        runs: runs.get(),
+        failed,
+    };
+    let first_failure = outcomes.into_iter().find_map(|outcome| match outcome {
+        Outcome::Failed(failures) => Some(failures),
+        Outcome::Passed => None,
+    });
+    let outcome = match first_failure {
+        None => Outcome::Passed,
+        Some(failures) if failed == runs.get() => Outcome::Failed(failures),
+        Some(failures) => {
+            let note = Failure {
+                message: format!(
+                    "nondeterministic: {failed} of {} runs failed, the first failure follows",
+                    runs.get()
+                ),
+                location: None,
+            };
+            Outcome::Failed(std::iter::once(note).chain(failures).collect())
+        }
+    };
+    (outcome, determinism)
+}
+
+struct JoinAll<F: Future> {
+    futures: Vec<Option<Pin<Box<F>>>>,
+    outcomes: Vec<Option<F::Output>>,
+}
+
+impl<F: Future> Unpin for JoinAll<F> {}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        for (future, outcome) in this.futures.iter_mut().zip(&mut this.outcomes) {
+            if let Some(pending) = future {
+                if let Poll::Ready(output) = pending.as_mut().poll(cx) {
+                    *outcome = Some(output);
+                    *future = None;
+                }
+            }
+        }
+        if this.futures.iter().any(Option::is_some) {
+            return Poll::Pending;
+        }
+        Poll::Ready(this.outcomes.drain(..).map(Option::unwrap).collect())
+    }
+}
@@ -0,0 +1,112 @@
+//! `--notify-webhook`: post the summary of the run to a URL once it finishes,
+//! e.g. to alert a Slack or Teams channel about a scheduled suite.
+//!
+//! The body is a JSON object with the [`RunSummary`] and the failed tests. Its
+//! `text` is a short digest of both, which is what incoming webhooks of chat
+//! services show.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::report::{Event, Reporter, RunSummary, TestStatus};
+
+/// At most this many failures are listed in the digest. All of them are in
+/// `failures`.
+const DIGEST_FAILURES: usize = 10;
+
+/// Failure messages are cut off after this many characters.
+const MAX_MESSAGE: usize = 200;
+
+pub(crate) struct Webhook {
+    url: String,
+    failures: Vec<FailedTest>,
+}
+
+#[derive(Serialize)]
+struct FailedTest {
+    name: String,
+    /// The first line of the failure message.
+    message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Notification<'a> {
+    text: String,
+    summary: &'a RunSummary,
+    failures: &'a [FailedTest],
+}
+
+impl Webhook {
+    pub(crate) fn new(url: String) -> Self {
+        Self {
+            url,
+            failures: vec![],
+        }
+    }
+
+    fn post(&self, summary: &RunSummary) {
+        let notification = Notification {
+            text: digest(summary, &self.failures),
+            summary,
+            failures: &self.failures,
+        };
+        let body = serde_json::to_string(&notification).unwrap();
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+        // The run is over, so a failure to notify only gets a warning.
+        if let Err(e) = agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body)
+        {
+            eprintln!("warning: failed to post the summary to --notify-webhook: {e}");
+        }
+    }
+}
+
+impl Reporter for Webhook {
+    fn report(&mut self, event: &Event) {
+        match event {
+            Event::TestFinished(result) if result.status == TestStatus::Failed => {
+                let message = result.output.as_deref().and_then(|output| {
+                    let line = output.lines().find(|line| !line.trim().is_empty())?;
+                    Some(line.chars().take(MAX_MESSAGE).collect())
+                });
+                self.failures.push(FailedTest {
+                    name: result.name.clone(),
+                    message,
+                });
+            }
+            Event::RunFinished(summary) => self.post(summary),
+            _ => {}
+        }
+    }
+}
+
+/// A few lines on the outcome of the run, for humans.
+fn digest(summary: &RunSummary, failures: &[FailedTest]) -> String {
+    let failed = summary.failed + summary.timed_out;
+    let mut text = format!(
+        "Test run {}: {} passed, {failed} failed, {} skipped in {:.2}s",
+        if failed + summary.collection_errors == 0 {
+            "passed"
+        } else {
+            "failed"
+        },
+        summary.passed,
+        summary.skipped,
+        summary.duration_secs,
+    );
+    for failure in failures.iter().take(DIGEST_FAILURES) {
+        text += &format!("\n- {}", failure.name);
+        if let Some(message) = &failure.message {
+            text += &format!(": {message}");
+        }
+    }
+    if failures.len() > DIGEST_FAILURES {
+        text += &format!("\n- and {} more", failures.len() - DIGEST_FAILURES);
+    }
+    text
+}
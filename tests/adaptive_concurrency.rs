@@ -0,0 +1,87 @@
+use std::{
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+/// Tests that run at the moment, like open file descriptors.
+static OPEN: AtomicUsize = AtomicUsize::new(0);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for name in ["a", "b", "c", "d"] {
+            tester.add(Trial::fallible(name, || async {
+                let open = OPEN.fetch_add(1, Ordering::AcqRel) + 1;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                OPEN.fetch_sub(1, Ordering::AcqRel);
+                match open > 2 {
+                    true => Err(io::Error::from_raw_os_error(24)),
+                    false => Ok(()),
+                }
+            }));
+        }
+    }
+);
+
+// The tests share `OPEN`, so the runs must not overlap.
+#[test]
+fn reduces_and_retries() {
+    let (c, out) = do_run(args([
+        "--test-threads",
+        "2",
+        "--test-tasks",
+        "4",
+        "--adaptive-concurrency",
+        "--color",
+        "never",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (4, 0), "{out}");
+    let reducing: Vec<_> = out
+        .lines()
+        .filter(|line| line.trim_start().starts_with("Reducing "))
+        .collect();
+    assert_eq!(reducing.len(), 1, "{out}");
+    assert!(
+        reducing[0].contains("from 4 to 2 tasks after")
+            && reducing[0].ends_with("failed with too many open files, retrying"),
+        "{out}"
+    );
+
+    let (c, out) = do_run(args([
+        "--test-threads",
+        "2",
+        "--test-tasks",
+        "4",
+        "--format",
+        "json",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 2), "{out}");
+    assert!(!out.contains("concurrency-reduced"), "{out}");
+
+    let (_, out) = do_run(args([
+        "--test-threads",
+        "2",
+        "--test-tasks",
+        "4",
+        "--adaptive-concurrency",
+        "--format",
+        "json",
+    ]));
+    let reduced = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "concurrency-reduced")
+        .unwrap_or_else(|| panic!("{out}"));
+    assert_eq!(reduced["reason"], "too many open files");
+    assert_eq!(
+        (reduced["from"].as_u64(), reduced["to"].as_u64()),
+        (Some(4), Some(2))
+    );
+}
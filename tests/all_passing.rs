@@ -28,6 +28,11 @@ fn normal() {
             num_filtered_out: 0,
             num_passed: 3,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test barro ... ok
@@ -46,6 +51,11 @@ fn filter_one() {
             num_filtered_out: 2,
             num_passed: 1,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "test foo ... ok",
     );
@@ -60,6 +70,11 @@ fn filter_two() {
             num_filtered_out: 1,
             num_passed: 2,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test barro ... ok
@@ -77,6 +92,11 @@ fn filter_exact() {
             num_filtered_out: 2,
             num_passed: 1,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "test bar ... ok",
     );
@@ -91,6 +111,11 @@ fn filter_two_and_skip() {
             num_filtered_out: 2,
             num_passed: 1,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "test bar ... ok",
     );
@@ -105,6 +130,11 @@ fn skip_nothing() {
             num_filtered_out: 0,
             num_passed: 3,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test barro ... ok
@@ -123,6 +153,11 @@ fn skip_two() {
             num_filtered_out: 2,
             num_passed: 1,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "test foo ... ok",
     );
@@ -137,6 +172,11 @@ fn skip_exact() {
             num_filtered_out: 1,
             num_passed: 2,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test barro ... ok
@@ -154,6 +194,11 @@ fn terse_output() {
             num_filtered_out: 0,
             num_passed: 3,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     );
     assert_log!(
@@ -0,0 +1,43 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async fn list_fixtures() -> Vec<String> {
+    tokio::task::yield_now().await;
+    vec!["a.json".to_owned(), "b.json".to_owned()]
+}
+
+async_test::async_tests!(
+    async fn fixtures(tester: Tester) {
+        for fixture in list_fixtures().await {
+            tester.add(Trial::test(fixture, || async {}));
+        }
+    }
+);
+
+async_test::async_tests!(
+    async fn unreachable(tester: Tester) {
+        tokio::task::yield_now().await;
+        panic!("service unavailable");
+    }
+);
+
+#[test]
+fn collected() {
+    let (c, out) = do_run(args(["--list"]));
+    assert_eq!(c.num_collection_errors, 1);
+    assert!(out.starts_with("a.json: test\nb.json: test\n"), "{out}");
+    assert!(
+        out.contains("error: collecting tests in async_collection::unreachable panicked"),
+        "{out}"
+    );
+}
+
+#[test]
+fn run() {
+    let (c, _) = do_run(args(["--test-threads", "1"]));
+    assert_eq!(c.num_passed, 2);
+    assert_eq!(c.num_collection_errors, 1);
+}
@@ -0,0 +1,57 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use async_test::{Cancellation, Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+static CLEANED_UP: AtomicBool = AtomicBool::new(false);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("stops_by_itself", || async {
+                assert!(!Cancellation::is_requested());
+                Cancellation::requested().await;
+                assert!(Cancellation::is_requested());
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                CLEANED_UP.store(true, Ordering::SeqCst);
+            })
+            .with_timeout(Duration::from_millis(50)),
+        );
+        tester.add(
+            Trial::test("ignores_request", || async {
+                Cancellation::is_requested();
+                std::future::pending::<()>().await
+            })
+            .with_timeout(Duration::from_millis(50)),
+        );
+    }
+);
+
+#[test]
+fn timed_out_tests_get_a_grace_period() {
+    let (c, out) = do_run(args(["--timeout-grace", "200ms", "--color", "never"]));
+    assert_eq!(c.num_failed, 2, "{out}");
+    assert!(CLEANED_UP.load(Ordering::SeqCst), "{out}");
+
+    assert!(
+        out.contains(
+            "test timed out after 50ms, and stopped by itself within the grace period of 200ms"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains("test timed out after 50ms, and was dropped"),
+        "{out}"
+    );
+}
+
+#[test]
+fn outside_of_a_test() {
+    assert!(!Cancellation::is_requested());
+}
@@ -0,0 +1,68 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("all_hold", || async {
+            let numbers = [1, 2];
+            async_test::check!(numbers.iter().sum::<i32>() == 3);
+        }));
+        tester.add(Trial::test("some_fail", || async {
+            let name = String::new();
+            async_test::check!(!name.is_empty(), "name is empty");
+            async_test::check!(name.len() < 10);
+            async_test::check!(name.starts_with('x'));
+        }));
+        tester.add(Trial::test("fails_then_panics", || async {
+            async_test::check!(false, "first {}", 1);
+            panic!("boom");
+        }));
+    }
+);
+
+#[test]
+fn failures_are_numbered() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 2));
+    assert!(
+        out.contains(
+            "some_fail ---\n\
+             [1/2] check failed at 'name is empty', tests/check.rs:16:13\n\n\
+             [2/2] check failed at 'name.starts_with('x')', tests/check.rs:18:13\n"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "fails_then_panics ---\n\
+             [1/2] check failed at 'first 1', tests/check.rs:21:13\n\n\
+             [2/2] thread 'main' panicked at 'boom', tests/check.rs:22:13"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn failures_in_json() {
+    let (_, out) = do_run(args(["--format", "json", "--exact", "fails_then_panics"]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    let failures = finished["failures"].as_array().unwrap();
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0]["location"], "tests/check.rs:21:13");
+    assert_eq!(failures[1]["location"], "tests/check.rs:22:13");
+    assert!(
+        failures[1]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("thread 'main' panicked at 'boom'"),
+        "{finished}"
+    );
+}
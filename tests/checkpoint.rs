@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const PATH: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/checkpoint.jsonl");
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_first", || async {}));
+        // Runs after `a_first`, so its result is in a checkpoint by now.
+        tester.add(Trial::test("b_second", || async {
+            for _ in 0..500 {
+                let snapshot = std::fs::read_to_string(PATH).unwrap_or_default();
+                if snapshot.contains(r#""type":"test-finished","name":"a_first""#) {
+                    assert!(!snapshot.contains("run-finished"));
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            panic!("no checkpoint with the result of a_first");
+        }));
+    }
+);
+
+#[test]
+fn snapshots_while_running() {
+    _ = std::fs::remove_file(PATH);
+    let (c, out) = do_run(args([
+        "--checkpoint",
+        PATH,
+        "--checkpoint-every",
+        "1",
+        "--test-tasks",
+        "1",
+    ]));
+    assert_eq!(c.num_passed, 2, "{out}");
+
+    // The last snapshot is the whole report.
+    let snapshot = std::fs::read_to_string(PATH).unwrap();
+    let last = snapshot.lines().last().unwrap();
+    assert!(last.contains(r#""type":"run-finished""#), "{snapshot}");
+    // Every snapshot only appended the records since the previous one.
+    let finished = snapshot.matches(r#""type":"test-finished""#).count();
+    assert_eq!(finished, 2, "{snapshot}");
+    assert!(!std::path::Path::new(&format!("{PATH}.tmp")).exists());
+}
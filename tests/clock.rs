@@ -0,0 +1,43 @@
+use std::{sync::OnceLock, time::Duration};
+
+use async_test::{clock::MockClock, Tester, Trial};
+use common::{args, do_run_with_clock};
+
+#[macro_use]
+mod common;
+
+static CLOCK: OnceLock<MockClock> = OnceLock::new();
+
+fn clock() -> &'static MockClock {
+    CLOCK.get_or_init(MockClock::new)
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("slow", || async {
+            clock().advance(Duration::from_secs(16));
+            // Lets the harness notice the slow period has passed.
+            tokio::task::yield_now().await;
+        }));
+    }
+);
+
+#[test]
+fn reports_slow_tests_without_waiting() {
+    let (c, out) = do_run_with_clock(
+        args(["--test-threads", "1", "--slow-report-interval", "0"]),
+        clock().clone(),
+    );
+    assert_eq!(c.num_passed, 1);
+    assert_log!(
+        out,
+        "
+            Starting 1 test
+                SLOW [> 15.000s] slow
+                PASS [  16.000s] slow
+        ------------
+             Summary [  16.000s] 1 test run: 1 passed (1 slow), 0 skipped
+                SLOW [  16.000s] slow
+        "
+    );
+}
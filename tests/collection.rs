@@ -0,0 +1,49 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+fn list_fixtures() -> Result<Vec<String>, &'static str> {
+    Err("permission denied")
+}
+
+async_test::tests!(
+    fn broken(tester: Tester) {
+        tester.add(Trial::test("before_panic", || async {}));
+        for entry in list_fixtures().unwrap() {
+            tester.add(Trial::test(entry, || async {}));
+        }
+    }
+);
+
+async_test::tests!(
+    fn working(tester: Tester) {
+        tester.add(Trial::test("unaffected", || async {}));
+    }
+);
+
+#[test]
+fn reports_collection_error() {
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_passed, 2);
+    assert_eq!(c.num_failed, 0);
+    assert_eq!(c.num_collection_errors, 1);
+    assert!(c.has_failed());
+    assert!(
+        out.contains("ERROR collecting tests in collection::broken"),
+        "{out}"
+    );
+    assert!(out.contains("permission denied"), "{out}");
+    assert!(out.contains("1 collection error,"), "{out}");
+}
+
+#[test]
+fn list_reports_collection_error() {
+    let (c, out) = do_run(args(["--list"]));
+    assert_eq!(c.num_collection_errors, 1);
+    assert!(
+        out.contains("error: collecting tests in collection::broken panicked"),
+        "{out}"
+    );
+}
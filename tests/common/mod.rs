@@ -1,7 +1,13 @@
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
 use pretty_assertions::assert_eq;
 use std::{collections::HashMap, iter::repeat_with, path::Path};
 
-use async_test::{run, Arguments, Conclusion};
+use async_test::{
+    clock::{Clock, SystemClock},
+    run_with_clock, run_with_handle, Arguments, Conclusion, RunHandle,
+};
 
 const TEMPDIR: &str = env!("CARGO_TARGET_TMPDIR");
 
@@ -11,7 +17,23 @@ pub fn args<const N: usize>(args: [&str; N]) -> Arguments {
     Arguments::from_iter(v)
 }
 
-pub fn do_run(mut args: Arguments) -> (Conclusion, String) {
+pub fn do_run(args: Arguments) -> (Conclusion, String) {
+    do_run_with_clock(args, SystemClock)
+}
+
+pub fn do_run_with_clock(args: Arguments, clock: impl Clock) -> (Conclusion, String) {
+    capture_output(args, |args| run_with_clock(args, clock))
+}
+
+pub fn do_run_with_handle(args: Arguments, handle: &RunHandle) -> (Conclusion, String) {
+    capture_output(args, |args| run_with_handle(args, handle))
+}
+
+/// Runs with the output going to a temporary logfile, and returns it.
+fn capture_output(
+    mut args: Arguments,
+    run: impl FnOnce(&Arguments) -> Conclusion,
+) -> (Conclusion, String) {
     // Create path to temporary file.
     let suffix = repeat_with(fastrand::alphanumeric)
         .take(10)
@@ -140,6 +162,7 @@ fn conclusion_to_output(c: &Conclusion) -> String {
         num_filtered_out,
         num_passed,
         num_failed,
+        ..
     } = *c;
     format!(
         "test result: {}. {} passed; {} failed; {} filtered out;",
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+        tester.add(Trial::test("new", || async {}));
+        tester.add(Trial::test("unchanged", || async {}));
+    }
+);
+
+const BASELINE: &str = r#"{"schema_version":1,"type":"run-started","test_count":4,"skip_count":0,"diagnostics":[]}
+{"schema_version":1,"type":"test-finished","name":"passes","status":"failed","duration_secs":0.5,"slow":false,"output":"boom"}
+{"schema_version":1,"type":"test-finished","name":"fails","status":"passed","duration_secs":0.0,"slow":false,"output":null}
+{"schema_version":1,"type":"test-finished","name":"unchanged","status":"passed","duration_secs":0.0,"slow":false,"output":null}
+{"schema_version":1,"type":"test-finished","name":"gone","status":"passed","duration_secs":0.0,"slow":false,"output":null}
+"#;
+
+fn baseline(name: &str) -> String {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, BASELINE).unwrap();
+    path.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn prints_changes() {
+    let path = baseline("compare_baseline.json");
+    let (c, out) = do_run(args(["--compare-with", &path, "--test-threads", "1"]));
+    assert_eq!(c.num_failed, 1);
+
+    let compared = out.lines().find(|line| line.contains("Compared")).unwrap();
+    assert!(
+        compared.ends_with(": 1 newly failing, 1 newly passing, 0 newly slow, 1 added, 1 removed"),
+        "{out}"
+    );
+    assert!(
+        out.lines()
+            .any(|line| line.trim_start().starts_with("NEW FAIL") && line.contains("fails (+")),
+        "{out}"
+    );
+    assert!(
+        out.lines()
+            .any(|line| line.trim_start().starts_with("NEW PASS") && line.contains("passes (-0.")),
+        "{out}"
+    );
+    assert!(out.contains("       ADDED new\n"), "{out}");
+    assert!(out.contains("     REMOVED gone\n"), "{out}");
+}
+
+#[test]
+fn filtered_tests_are_not_removed() {
+    let path = baseline("compare_filtered_baseline.json");
+    let (_, out) = do_run(args(["--compare-with", &path, "unchanged"]));
+    assert!(
+        out.contains(": 0 newly failing, 0 newly passing, 0 newly slow, 0 added, 1 removed"),
+        "{out}"
+    );
+}
+
+#[test]
+fn json_comparison() {
+    let path = baseline("compare_json_baseline.json");
+    let (_, out) = do_run(args(["--compare-with", &path, "--format", "json"]));
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let [.., comparison, finished] = &lines[..] else {
+        panic!("{out}")
+    };
+    assert_eq!(finished["type"], "run-finished");
+    assert_eq!(comparison["type"], "comparison");
+    assert_eq!(comparison["added"], serde_json::json!(["new"]));
+    assert_eq!(comparison["removed"], serde_json::json!(["gone"]));
+    assert_eq!(comparison["newly_failing"][0]["name"], "fails");
+    assert_eq!(
+        comparison["newly_passing"][0]["previous_duration_secs"],
+        0.5
+    );
+}
@@ -0,0 +1,46 @@
+use std::sync::{Arc, Mutex};
+
+use async_test::{
+    report::{Event, TestStatus},
+    run_with_reporter, Tester, Trial,
+};
+use common::args;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("broken") }));
+    }
+);
+
+#[test]
+fn receives_events() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let collected = events.clone();
+    let c = run_with_reporter(&args([]), move |event: &Event| {
+        collected.lock().unwrap().push(event.clone());
+    });
+    assert_eq!((c.num_passed, c.num_failed), (1, 1));
+
+    let events = events.lock().unwrap();
+    assert!(matches!(events.first(), Some(Event::RunStarted { .. })));
+    assert!(matches!(events.last(), Some(Event::RunFinished(_))));
+    let mut results: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::TestFinished(result) => Some((result.name.as_str(), result.status)),
+            _ => None,
+        })
+        .collect();
+    results.sort_by_key(|(name, _)| *name);
+    assert_eq!(
+        results,
+        [
+            ("fails", TestStatus::Failed),
+            ("passes", TestStatus::Passed)
+        ]
+    );
+}
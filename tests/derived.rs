@@ -0,0 +1,68 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use async_test::cache;
+use common::do_run;
+
+#[macro_use]
+mod common;
+
+static COMPILED: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Module(Vec<u8>);
+
+async_test::setup!(
+    async fn module() -> Module {
+        let source = b"(module (func $add))";
+        let compiled = cache::derived("wasm", source, || async {
+            COMPILED.fetch_add(1, Ordering::SeqCst);
+            source.iter().rev().copied().collect()
+        })
+        .await
+        .unwrap();
+        Module(compiled)
+    }
+);
+
+async_test::test!(
+    async fn uses_module(module: &Module) {
+        assert_eq!(module.0, b"))dda$ cnuf( eludom(");
+    }
+);
+
+/// Where the results of `name` are cached, like the harness finds it.
+fn cache_dir(name: &str) -> PathBuf {
+    let target = match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let exe = std::env::current_exe().unwrap();
+            exe.ancestors().nth(3).unwrap().to_owned()
+        }
+    };
+    target.join("async-test").join("cache").join(name)
+}
+
+#[test]
+fn derived_once() {
+    // A result of an earlier input, which the rebuild replaces.
+    let stale = cache_dir("wasm").join("0000000000000000-0");
+    std::fs::create_dir_all(stale.parent().unwrap()).unwrap();
+    std::fs::write(&stale, b"stale").unwrap();
+
+    for (flags, compiled) in [
+        (&["--fixture-cache", "rebuild"][..], 1),
+        (&[], 1),
+        (&["--no-cache"], 2),
+        (&[], 2),
+    ] {
+        let mut v = vec!["<dummy-executable>"];
+        v.extend(flags);
+        let (c, out) = do_run(v.into_iter().collect());
+        assert_eq!(c.num_passed, 1, "{out}");
+        assert_eq!(COMPILED.load(Ordering::SeqCst), compiled, "{flags:?}");
+    }
+    let entries = std::fs::read_dir(cache_dir("wasm")).unwrap().count();
+    assert_eq!(entries, 1);
+}
@@ -0,0 +1,48 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    /// Verifies the corner case
+    /// from RFC 1234.
+    async fn documented() {
+        panic!("boom")
+    }
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("described", || async { panic!("boom") })
+                .with_description("checks the answer"),
+        );
+    }
+);
+
+#[test]
+fn printed_under_failure_header() {
+    let (c, out) = do_run(args(["--test-threads", "1"]));
+    assert_eq!(c.num_failed, 2);
+    assert!(
+        out.contains("described ---\nchecks the answer\n\nthread 'main' panicked at 'boom'"),
+        "{out}"
+    );
+    assert!(
+        out.contains("documented ---\nVerifies the corner case\nfrom RFC 1234.\n\n"),
+        "{out}"
+    );
+}
+
+#[test]
+fn included_in_json() {
+    let (_, out) = do_run(args(["--format", "json", "--exact", "described"]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(finished["description"], "checks the answer");
+}
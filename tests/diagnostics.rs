@@ -0,0 +1,28 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn fixtures(tester: Tester) {
+        tester.add(Trial::test("readable", || async {}));
+        tester.warn("skipping dir private: permission denied");
+        tester.note("found 1 fixture");
+    }
+);
+
+#[test]
+fn printed_before_start() {
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_passed, 1);
+    assert!(!c.has_failed());
+    assert!(
+        out.starts_with(
+            "     Warning diagnostics::fixtures: skipping dir private: permission denied\n        \
+            Note diagnostics::fixtures: found 1 fixture\n    \
+            Starting 1 test\n"
+        ),
+        "{out}"
+    );
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for name in ["instant_a", "instant_b", "instant_c"] {
+            tester.add(Trial::test(name, || async {}));
+        }
+        tester.add(Trial::test("sleeps", || async {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+        }));
+    }
+);
+
+#[test]
+fn verbose() {
+    let (_, out) = do_run(args(["--verbose", "--color", "never"]));
+    let percentiles = out
+        .lines()
+        .find(|line| line.trim_start().starts_with("Durations "))
+        .unwrap_or_else(|| panic!("{out}"));
+    for label in [" p50 ", " p90 ", " p99 ", " max "] {
+        assert!(percentiles.contains(label), "{percentiles}");
+    }
+    let bucket = |label: &str| {
+        out.lines()
+            .find(|line| line.trim_start().starts_with(label))
+            .unwrap_or_else(|| panic!("{label}\n{out}"))
+            .rsplit(' ')
+            .next()
+            .unwrap()
+            .to_owned()
+    };
+    assert_eq!(bucket("< 1s "), "1", "{out}");
+    assert_eq!(bucket(">= 1m "), "0", "{out}");
+
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains("Durations"), "{out}");
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args(["--format", "json"]));
+    let finished: Value = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "run-finished")
+        .unwrap();
+    let durations = &finished["durations"];
+    assert!(durations["p50_secs"].as_f64().unwrap() < 0.1, "{out}");
+    assert!(durations["max_secs"].as_f64().unwrap() >= 0.14, "{out}");
+    let histogram = durations["histogram"].as_array().unwrap();
+    assert_eq!(histogram.len(), 6, "{out}");
+    let total: u64 = histogram.iter().map(|b| b["count"].as_u64().unwrap()).sum();
+    assert_eq!(total, 4, "{out}");
+    assert_eq!(histogram[5]["below_secs"], Value::Null, "{out}");
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+const ENV: &str = r#"
+# Settings for the test environment.
+ASYNC_TEST_ENV_URL=http://staging
+export ASYNC_TEST_ENV_QUOTED="with spaces"
+ASYNC_TEST_ENV_SINGLE='single'
+"#;
+
+async_test::test!(
+    async fn sees_env() {
+        assert_eq!(
+            std::env::var("ASYNC_TEST_ENV_URL").unwrap(),
+            "http://staging"
+        );
+        assert_eq!(
+            std::env::var("ASYNC_TEST_ENV_QUOTED").unwrap(),
+            "with spaces"
+        );
+        assert_eq!(std::env::var("ASYNC_TEST_ENV_SINGLE").unwrap(), "single");
+    }
+);
+
+#[test]
+fn loads_variables_and_reports_keys() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test.env");
+    std::fs::write(&path, ENV).unwrap();
+
+    let (c, out) = do_run(args([
+        "--env-file",
+        path.to_str().unwrap(),
+        "--format",
+        "json",
+    ]));
+    assert_eq!(c.num_passed, 1, "{out}");
+
+    let started: Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+    assert_eq!(
+        started["env_file_keys"],
+        serde_json::json!([
+            "ASYNC_TEST_ENV_URL",
+            "ASYNC_TEST_ENV_QUOTED",
+            "ASYNC_TEST_ENV_SINGLE"
+        ])
+    );
+    assert!(!out.contains("http://staging"), "{out}");
+}
+
+#[test]
+fn loaded_before_detecting_ci() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ci.env");
+    std::fs::write(&path, format!("{ENV}CI=true\n")).unwrap();
+
+    let (c, out) = do_run(args([
+        "--env-file",
+        path.to_str().unwrap(),
+        "--verbose",
+        "--color",
+        "never",
+    ]));
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert!(out.contains("ci=true"), "{out}");
+}
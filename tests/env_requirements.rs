@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn uses_the_environment() {}
+);
+
+fn write(name: &str, contents: &str) -> String {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn met_requirements_run() {
+    let path = write(
+        "env-requirements-met.toml",
+        r#"
+        [environment]
+        min_open_files = 1
+        env_vars = ["PATH"]
+        "#,
+    );
+    let (c, _) = do_run(args(["--env-requirements", &path]));
+    assert_eq!(c.num_passed, 1);
+}
+
+#[test]
+fn listing_does_not_check() {
+    let path = write(
+        "env-requirements-unmet.toml",
+        r#"
+        [environment]
+        binaries = ["async-test-no-such-binary"]
+        "#,
+    );
+    let (_, out) = do_run(args(["--env-requirements", &path, "--list"]));
+    assert_log!(
+        out,
+        "
+            uses_the_environment: test
+        "
+    );
+}
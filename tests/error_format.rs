@@ -0,0 +1,102 @@
+use std::{error::Error, fmt, io};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+#[derive(Debug)]
+struct LoadConfig(io::Error);
+
+impl fmt::Display for LoadConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to load the config")
+    }
+}
+
+impl Error for LoadConfig {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+async_test::test!(
+    async fn returns_ok() -> Result<(), LoadConfig> {
+        Ok(())
+    }
+);
+
+async_test::test!(
+    async fn returns_err() -> Result<(), LoadConfig> {
+        Err(LoadConfig(io::Error::new(
+            io::ErrorKind::NotFound,
+            "config.toml is missing",
+        )))
+    }
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::fallible("returns_string", || async {
+            Err::<(), _>("just a message")
+        }));
+    }
+);
+
+#[test]
+fn display_with_sources() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 2));
+    assert!(
+        out.contains(
+            "test returned an error: failed to load the config\n\n\
+             Caused by:\n    \
+             0: config.toml is missing\n"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains("test returned an error: just a message\n"),
+        "{out}"
+    );
+}
+
+#[test]
+fn debug() {
+    let (_, out) = do_run(args(["--error-format", "debug", "--exact", "returns_err"]));
+    assert!(
+        out.contains(
+            "test returned an error: LoadConfig(Custom { kind: NotFound, error: \"config.toml is missing\" })\n\n\
+             Caused by:\n    \
+             0: config.toml is missing\n"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args([
+        "--format",
+        "json",
+        "--error-format",
+        "json",
+        "--exact",
+        "returns_err",
+    ]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(finished["status"], "failed");
+    let message = finished["failures"][0]["message"].as_str().unwrap();
+    let error: Value = serde_json::from_str(message).unwrap();
+    assert_eq!(error["error"], "failed to load the config");
+    assert_eq!(
+        error["sources"],
+        serde_json::json!(["config.toml is missing"])
+    );
+}
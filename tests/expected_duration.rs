@@ -0,0 +1,55 @@
+use std::{sync::OnceLock, time::Duration};
+
+use async_test::{clock::MockClock, Tester, Trial};
+use common::{args, do_run_with_clock};
+
+#[macro_use]
+mod common;
+
+static CLOCK: OnceLock<MockClock> = OnceLock::new();
+
+fn clock() -> &'static MockClock {
+    CLOCK.get_or_init(MockClock::new)
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("overran", || async {
+                clock().advance(Duration::from_secs(5));
+                // Lets the harness notice the slow period has passed.
+                tokio::task::yield_now().await;
+            })
+            .with_expected_duration(Duration::from_secs(2)),
+        );
+        tester.add(
+            Trial::test("within", || async {
+                clock().advance(Duration::from_secs(1));
+            })
+            .with_expected_duration(Duration::from_secs(2)),
+        );
+    }
+);
+
+#[test]
+fn slow_relative_to_expected() {
+    let (c, out) = do_run_with_clock(
+        args(["--test-threads", "1", "--slow-report-interval", "0"]),
+        clock().clone(),
+    );
+    assert_eq!(c.num_passed, 2);
+    assert_log!(
+        out,
+        "
+            Starting 2 tests
+                SLOW [>  2.000s] overran
+                PASS [   5.000s] overran
+                PASS [   1.000s] within
+        ------------
+             Summary [   6.000s] 2 tests run: 2 passed (1 slow), 0 skipped
+                SLOW [   5.000s] overran
+            Expected 1 test exceeded their expected duration:
+                OVER [   5.000s] overran (expected 2.000s)
+        "
+    );
+}
@@ -0,0 +1,55 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("known_bug", || async { panic!("still broken") })
+                .with_expected_failure("issue 12"),
+        );
+        tester.add(Trial::test("fixed_bug", || async {}).with_expected_failure("issue 34"));
+        tester.add(Trial::test("passes", || async {}));
+    }
+);
+
+#[test]
+fn xfail_passes_and_xpass_fails() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 1));
+    assert!(out.contains("XFAIL ["), "{out}");
+    assert!(out.contains("XPASS ["), "{out}");
+    assert!(
+        out.contains("test passed, but was expected to fail: issue 34"),
+        "{out}"
+    );
+    assert!(!out.contains("still broken"), "{out}");
+    assert!(
+        out.contains("2 passed (1 expected to fail), 1 failed"),
+        "{out}"
+    );
+}
+
+#[test]
+fn reported_in_json() {
+    let (_, out) = do_run(args(["--format", "json"]));
+    let results: Vec<_> = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|event| event["type"] == "test-finished")
+        .collect();
+    let result = |name: &str| {
+        results
+            .iter()
+            .find(|result| result["name"] == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(result("known_bug")["status"], "passed");
+    assert_eq!(result("known_bug")["expected_failure"], "issue 12");
+    assert_eq!(result("fixed_bug")["status"], "failed");
+    assert_eq!(result("passes")["expected_failure"], Value::Null);
+}
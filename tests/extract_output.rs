@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+    }
+);
+
+#[test]
+fn replays_stored_failure() {
+    let (_, report) = do_run(args(["--format", "json"]));
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("extract_output_report.json");
+    std::fs::write(&path, report).unwrap();
+    let path = path.to_str().unwrap();
+
+    let (c, out) = do_run(args(["--extract-output", "fails", "--from-report", path]));
+    assert_eq!(c.num_failed, 1);
+    assert!(out.starts_with("--- fails: failed after "), "{out}");
+    assert!(out.contains("panicked at 'boom'"), "{out}");
+
+    let (c, out) = do_run(args(["--extract-output", "passes", "--from-report", path]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(out.lines().count(), 1, "{out}");
+}
@@ -0,0 +1,70 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_passes", || async {}));
+        tester.add(Trial::test("b_fails", || async { panic!("oops") }));
+        tester.add(Trial::test("c_never_starts", || async {}));
+        tester.add(Trial::test("d_never_starts", || async {}));
+    }
+);
+
+#[test]
+fn stops_starting_tests() {
+    let (c, out) = do_run(args([
+        "--fail-fast",
+        "--test-tasks",
+        "1",
+        "--color",
+        "never",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 1), "{out}");
+    assert!(
+        out.contains("Canceling due to test failure (b_fails failed): 0 tests still running"),
+        "{out}"
+    );
+    assert!(
+        out.contains("NOT RUN [         ] c_never_starts (a test failed with --fail-fast)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("2/4 tests run: 1 passed, 1 failed, 2 not run (fail-fast), 0 skipped"),
+        "{out}"
+    );
+}
+
+#[test]
+fn reported_as_not_run() {
+    let (_, out) = do_run(args([
+        "--fail-fast",
+        "--test-tasks",
+        "1",
+        "--format",
+        "json",
+    ]));
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let canceled = events.iter().find(|e| e["type"] == "run-canceled").unwrap();
+    assert_eq!(canceled["reason"], "b_fails failed");
+    let not_run: Vec<_> = events
+        .iter()
+        .filter(|e| e["type"] == "test-skipped" && e["reason"] == "fail-fast")
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(not_run, ["c_never_starts", "d_never_starts"]);
+    let summary = events.last().unwrap();
+    assert_eq!(summary["not_run"], 2);
+}
+
+#[test]
+fn no_fail_fast_overrides() {
+    let (c, _) = do_run(args(["--fail-fast", "--no-fail-fast", "--test-tasks", "1"]));
+    assert_eq!((c.num_passed, c.num_failed), (3, 1));
+}
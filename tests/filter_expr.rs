@@ -0,0 +1,93 @@
+use async_test::{Arguments, Tester, Trial};
+use clap::Parser;
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+pub struct Database {}
+
+async_test::setup!(
+    async fn database() -> Database {
+        Database {}
+    }
+);
+
+async_test::test!(
+    async fn db_query(_db: &Database) {}
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("db_migrate", || async {}).with_tags(["slow"]));
+        tester.add(Trial::test("fetch", || async {}).with_tags(["net"]));
+        tester.add(Trial::test("fetch_all", || async {}).with_tags(["net", "slow"]));
+    }
+);
+
+fn list(extra: &[&str]) -> Vec<String> {
+    let mut v = vec!["<dummy-executable>", "--list"];
+    v.extend(extra);
+    let (_, out) = do_run(v.into_iter().collect());
+    let mut names: Vec<_> = out.lines().map(str::to_owned).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn predicates() {
+    assert_eq!(
+        list(&["-E", "test(db_)"]),
+        ["db_migrate: test", "db_query: test"]
+    );
+    assert_eq!(list(&["-E", "test(=fetch)"]), ["fetch: test"]);
+    assert_eq!(
+        list(&["-E", "tag(slow)"]),
+        ["db_migrate: test", "fetch_all: test"]
+    );
+    assert_eq!(list(&["-E", "requires(Database)"]), ["db_query: test"]);
+    assert_eq!(list(&["-E", "none()"]), Vec::<String>::new());
+}
+
+#[test]
+fn operators() {
+    assert_eq!(
+        list(&["-E", "test(db_) and not tag(slow)"]),
+        ["db_query: test"]
+    );
+    assert_eq!(
+        list(&["-E", "!(tag(net) | requires(Database))"]),
+        ["db_migrate: test"]
+    );
+    assert_eq!(
+        list(&["-E", "tag(slow) & tag(net) or test(=db_query)"]),
+        ["db_query: test", "fetch_all: test"]
+    );
+}
+
+#[test]
+fn combined() {
+    // Any of the expressions, and the name filters too.
+    assert_eq!(
+        list(&["-E", "tag(net)", "-E", "requires(Database)"]),
+        ["db_query: test", "fetch: test", "fetch_all: test"]
+    );
+    assert_eq!(list(&["-E", "tag(net)", "fetch_"]), ["fetch_all: test"]);
+}
+
+#[test]
+fn run() {
+    let (c, _) = do_run(args(["-E", "tag(slow)"]));
+    assert_eq!((c.num_passed, c.num_filtered_out), (2, 2));
+}
+
+#[test]
+fn invalid() {
+    let err = Arguments::try_parse_from(["<dummy-executable>", "-E", "test(a) and"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    assert!(
+        err.to_string()
+            .contains("expected an expression at the end"),
+        "{err}"
+    );
+}
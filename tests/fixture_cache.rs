@@ -0,0 +1,51 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use async_test::cache::CachedArtifact;
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+/// Whether each setup found its artifact warm.
+static WARM: Mutex<Vec<bool>> = Mutex::new(vec![]);
+/// The directory of the last artifact.
+static PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub struct Dump(String);
+
+async_test::setup!(
+    async fn dump() -> Dump {
+        let artifact = CachedArtifact::open("dump", "schema-v1").unwrap();
+        let file = artifact.path().join("dump.sql");
+        WARM.lock().unwrap().push(artifact.is_warm());
+        *PATH.lock().unwrap() = Some(artifact.path().to_owned());
+        if !artifact.is_warm() {
+            std::fs::write(&file, "create table t;").unwrap();
+            artifact.finish().unwrap();
+        }
+        Dump(std::fs::read_to_string(file).unwrap())
+    }
+);
+
+async_test::test!(
+    async fn uses_dump(dump: &Dump) {
+        assert_eq!(dump.0, "create table t;");
+    }
+);
+
+#[test]
+fn warm_and_cold() {
+    for (setting, warm) in [
+        ("rebuild", false),
+        ("use", true),
+        ("off", false),
+        ("use", true),
+    ] {
+        let (c, out) = do_run(args(["--fixture-cache", setting]));
+        assert_eq!(c.num_passed, 1, "{out}");
+        assert_eq!(WARM.lock().unwrap().pop(), Some(warm), "{setting}");
+        // Artifacts built without the cache are removed with the run.
+        let path = PATH.lock().unwrap().take().unwrap();
+        assert_eq!(path.exists(), setting != "off", "{setting}");
+    }
+}
@@ -0,0 +1,41 @@
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+pub struct Config {}
+
+async_test::setup!(
+    async fn config() -> Config {
+        Config {}
+    }
+);
+
+async_test::test!(
+    async fn uses_config(_config: &Config) {}
+);
+
+async_test::test!(
+    async fn standalone() {}
+);
+
+#[test]
+fn list_verbose() {
+    let (_, out) = do_run(args(["--list", "--verbose", "--exact", "uses_config"]));
+    assert_log!(
+        out,
+        "
+            uses_config: test
+                requires fixtures::Config (shared, set up by fixtures::config)
+        "
+    );
+}
+
+#[test]
+fn filter_requires() {
+    let (_, out) = do_run(args(["--list", "requires(Config)"]));
+    assert_log!(out, "uses_config: test");
+
+    let (_, out) = do_run(args(["--list", "--skip", "requires(Config)"]));
+    assert_log!(out, "standalone: test");
+}
@@ -0,0 +1,26 @@
+use async_test::{Arguments, Tester, Trial};
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        let outer_thread = std::thread::current().id();
+
+        tester.add(
+            Trial::test("forced", move || async move {
+                assert_eq!(outer_thread, std::thread::current().id());
+            })
+            .with_main_thread(true),
+        );
+    }
+);
+
+#[test]
+fn forced_main_thread_with_workers() {
+    let args = Arguments {
+        test_threads: Some(4),
+        ..Arguments::default()
+    };
+
+    let conclusion = async_test::run(&args);
+
+    assert_eq!(conclusion.num_passed, 1);
+}
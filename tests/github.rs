@@ -0,0 +1,45 @@
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn passing() {}
+);
+
+async_test::test!(
+    async fn failing() {
+        assert_eq!(1 + 1, 3, "math, 100% broken");
+    }
+);
+
+#[test]
+fn annotates_failures() {
+    let (c, out) = do_run(args(["--format", "github", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 1), "{out}");
+
+    let annotations: Vec<_> = out
+        .lines()
+        .filter(|line| line.starts_with("::error "))
+        .collect();
+    assert_eq!(annotations.len(), 1, "{out}");
+    assert!(
+        annotations[0].starts_with("::error file=tests/github.rs,line=12,col=9,title=failing::"),
+        "{out}"
+    );
+    assert!(
+        annotations[0]
+            .ends_with("math, 100%25 broken%0A  left: 2%0A right: 3', tests/github.rs:12:9"),
+        "{out}"
+    );
+
+    assert_eq!(out.matches("::group::--- ERROR:").count(), 1, "{out}");
+    assert_eq!(out.matches("::endgroup::").count(), 1, "{out}");
+}
+
+#[test]
+fn not_annotated_by_default() {
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains("::error"), "{out}");
+    assert!(!out.contains("::group::"), "{out}");
+}
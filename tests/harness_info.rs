@@ -0,0 +1,39 @@
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn never_runs() {
+        unreachable!()
+    }
+);
+
+#[test]
+fn describes_the_harness() {
+    let (c, out) = do_run(args(["--harness-info"]));
+    assert_eq!(c.num_passed + c.num_failed, 0);
+
+    let info: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(info["schema_version"], 1);
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    let strings = |key: &str| -> Vec<String> {
+        info[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value.as_str().unwrap().to_owned())
+            .collect()
+    };
+    assert!(strings("formats").contains(&"json".to_owned()), "{out}");
+    assert!(
+        strings("formats").contains(&"summary-line".to_owned()),
+        "{out}"
+    );
+    assert!(
+        strings("flags").contains(&"--harness-info".to_owned()),
+        "{out}"
+    );
+    assert!(strings("flags").contains(&"--exact".to_owned()), "{out}");
+}
@@ -0,0 +1,36 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+async fn heavy() {
+    let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+    MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for name in ["a", "b", "c"] {
+            tester.add(Trial::test(name, heavy).with_tags(["heavy"]));
+        }
+    }
+);
+
+#[test]
+fn heavy_tests_run_alone() {
+    // At most four tasks, so a single heavy task.
+    let (c, _) = do_run(args(["--test-threads", "4"]));
+    assert_eq!(c.num_passed, 3);
+    assert_eq!(MAX_RUNNING.load(Ordering::SeqCst), 1);
+}
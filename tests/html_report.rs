@@ -0,0 +1,59 @@
+use async_test::{Arguments, Tester, Trial};
+use clap::Parser;
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const PATH: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/report.html");
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async {
+            panic!("expected <b> & </b>")
+        }));
+        tester.add(Trial::test("slow", || async {
+            tokio::time::sleep(std::time::Duration::from_millis(150)).await
+        }));
+        tester.add(Trial::test("ignored", || async {}).with_ignored_flag(true));
+    }
+);
+
+#[test]
+fn report() {
+    // Captured backtraces are folded away.
+    std::env::set_var("RUST_BACKTRACE", "1");
+    _ = std::fs::remove_file(PATH);
+    let (c, out) = do_run(args(["--html-report", PATH]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 1), "{out}");
+
+    let html = std::fs::read_to_string(PATH).unwrap();
+    for part in [
+        "<!DOCTYPE html>",
+        "<title>Test run failed</title>",
+        "<p>2 passed, 1 failed, 1 skipped, 0 not run in ",
+        "<h2>Failures</h2>",
+        "<h3 class=\"failed\" id=\"fails\">fails</h3>",
+        "expected &lt;b&gt; &amp; &lt;/b&gt;",
+        "<details><summary>stack backtrace</summary><pre>",
+        "<p>p50 ",
+        ">&lt; 1s</text>",
+        ">&gt;= 1m</text>",
+        "<table class=\"tests\">",
+        "<h2>Slowest tests</h2>",
+        "<td class=\"passed\">PASS</td>",
+        "<script>",
+        "<td><a href=\"#fails\">fails</a></td>",
+    ] {
+        assert!(html.contains(part), "{part}\n{html}");
+    }
+    assert!(!html.contains("<b>"), "{html}");
+    assert!(!html.contains("ignored"), "{html}");
+}
+
+#[test]
+fn open_requires_report() {
+    let err = Arguments::try_parse_from(["<dummy-executable>", "--open-report"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+}
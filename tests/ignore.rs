@@ -0,0 +1,66 @@
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    #[ignore]
+    async fn plain() {}
+);
+
+async_test::test!(
+    #[ignore = "needs a GPU"]
+    async fn with_reason() {}
+);
+
+async_test::test!(
+    #[ignore_if(env = "ASYNC_TEST_IGNORE_IF_SET")]
+    async fn if_set() {}
+);
+
+async_test::test!(
+    #[ignore_if(env = "ASYNC_TEST_IGNORE_IF_UNSET")]
+    async fn if_unset() {}
+);
+
+#[test]
+fn ignored_tests() {
+    // Set before any run, tests are collected at the start of each.
+    std::env::set_var("ASYNC_TEST_IGNORE_IF_SET", "1");
+
+    let (_, out) = do_run(args(["--list", "--ignored"]));
+    let mut names: Vec<_> = out.lines().collect();
+    names.sort();
+    assert_eq!(names, ["if_set: test", "plain: test", "with_reason: test"]);
+
+    let (_, out) = do_run(args([
+        "--list",
+        "--verbose",
+        "--ignored",
+        "--exact",
+        "with_reason",
+    ]));
+    assert_log!(
+        out,
+        "
+            with_reason: test
+                ignored: needs a GPU
+        "
+    );
+
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(c.num_filtered_out, 3);
+    assert!(out.contains("Starting 1 test (3 ignored)"), "{out}");
+    assert!(out.contains("1 passed, 3 ignored\n"), "{out}");
+
+    let (_, out) = do_run(args(["--color", "never", "--skip", "if_unset"]));
+    assert!(
+        out.contains("Starting 0 tests (1 filtered out, 3 ignored)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("0 passed, 1 filtered out, 3 ignored\n"),
+        "{out}"
+    );
+}
@@ -0,0 +1,124 @@
+use async_test::{report::SCHEMA_VERSION, Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.warn("something is off");
+        tester.add(Trial::test("passes", || async {}).with_tags(["smoke"]));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+        tester.add(Trial::test("elsewhere", || async {}).with_required_cfg(false, "miri"));
+    }
+);
+
+fn lines(out: &str) -> Vec<Value> {
+    out.lines()
+        .map(|line| serde_json::from_str(line).expect("every line is a JSON object"))
+        .collect()
+}
+
+#[test]
+fn events() {
+    let (c, out) = do_run(args(["--format", "json"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(c.num_failed, 1);
+
+    let events = lines(&out);
+    for event in &events {
+        assert_eq!(event["schema_version"], SCHEMA_VERSION, "{event}");
+    }
+
+    let first = &events[0];
+    assert_eq!(first["type"], "run-started");
+    assert_eq!(first["test_count"], 2);
+    assert_eq!(first["diagnostics"][0]["level"], "warning");
+    assert_eq!(first["diagnostics"][0]["message"], "something is off");
+
+    let last = events.last().unwrap();
+    assert_eq!(last["type"], "run-finished");
+    assert_eq!(last["passed"], 1);
+    assert_eq!(last["failed"], 1);
+    assert_eq!(last["skipped"], 1);
+
+    let skipped = events.iter().find(|e| e["type"] == "test-skipped").unwrap();
+    assert_eq!(skipped["name"], "elsewhere");
+    assert_eq!(skipped["reason"], "cfg");
+    assert_eq!(skipped["message"], "requires cfg(miri)");
+
+    let failed = events
+        .iter()
+        .find(|e| e["type"] == "test-finished" && e["name"] == "fails")
+        .unwrap();
+    assert_eq!(failed["status"], "failed");
+    assert!(failed["output"].as_str().unwrap().contains("boom"));
+}
+
+#[test]
+fn list() {
+    let (_, out) = do_run(args(["--list", "--format", "json"]));
+    let list = lines(&out);
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["schema_version"], SCHEMA_VERSION);
+
+    let tests = list[0]["tests"].as_array().unwrap();
+    let passes = tests.iter().find(|t| t["name"] == "passes").unwrap();
+    assert_eq!(passes["tags"][0], "smoke");
+    let elsewhere = tests.iter().find(|t| t["name"] == "elsewhere").unwrap();
+    assert_eq!(elsewhere["unmet_cfg"], "miri");
+}
+
+#[test]
+fn pretty() {
+    let (_, out) = do_run(args(["--format", "json-pretty", "--test-threads", "1"]));
+    assert!(out.contains("\n  \"schema_version\": "), "{out}");
+    let events: Vec<Value> = serde_json::Deserializer::from_str(&out)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // The same events as the compact format, apart from the durations.
+    let (_, compact) = do_run(args(["--format", "json", "--test-threads", "1"]));
+    let types = |events: &[Value]| -> Vec<Value> {
+        events.iter().map(|event| event["type"].clone()).collect()
+    };
+    assert_eq!(types(&events), types(&lines(&compact)));
+}
+
+#[cfg(feature = "report-types")]
+#[test]
+fn run_report() {
+    use async_test::report::{RunReport, SkipReason, TestStatus};
+
+    let (_, out) = do_run(args(["--format", "json"]));
+    let report = RunReport::parse(&out).unwrap();
+    assert_eq!(report.schema_version, SCHEMA_VERSION);
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.test("passes").unwrap().status, TestStatus::Passed);
+    assert_eq!(report.test("fails").unwrap().status, TestStatus::Failed);
+    assert_eq!(report.skipped[0].reason, SkipReason::Cfg);
+    assert_eq!(report.summary.unwrap().failed, 1);
+
+    // Values from newer schema versions are tolerated.
+    let newer = r#"{"schema_version":2,"type":"test-retried","name":"x"}
+{"schema_version":2,"type":"test-finished","name":"x","status":"flaky","duration_secs":1.0,"slow":false,"output":null,"attempts":2}"#;
+    let report = RunReport::parse(newer).unwrap();
+    assert_eq!(report.schema_version, 2);
+    assert_eq!(report.test("x").unwrap().status, TestStatus::Unknown);
+
+    // A stream cut short by a crash shows what was still running.
+    let crashed = r#"{"schema_version":1,"type":"run-started","test_count":2,"skip_count":0,"diagnostics":[]}
+{"schema_version":1,"type":"test-started","name":"quick"}
+{"schema_version":1,"type":"test-started","name":"hangs"}
+{"schema_version":1,"type":"test-finished","name":"quick","status":"passed","duration_secs":0.0,"slow":false,"output":null}"#;
+    let report = RunReport::parse(crashed).unwrap();
+    assert!(report.summary.is_none());
+    assert_eq!(report.unfinished, ["hangs"]);
+
+    // So does a checkpoint that is still being written to.
+    let cut = format!("{crashed}\n{{\"schema_version\":1,\"type\":\"test-fini");
+    let report = RunReport::parse(&cut).unwrap();
+    assert_eq!(report.unfinished, ["hangs"]);
+}
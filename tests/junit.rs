@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn junit_passes() {}
+);
+
+async_test::test!(
+    async fn junit_fails() {
+        panic!("junit failure message");
+    }
+);
+
+#[test]
+fn writes_report() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("junit/report.xml");
+    _ = std::fs::remove_file(&path);
+
+    let (c, out) = do_run(args(["--junit-path", path.to_str().unwrap()]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 1));
+    // The regular output is unchanged.
+    assert!(out.contains("PASS ["), "{out}");
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    assert!(report.contains("junit_passes"), "{report}");
+    assert!(report.contains("junit_fails"), "{report}");
+    assert!(report.contains("junit failure message"), "{report}");
+}
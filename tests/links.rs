@@ -0,0 +1,58 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("linked", || async { panic!("boom") })
+                .with_link("issue", "https://example.com/issues/1")
+                .with_link("docs", "https://example.com/docs"),
+        );
+    }
+);
+
+#[test]
+fn printed_under_failure_header() {
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_failed, 1);
+    assert!(
+        out.contains(
+            "linked ---\nissue: https://example.com/issues/1\n\
+             docs: https://example.com/docs\n\nthread 'main' panicked at 'boom'"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn hyperlinked_when_colorized() {
+    let (_, out) = do_run(args(["--color", "always"]));
+    assert!(
+        out.contains(
+            "\x1b]8;;https://example.com/issues/1\x1b\\https://example.com/issues/1\x1b]8;;\x1b\\"
+        ),
+        "{out:?}"
+    );
+}
+
+#[test]
+fn included_in_json() {
+    let (_, out) = do_run(args(["--format", "json"]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(finished["links"][0]["label"], "issue");
+    assert_eq!(finished["links"][1]["url"], "https://example.com/docs");
+}
+
+#[test]
+fn listed_verbose() {
+    let (_, out) = do_run(args(["--list", "--verbose"]));
+    assert!(out.contains("    issue: https://example.com/issues/1\n"), "{out}");
+}
@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+use std::{os::unix::net::UnixDatagram, path::Path};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("good", || async {}));
+        tester.add(Trial::test("bad", || async { panic!("oops") }));
+    }
+);
+
+fn receive_all(socket: &UnixDatagram) -> Vec<Vec<u8>> {
+    socket.set_nonblocking(true).unwrap();
+    let mut buf = vec![0; 65536];
+    let mut datagrams = vec![];
+    while let Ok(n) = socket.recv(&mut buf) {
+        datagrams.push(buf[..n].to_vec());
+    }
+    datagrams
+}
+
+fn bind(name: &str) -> (UnixDatagram, String) {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    (socket, path.display().to_string())
+}
+
+#[test]
+fn syslog() {
+    let (socket, path) = bind("log_events_syslog.sock");
+    let args = args([
+        "--test-threads",
+        "1",
+        "--log-events",
+        "syslog",
+        "--log-events-socket",
+        &path,
+    ]);
+    let (c, _) = do_run(args);
+    assert_eq!((c.num_passed, c.num_failed), (1, 1));
+
+    let messages: Vec<String> = receive_all(&socket)
+        .into_iter()
+        .map(|datagram| String::from_utf8(datagram).unwrap())
+        .collect();
+    assert_eq!(messages.len(), 6, "{messages:#?}");
+    assert!(
+        messages[0].starts_with("<14>1 - - async-test "),
+        "{}",
+        messages[0]
+    );
+    assert!(
+        messages[0].ends_with(" starting 2 tests"),
+        "{}",
+        messages[0]
+    );
+
+    let bad = messages
+        .iter()
+        .find(|m| m.contains("test_name=\"bad\" test_status=\"failed\""))
+        .unwrap();
+    assert!(bad.starts_with("<11>1 "), "{bad}");
+    assert!(bad.contains("] bad failed in "), "{bad}");
+    assert!(bad.contains("oops"), "{bad}");
+
+    let finished = messages.last().unwrap();
+    assert!(finished.starts_with("<11>1 "), "{finished}");
+    assert!(
+        finished.contains("tests_passed=\"1\" tests_failed=\"1\" tests_skipped=\"0\""),
+        "{finished}"
+    );
+}
+
+#[test]
+fn journald() {
+    let (socket, path) = bind("log_events_journald.sock");
+    let args = args([
+        "--test-threads",
+        "1",
+        "--log-events",
+        "journald",
+        "--log-events-socket",
+        &path,
+    ]);
+    let (c, _) = do_run(args);
+    assert_eq!((c.num_passed, c.num_failed), (1, 1));
+
+    let messages = receive_all(&socket);
+    assert_eq!(messages.len(), 6);
+    let good = messages
+        .iter()
+        .map(|m| String::from_utf8_lossy(m))
+        .find(|m| m.contains("\nTEST_STATUS=passed\n"))
+        .unwrap()
+        .into_owned();
+    assert!(good.starts_with("MESSAGE=good passed in "), "{good}");
+    assert!(
+        good.contains("\nPRIORITY=6\nSYSLOG_IDENTIFIER=async-test\nTEST_NAME=good\n"),
+        "{good}"
+    );
+    assert!(good.contains("\nTEST_DURATION_SECS="), "{good}");
+}
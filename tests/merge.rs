@@ -0,0 +1,46 @@
+use async_test::{merge_reports, Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const COMBINED: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/merge-combined.json");
+const UNIT: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/merge-unit.json");
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+    }
+);
+
+#[test]
+fn merges_runs() {
+    _ = std::fs::remove_file(COMBINED);
+    let mut unit = args(["--format", "json", "--skip", "fails"]);
+    unit.logfile = Some(UNIT.to_owned());
+    _ = async_test::run(&unit);
+
+    // Running the binary again replaces its results.
+    for _ in 0..2 {
+        let (c, out) = do_run(args(["--merge-into", COMBINED]));
+        assert_eq!((c.num_passed, c.num_failed), (1, 1), "{out}");
+    }
+    let report = merge_reports([COMBINED]).unwrap();
+    assert_eq!(report.binaries().collect::<Vec<_>>(), ["merge"]);
+    assert_eq!((report.passed(), report.failed()), (1, 1));
+
+    let report = merge_reports([COMBINED, UNIT]).unwrap();
+    assert_eq!(
+        report.binaries().collect::<Vec<_>>(),
+        ["merge", "merge-unit"]
+    );
+    assert_eq!((report.passed(), report.failed()), (2, 1));
+
+    let mut json = vec![];
+    report.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    let finished = json.lines().last().unwrap();
+    assert!(finished.contains(r#""type":"run-finished""#), "{json}");
+    assert!(!finished.contains(r#""binary""#), "{json}");
+}
@@ -0,0 +1,46 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const PATH: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/metrics.prom");
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}).with_tags(["smoke"]));
+        tester.add(Trial::test("fails", || async { panic!("boom") }).with_tags(["smoke", "db"]));
+        tester.add(Trial::test("untagged", || async {}));
+        tester.add(Trial::test("ignored", || async {}).with_ignored_flag(true));
+    }
+);
+
+#[test]
+fn counters() {
+    _ = std::fs::remove_file(PATH);
+    let (c, out) = do_run(args(["--metrics-file", PATH]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 1), "{out}");
+
+    let metrics = std::fs::read_to_string(PATH).unwrap();
+    for line in [
+        "# TYPE async_test_tests_total counter",
+        "async_test_tests_total{result=\"passed\"} 2",
+        "async_test_tests_total{result=\"failed\"} 1",
+        "async_test_tests_total{result=\"skipped\"} 1",
+        "async_test_failed_total 1",
+    ] {
+        assert!(metrics.lines().any(|l| l == line), "{line}\n{metrics}");
+    }
+    for series in [
+        "async_test_duration_seconds{kind=\"run\",tag=\"\"} ",
+        "async_test_duration_seconds{kind=\"test\",tag=\"\"} ",
+        "async_test_duration_seconds{kind=\"test\",tag=\"db\"} ",
+        "async_test_duration_seconds{kind=\"test\",tag=\"smoke\"} ",
+    ] {
+        let value = metrics
+            .lines()
+            .find_map(|l| l.strip_prefix(series))
+            .unwrap_or_else(|| panic!("{series}\n{metrics}"));
+        value.parse::<f64>().unwrap();
+    }
+}
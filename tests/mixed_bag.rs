@@ -61,6 +61,11 @@ fn normal() {
             num_filtered_out: 4,
             num_passed: 2,
             num_failed: 2,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test          cat   ... ok
@@ -97,6 +102,11 @@ fn test_mode() {
             num_filtered_out: 4,
             num_passed: 2,
             num_failed: 2,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test          cat   ... ok
@@ -146,6 +156,11 @@ fn list() {
             num_filtered_out: 0,
             num_passed: 0,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     );
 }
@@ -168,6 +183,11 @@ fn list_ignored() {
             num_filtered_out: 0,
             num_passed: 0,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     );
 }
@@ -188,6 +208,11 @@ fn list_with_filter() {
             num_filtered_out: 0,
             num_passed: 0,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     );
 }
@@ -201,6 +226,11 @@ fn filter_c() {
             num_filtered_out: 7,
             num_passed: 1,
             num_failed: 0,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test cat ... ok
@@ -217,6 +247,11 @@ fn filter_o_test() {
             num_filtered_out: 6,
             num_passed: 1,
             num_failed: 1,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test         dog  ... FAILED
@@ -245,6 +280,11 @@ fn filter_o_test_include_ignored() {
             num_filtered_out: 4,
             num_passed: 2,
             num_failed: 2,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test         dog  ... FAILED
@@ -277,6 +317,11 @@ fn filter_o_test_ignored() {
             num_filtered_out: 6,
             num_passed: 1,
             num_failed: 1,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test frog ... ok
@@ -303,6 +348,11 @@ fn normal_include_ignored() {
             num_filtered_out: 0,
             num_passed: 4,
             num_failed: 4,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test          cat   ... ok
@@ -347,6 +397,11 @@ fn normal_ignored() {
             num_filtered_out: 4,
             num_passed: 2,
             num_failed: 2,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test          frog ... ok
@@ -379,6 +434,11 @@ fn lots_of_flags() {
             num_filtered_out: 6,
             num_passed: 1,
             num_failed: 1,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test [apple] fox ... ok
@@ -405,6 +465,11 @@ fn terse_output() {
             num_filtered_out: 4,
             num_passed: 2,
             num_failed: 2,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         }
     );
     assert_log!(
@@ -0,0 +1,29 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for name in ["conformance/parse_a", "conformance/parse_b", "other"] {
+            tester.add(Trial::test(name, || async {}));
+        }
+    }
+);
+
+fn strip_prefix(name: &str) -> String {
+    name.trim_start_matches("conformance/").to_owned()
+}
+
+#[test]
+fn filters_use_mapped_names() {
+    let (c, out) =
+        do_run(args(["--exact", "parse_a", "--color", "never"]).with_name_mapper(strip_prefix));
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert!(out.contains("] parse_a\n"), "{out}");
+    assert!(!out.contains("conformance/"), "{out}");
+
+    let (c, _) = do_run(args(["--exact", "conformance/parse_a"]).with_name_mapper(strip_prefix));
+    assert_eq!(c.num_passed, 0);
+}
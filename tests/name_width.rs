@@ -0,0 +1,29 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const LONG: &str = "cases::parse::input_0123456789_abcdefghij_klmnopqrst";
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test(LONG, || async { panic!("bad input") }));
+    }
+);
+
+#[test]
+fn shortened_in_status_lines_only() {
+    let (_, out) = do_run(args(["--name-width", "20", "--color", "never"]));
+    let lines: Vec<_> = out.lines().filter(|line| line.contains("FAIL [")).collect();
+    // The status line while running, then the one in the final summary.
+    assert_eq!(lines.len(), 2, "{out}");
+    assert!(lines[0].ends_with("] cases::pa…klmnopqrst"), "{out}");
+    assert!(lines[1].ends_with(LONG), "{out}");
+}
+
+#[test]
+fn full_names_by_default() {
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains('…'), "{out}");
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "notify-webhook")]
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("good", || async {}));
+        tester.add(Trial::test("bad", || async { panic!("oops") }));
+    }
+);
+
+/// Accepts a single request and returns its body.
+fn receive_one(listener: TcpListener) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let mut stream = reader.into_inner();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    })
+}
+
+#[test]
+fn posts_summary() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = receive_one(listener);
+
+    let (c, _) = do_run(args(["--notify-webhook", &url]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 1));
+
+    let body: Value = serde_json::from_str(&received.join().unwrap()).unwrap();
+    assert_eq!(body["summary"]["passed"], 1);
+    assert_eq!(body["summary"]["failed"], 1);
+    assert_eq!(body["failures"][0]["name"], "bad");
+    assert!(
+        body["failures"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("oops"),
+        "{body}"
+    );
+    let text = body["text"].as_str().unwrap();
+    assert!(
+        text.starts_with("Test run failed: 1 passed, 1 failed, 0 skipped in "),
+        "{text}"
+    );
+    assert!(text.contains("\n- bad: "), "{text}");
+}
+
+#[test]
+fn unreachable_webhook_does_not_fail_the_run() {
+    // Nothing listens on the port once the listener is dropped.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    drop(listener);
+
+    let (c, _) = do_run(args(["--notify-webhook", &url, "good"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 0));
+}
@@ -0,0 +1,36 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use async_test::{report::Event, run_with_observer, Tester, Trial};
+use common::args;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("observed", || async {}));
+    }
+);
+
+#[test]
+fn output_is_still_written() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("observer.log");
+    let mut args = args(["--color", "never"]);
+    args.logfile = Some(path.display().to_string());
+
+    let started = Arc::new(Mutex::new(vec![]));
+    let observed = started.clone();
+    let c = run_with_observer(&args, move |event: &Event| {
+        if let Event::TestStarted { name } = event {
+            observed.lock().unwrap().push(name.clone());
+        }
+    });
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(*started.lock().unwrap(), ["observed"]);
+
+    let out = std::fs::read_to_string(&path).unwrap();
+    assert!(out.contains("] observed\n"), "{out}");
+}
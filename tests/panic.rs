@@ -23,6 +23,11 @@ fn normal() {
             num_filtered_out: 0,
             num_passed: 1,
             num_failed: 1,
+            num_flaky: 0,
+            num_collection_errors: 0,
+            run_timed_out: false,
+            canceled: false,
+            signal: None,
         },
         "
             test passes ... ok
@@ -0,0 +1,69 @@
+use std::{error::Error, fmt, io};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+#[derive(Debug)]
+struct Report {
+    chain: Vec<&'static str>,
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        async_test::register_payload_formatter(|report: &Report| report.chain.join(": "));
+
+        tester.add(Trial::test("formatted", || async {
+            std::panic::panic_any(Report {
+                chain: vec!["request failed", "connection reset"],
+            });
+        }));
+        tester.add(Trial::test("boxed_error", || async {
+            let error: Box<dyn Error + Send + Sync> =
+                Box::new(Wrapped(io::Error::new(io::ErrorKind::Other, "disk full")));
+            std::panic::panic_any(error);
+        }));
+        tester.add(Trial::test("unknown", || async {
+            std::panic::panic_any(42_u32);
+        }));
+    }
+);
+
+#[derive(Debug)]
+struct Wrapped(io::Error);
+
+impl fmt::Display for Wrapped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to write the snapshot")
+    }
+}
+
+impl Error for Wrapped {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn payloads_are_formatted() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--color", "never"]));
+    assert_eq!(c.num_failed, 3);
+    assert!(
+        out.contains("thread 'main' panicked at 'request failed: connection reset'"),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "thread 'main' panicked at 'failed to write the snapshot\n\n\
+             Caused by:\n    \
+             0: disk full'"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains("thread 'main' panicked at 'test panicked'"),
+        "{out}"
+    );
+}
@@ -0,0 +1,60 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for i in 0..10 {
+            tester.add(Trial::test(format!("test_{i}"), || async {}));
+        }
+        tester.add(Trial::test("filtered", || async {}));
+    }
+);
+
+/// The names of the tests that ran with `--partition partition`.
+fn run(partition: &str) -> Vec<String> {
+    let (c, out) = do_run(args([
+        "--partition",
+        partition,
+        "--skip",
+        "filtered",
+        "--format",
+        "json",
+    ]));
+    assert_eq!(c.num_passed + c.num_filtered_out, 11);
+    let events: Vec<serde_json::Value> = out
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    for event in &events {
+        if event["type"] == "test-skipped" {
+            assert_eq!(event["reason"], "partition", "{event}");
+            let message = format!("not in partition {partition}");
+            assert_eq!(event["message"], message.as_str(), "{event}");
+        }
+    }
+    let mut ran: Vec<String> = events
+        .iter()
+        .filter(|event| event["type"] == "test-finished")
+        .map(|event| event["name"].as_str().unwrap().to_owned())
+        .collect();
+    ran.sort();
+    ran
+}
+
+#[test]
+fn parts_add_up_to_the_run() {
+    for kind in ["count", "hash"] {
+        let parts: Vec<_> = (1..=3).map(|i| run(&format!("{kind}:{i}/3"))).collect();
+        let mut all: Vec<String> = parts.concat();
+        all.sort();
+        let expected: Vec<String> = (0..10).map(|i| format!("test_{i}")).collect();
+        assert_eq!(all, expected, "{kind}: {parts:?}");
+        if kind == "count" {
+            let sizes: Vec<usize> = parts.iter().map(Vec::len).collect();
+            assert_eq!(sizes, [4, 3, 3]);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for name in ["first", "second"] {
+            tester.add(Trial::test(name, || async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }));
+        }
+    }
+);
+
+#[test]
+fn summary_and_verbose() {
+    let (_, out) = do_run(args(["--test-tasks", "1", "--verbose", "--color", "never"]));
+    let queued = out
+        .lines()
+        .find(|line| line.trim_start().starts_with("Queued "))
+        .unwrap_or_else(|| panic!("{out}"));
+    assert!(
+        queued.ends_with("] in total waiting for a free task (--test-tasks 1)"),
+        "{queued}"
+    );
+    assert_eq!(out.matches(" (queued for ").count(), 1, "{out}");
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args(["--test-tasks", "1", "--format", "json"]));
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let queued: Vec<f64> = events
+        .iter()
+        .filter(|event| event["type"] == "test-finished")
+        .map(|event| event["queued_secs"].as_f64().unwrap())
+        .collect();
+    assert_eq!(queued.len(), 2);
+    assert!(queued.iter().any(|&secs| secs >= 0.04), "{queued:?}");
+
+    let finished = events
+        .iter()
+        .find(|event| event["type"] == "run-finished")
+        .unwrap();
+    assert!(
+        finished["queue_wait_secs"].as_f64().unwrap() >= 0.04,
+        "{out}"
+    );
+}
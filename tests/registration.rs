@@ -0,0 +1,45 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+pub struct Config {}
+
+async_test::setup!(
+    async fn config() -> Config {
+        Config {}
+    }
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("registered", || async {}));
+    }
+);
+
+#[test]
+fn explain() {
+    let (c, out) = do_run(args(["--explain-registration"]));
+    assert_eq!(c.num_passed, 0);
+    // The order of registrations depends on the linker.
+    let mut lines: Vec<_> = out.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        [
+            "async_test::test_args: setup!",
+            "registration::config: setup!",
+            "registration::tests: tests!",
+        ]
+    );
+}
+
+#[test]
+fn own_crate_only() {
+    let (_, out) = do_run(args(["--own-crate-only", "--explain-registration"]));
+    assert!(!out.contains("ignored"), "{out}");
+
+    let (c, out) = do_run(args(["--own-crate-only"]));
+    assert_eq!(c.num_passed, 1, "{out}");
+}
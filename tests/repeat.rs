@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+static COUNTED: AtomicUsize = AtomicUsize::new(0);
+static FLAKY: AtomicUsize = AtomicUsize::new(0);
+static UNTIL: AtomicUsize = AtomicUsize::new(0);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("counted", || async {
+            COUNTED.fetch_add(1, Ordering::SeqCst);
+        }));
+        tester.add(Trial::test("flaky", || async {
+            let run = FLAKY.fetch_add(1, Ordering::SeqCst) + 1;
+            assert!(run != 3, "race lost");
+        }));
+        tester.add(Trial::test("until", || async {
+            let run = UNTIL.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            assert!(run != 10, "race lost");
+        }));
+    }
+);
+
+#[test]
+fn repeats_every_iteration() {
+    let (c, out) = do_run(args(["--repeat", "5", "--color", "never", "counted"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(COUNTED.load(Ordering::SeqCst), 5);
+    assert!(out.contains("counted (5 iterations, mean "), "{out}");
+}
+
+#[test]
+fn stops_at_first_failure() {
+    let (c, out) = do_run(args(["--repeat", "100", "--format", "json", "flaky"]));
+    assert_eq!(c.num_failed, 1);
+    assert_eq!(FLAKY.load(Ordering::SeqCst), 3);
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(finished["iterations"]["count"], 3);
+}
+
+#[test]
+fn repeats_until_failure() {
+    let (c, out) = do_run(args([
+        "--repeat-until-failure",
+        "1m",
+        "--color",
+        "never",
+        "until",
+    ]));
+    assert_eq!(c.num_failed, 1);
+    assert_eq!(UNTIL.load(Ordering::SeqCst), 10);
+    assert!(out.contains("until (failed on iteration 10)"), "{out}");
+}
+
+static SETUPS: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Counter(AtomicUsize);
+
+async_test::setup!(
+    async fn counter() -> Counter {
+        SETUPS.fetch_add(1, Ordering::SeqCst);
+        Counter(AtomicUsize::new(0))
+    }
+);
+
+async_test::test!(
+    async fn with_fixture(counter: &Counter) {
+        // A fixture shared with an earlier iteration was counted already.
+        assert_eq!(counter.0.fetch_add(1, Ordering::SeqCst), 0);
+    }
+);
+
+#[test]
+fn fresh_fixtures_per_iteration() {
+    let (c, out) = do_run(args(["--repeat", "3", "--color", "never", "with_fixture"]));
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert_eq!(SETUPS.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,69 @@
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+pub struct Database {}
+
+async_test::setup!(
+    async fn database() -> Database {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        Database {}
+    }
+);
+
+pub struct Unused {}
+
+async_test::setup!(
+    async fn unused() -> Unused {
+        Unused {}
+    }
+);
+
+async_test::test!(
+    async fn first(_db: &Database) {}
+);
+
+async_test::test!(
+    async fn second(_db: &Database) {}
+);
+
+async_test::test!(
+    async fn standalone() {}
+);
+
+#[test]
+fn printed_after_summary() {
+    let (_, out) = do_run(args(["--report-fixtures", "--color", "never"]));
+    let line = out
+        .lines()
+        .find(|line| line.trim_start().starts_with("FIXTURE "))
+        .unwrap_or_else(|| panic!("{out}"));
+    assert!(
+        line.ends_with("] database: used by 2 tests, 2 waiting at most"),
+        "{line}"
+    );
+    assert!(!out.contains("unused"), "{out}");
+}
+
+#[test]
+fn off_by_default() {
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains("FIXTURE"), "{out}");
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args(["--format", "json"]));
+    let usage = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "fixture-usage")
+        .unwrap_or_else(|| panic!("{out}"));
+    let fixture = &usage["fixtures"][0];
+    assert_eq!(fixture["name"], "database");
+    assert_eq!(fixture["tests"], 2);
+    assert_eq!(fixture["peak_waiters"], 2);
+    assert!(fixture["init_secs"].as_f64().unwrap() >= 0.05, "{fixture}");
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+pub struct Database {}
+
+async_test::setup!(
+    async fn database() -> Database {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Database {}
+    }
+);
+
+async_test::test!(
+    async fn first(_db: &Database) {}
+);
+
+async_test::test!(
+    async fn second(_db: &Database) {}
+);
+
+async_test::test!(
+    async fn long() {
+        tokio::time::sleep(Duration::from_millis(600)).await
+    }
+);
+
+#[test]
+fn causes() {
+    let (c, out) = do_run(args([
+        "--color",
+        "never",
+        "--test-tasks",
+        "3",
+        "--report-gaps",
+    ]));
+    assert_eq!(c.num_passed, 3, "{out}");
+    for part in [
+        "        Gaps [",
+        "of task time idle (",
+        "        IDLE [",
+        "waiting on fixture database, make it faster or cache its artifacts",
+        "long tail: the last test ran alone for ",
+        "split long (0.6",
+    ] {
+        assert!(out.contains(part), "{part}\n{out}");
+    }
+}
+
+#[test]
+fn off_by_default() {
+    let (_, out) = do_run(args(["--color", "never", "--test-tasks", "3"]));
+    assert!(!out.contains("Gaps"), "{out}");
+}
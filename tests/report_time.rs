@@ -0,0 +1,60 @@
+use std::{sync::OnceLock, time::Duration};
+
+use async_test::{clock::MockClock, Tester, Trial};
+use common::{args, do_run_with_clock};
+
+#[macro_use]
+mod common;
+
+static CLOCK: OnceLock<MockClock> = OnceLock::new();
+
+fn clock() -> &'static MockClock {
+    CLOCK.get_or_init(MockClock::new)
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for (name, millis) in [("quick", 0), ("warned", 700), ("critical", 2000)] {
+            tester.add(Trial::test(name, move || async move {
+                clock().advance(Duration::from_millis(millis));
+            }));
+        }
+    }
+);
+
+#[test]
+fn highlights_and_ensures_times() {
+    let run = |flag| {
+        do_run_with_clock(
+            args([
+                "--test-threads",
+                "1",
+                "--test-tasks",
+                "1",
+                "--color",
+                "never",
+                flag,
+            ]),
+            clock().clone(),
+        )
+    };
+
+    let (c, out) = run("--report-time");
+    assert_eq!((c.num_passed, c.num_failed), (3, 0), "{out}");
+    assert!(out.contains("PASS [   0.000s] quick\n"), "{out}");
+    assert!(
+        out.contains("PASS [   0.700s] warned (over the warning time of 0.5s)\n"),
+        "{out}"
+    );
+    assert!(
+        out.contains("PASS [   2.000s] critical (over the critical time of 1s)\n"),
+        "{out}"
+    );
+
+    let (c, out) = run("--ensure-time");
+    assert_eq!((c.num_passed, c.num_failed), (2, 1), "{out}");
+    assert!(
+        out.contains("test ran for 2.000s, longer than the critical time of 1s (--ensure-time)"),
+        "{out}"
+    );
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use async_test::{report::Event, run_with_observer, run_with_reporter, Tester, Trial};
+use common::args;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("first", || async {}));
+        tester.add(Trial::test("second", || async {}));
+    }
+);
+
+#[test]
+fn observer_panic_is_summarized() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("reporter_panic.log");
+    let mut args = args(["--color", "never", "--test-threads", "1"]);
+    args.logfile = Some(path.display().to_string());
+
+    let c = run_with_observer(&args, |event: &Event| {
+        if let Event::TestFinished(_) = event {
+            panic!("observer bug");
+        }
+    });
+    assert_eq!((c.num_passed, c.num_failed), (2, 0));
+
+    let out = std::fs::read_to_string(&path).unwrap();
+    assert!(out.contains("     Summary ["), "{out}");
+    assert!(
+        out.contains(
+            "    Disabled the observer panicked and got no events after that: observer bug, \
+            tests/reporter_panic.rs:"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn reporter_panic_keeps_running() {
+    let mut events = 0;
+    let c = run_with_reporter(&args([]), move |_: &Event| {
+        events += 1;
+        assert!(events < 2, "reporter bug");
+    });
+    assert_eq!((c.num_passed, c.num_failed), (2, 0));
+}
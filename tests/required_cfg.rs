@@ -0,0 +1,42 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    #[required_cfg(not(any(unix, windows)))]
+    async fn exotic() {
+        panic!("should never run")
+    }
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("everywhere", || async {}).with_required_cfg(true, "all()"));
+    }
+);
+
+#[test]
+fn skipped_elsewhere() {
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(c.num_failed, 0);
+    assert_eq!(c.num_filtered_out, 1);
+    assert!(
+        out.contains("SKIP [         ] exotic (requires cfg(not(any(unix, windows))))"),
+        "{out}"
+    );
+}
+
+#[test]
+fn list_verbose() {
+    let (_, out) = do_run(args(["--list", "--verbose", "--exact", "exotic"]));
+    assert_log!(
+        out,
+        "
+            exotic: test
+                skipped: requires cfg(not(any(unix, windows)))
+        "
+    );
+}
@@ -0,0 +1,97 @@
+use std::net::TcpListener;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    #[requires_docker]
+    async fn containers() {
+        panic!("should not run without docker")
+    }
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("online", || async {}).with_requires_network());
+        tester.add(Trial::test("offline", || async {}));
+    }
+);
+
+/// The probes read the environment, so they are checked in one test.
+#[test]
+fn requires() {
+    std::env::set_var("DOCKER_HOST", "unix:///nonexistent/docker.sock");
+
+    // Nothing listens on a port that was just released.
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    std::env::set_var("ASYNC_TEST_NETWORK_PROBE", closed.to_string());
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(c.num_failed, 0);
+    assert_eq!(c.num_filtered_out, 2);
+    assert!(
+        out.contains(&format!(
+            "SKIP [         ] online (requires network, but {closed} is unreachable: "
+        )),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "SKIP [         ] containers (requires docker, but \
+            unix:///nonexistent/docker.sock is unavailable: "
+        ),
+        "{out}"
+    );
+
+    let (_, out) = do_run(args(["--format", "json", "--exact", "containers"]));
+    let skipped = out
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|event| event["type"] == "test-skipped");
+    assert_eq!(skipped.unwrap()["reason"], "unavailable", "{out}");
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let open = listener.local_addr().unwrap();
+    std::env::set_var("ASYNC_TEST_NETWORK_PROBE", open.to_string());
+    let (c, _) = do_run(args(["--exact", "online"]));
+    assert_eq!(c.num_passed, 1);
+
+    let (_, out) = do_run(args(["--list", "--verbose", "--exact", "containers"]));
+    assert_log!(
+        out,
+        "
+            containers: test
+                requires: docker
+        "
+    );
+}
+
+#[test]
+fn offline() {
+    let (c, out) = do_run(args(["--offline", "--color", "never"]));
+    assert_eq!(c.num_passed, 1);
+    assert_eq!(c.num_filtered_out, 2);
+    assert!(
+        out.contains("SKIP [         ] online (offline, requires network)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("SKIP [         ] containers (offline, requires docker)"),
+        "{out}"
+    );
+    assert!(out.contains("Starting 1 test (2 offline)"), "{out}");
+    assert!(out.contains("1 passed, 2 offline"), "{out}");
+
+    let (_, out) = do_run(args(["--offline", "--format", "json"]));
+    let finished = out
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|event| event["type"] == "run-finished");
+    assert_eq!(finished.unwrap()["offline"], 2, "{out}");
+}
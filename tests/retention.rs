@@ -0,0 +1,45 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use async_test::{Screen, Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const CONFIG: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/retention.toml");
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("fails", || async {
+            Screen::capture("broken");
+            panic!("boom");
+        }));
+    }
+);
+
+#[test]
+fn prunes_stale_screens() {
+    std::fs::write(CONFIG, "[artifacts]\nretention = { days = 1 }\n").unwrap();
+    let run = || do_run(args(["--color", "never", "--retention", CONFIG]));
+    let (_, out) = run();
+    let (_, rest) = out.split_once("screen at failure, saved to ").unwrap();
+    let (path, _) = rest.split_once(":\n").unwrap();
+
+    // A screen of a test that was renamed a week ago.
+    let stale = Path::new(path).with_file_name("renamed-0000000000000000.txt");
+    std::fs::write(&stale, "old").unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&stale)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60))
+        .unwrap();
+
+    let (c, out) = run();
+    assert_eq!(c.num_failed, 1, "{out}");
+    assert!(!stale.exists(), "{}", stale.display());
+    assert!(Path::new(path).exists(), "{path}");
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+static OWN_RETRIES: AtomicUsize = AtomicUsize::new(0);
+static CLI_RETRIES: AtomicUsize = AtomicUsize::new(0);
+static NO_RETRIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Fails the first two times it is called.
+fn third_time_lucky(attempts: &AtomicUsize) {
+    let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+    assert!(attempt > 2, "attempt {attempt}");
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("own_retries", || async { third_time_lucky(&OWN_RETRIES) }).with_retries(2),
+        );
+        tester.add(Trial::test("cli_retries", || async {
+            third_time_lucky(&CLI_RETRIES)
+        }));
+        tester.add(
+            Trial::test("no_retries", || async { third_time_lucky(&NO_RETRIES) }).with_retries(0),
+        );
+    }
+);
+
+fn reset() {
+    for attempts in [&OWN_RETRIES, &CLI_RETRIES, &NO_RETRIES] {
+        attempts.store(0, Ordering::SeqCst);
+    }
+}
+
+/// Whether `out` has a status line starting with `status` for `name`.
+fn has_status(out: &str, status: &str, name: &str) -> bool {
+    out.lines()
+        .any(|line| line.trim_start().starts_with(status) && line.ends_with(&format!("] {name}")))
+}
+
+// A single test, as the trials count their attempts in statics.
+#[test]
+fn retries() {
+    reset();
+    let (c, out) = do_run(args(["--retries", "3", "--color", "never"]));
+    assert_eq!(
+        (c.num_passed, c.num_failed, c.num_flaky),
+        (2, 1, 2),
+        "{out}"
+    );
+
+    // Every failed attempt is shown, with its output.
+    assert!(has_status(&out, "TRY 1 FAIL [", "cli_retries"), "{out}");
+    assert!(has_status(&out, "TRY 2 FAIL [", "cli_retries"), "{out}");
+    assert!(
+        out.contains("\n--- TRY 2 ERROR:         cli_retries ---\n"),
+        "{out}"
+    );
+    assert!(has_status(&out, "TRY 3 PASS [", "cli_retries"), "{out}");
+    assert!(has_status(&out, "TRY 3 PASS [", "own_retries"), "{out}");
+    // The trial's own retries win over `--retries`.
+    assert!(has_status(&out, "FAIL [", "no_retries"), "{out}");
+    assert!(!has_status(&out, "TRY 1 FAIL [", "no_retries"), "{out}");
+    assert!(out.contains("2 passed (2 flaky), 1 failed"), "{out}");
+    // Flaky tests are listed again after the summary.
+    let summary = &out[out.find("Summary").unwrap()..];
+    assert!(
+        summary
+            .lines()
+            .any(|line| line.trim_start().starts_with("FLAKY [")
+                && line.ends_with("] cli_retries (passed on try 3)")),
+        "{out}"
+    );
+
+    reset();
+    let (_, out) = do_run(args(["--retries", "1", "--format", "json"]));
+    let attempts = |name: &str| {
+        out.lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .find(|event| event["type"] == "test-finished" && event["name"] == name)
+            .unwrap()["attempts"]
+            .clone()
+    };
+    assert_eq!(attempts("own_retries"), 3, "{out}");
+    assert_eq!(attempts("cli_retries"), 2, "{out}");
+    assert_eq!(attempts("no_retries"), 1, "{out}");
+}
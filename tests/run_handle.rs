@@ -0,0 +1,59 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+use async_test::{RunHandle, Tester, Trial};
+use common::{args, do_run_with_handle};
+
+#[macro_use]
+mod common;
+
+static HANDLE: OnceLock<RunHandle> = OnceLock::new();
+static RAN_LAST: AtomicBool = AtomicBool::new(false);
+
+fn handle() -> &'static RunHandle {
+    HANDLE.get_or_init(RunHandle::new)
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_pauses", || async {
+            handle().pause();
+            std::thread::spawn(|| {
+                std::thread::sleep(Duration::from_millis(20));
+                handle().resume();
+            });
+        }));
+        tester.add(Trial::test("b_cancels", || async {
+            handle().cancel("enough");
+        }));
+        tester.add(Trial::test("c_never_starts", || async {
+            RAN_LAST.store(true, Ordering::SeqCst);
+        }));
+    }
+);
+
+#[test]
+fn handle_controls_the_run() {
+    let (c, out) = do_run_with_handle(args(["--test-threads", "1", "--color", "never"]), handle());
+    assert_eq!(c.num_passed, 2, "{out}");
+    assert!(!RAN_LAST.load(Ordering::SeqCst));
+    assert!(handle().is_canceled());
+    assert!(c.canceled && c.has_failed());
+
+    assert!(out.contains("Pausing"), "{out}");
+    assert!(out.contains("Continuing"), "{out}");
+    assert!(out.contains("Canceling due to request (enough)"), "{out}");
+    assert!(
+        out.contains("NOT RUN [         ] c_never_starts (the run was canceled)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("2/3 tests run: 2 passed, 1 not run (canceled)"),
+        "{out}"
+    );
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+    }
+);
+
+fn read_log(path: &str) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is a JSON object"))
+        .collect()
+}
+
+#[test]
+fn starts_and_finishes_are_logged() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("run_log.jsonl");
+    _ = std::fs::remove_file(&path);
+    let path = path.to_str().unwrap();
+
+    let (c, out) = do_run(args(["--run-log", path]));
+    assert_eq!(c.num_failed, 1);
+    // The regular output is unchanged.
+    assert!(out.contains("Summary"), "{out}");
+
+    let records = read_log(path);
+    assert_eq!(records[0]["type"], "run-started");
+    assert_eq!(records.last().unwrap()["type"], "run-finished");
+    for name in ["passes", "fails"] {
+        let position = |ty: &str| {
+            records
+                .iter()
+                .position(|r| r["type"] == ty && r["name"] == name)
+                .unwrap_or_else(|| panic!("no {ty} for {name}"))
+        };
+        assert!(position("test-started") < position("test-finished"));
+    }
+
+    // Later runs are appended.
+    _ = do_run(args(["--run-log", path]));
+    let runs = read_log(path)
+        .iter()
+        .filter(|r| r["type"] == "run-started")
+        .count();
+    assert_eq!(runs, 2);
+}
@@ -0,0 +1,73 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_passes", || async {}));
+        tester.add(Trial::test("b_hangs", std::future::pending::<()>));
+        tester.add(Trial::test("c_never_starts", || async {}));
+    }
+);
+
+#[test]
+fn cancels_the_run() {
+    let (c, out) = do_run(args([
+        "--run-timeout",
+        "200ms",
+        "--test-tasks",
+        "1",
+        "--color",
+        "never",
+    ]));
+    assert!(c.run_timed_out, "{out}");
+    assert!(c.has_failed());
+    assert_eq!((c.num_passed, c.num_failed), (1, 1), "{out}");
+    assert!(
+        out.contains("Canceling due to run timeout (the run timed out after 200ms)"),
+        "{out}"
+    );
+    assert!(
+        out.lines()
+            .any(|line| line.trim_start().starts_with("TERMINATING [")
+                && line.ends_with("] b_hangs")),
+        "{out}"
+    );
+    assert!(
+        out.contains("NOT RUN [         ] c_never_starts (the run timed out)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("1 passed, 1 timed out, 1 not run (run timeout)"),
+        "{out}"
+    );
+}
+
+#[test]
+fn reported_as_not_run() {
+    let (_, out) = do_run(args([
+        "--run-timeout",
+        "200ms",
+        "--test-tasks",
+        "1",
+        "--format",
+        "json",
+    ]));
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let canceled = events.iter().find(|e| e["type"] == "run-canceled").unwrap();
+    assert_eq!(canceled["reason"], "the run timed out after 200ms");
+    let not_run: Vec<_> = events
+        .iter()
+        .filter(|e| e["type"] == "test-skipped" && e["reason"] == "run-timeout")
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(not_run, ["c_never_starts"], "{out}");
+    let finished = events.iter().find(|e| e["type"] == "run-finished").unwrap();
+    assert_eq!(finished["timed_out"], 1, "{out}");
+}
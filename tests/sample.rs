@@ -0,0 +1,100 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for i in 0..20 {
+            tester.add(Trial::test(format!("generated_{i}"), || async {}));
+        }
+        tester.add(Trial::test("other", || async {}));
+    }
+);
+
+fn finished_names(out: &str) -> Vec<String> {
+    let mut names: Vec<_> = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|event| event["type"] == "test-finished")
+        .map(|event| event["name"].as_str().unwrap().to_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn sample_count() {
+    let (c, out) = do_run(args(["--sample", "5", "--sample-seed", "7", "generated"]));
+    assert_eq!((c.num_passed, c.num_filtered_out), (5, 16));
+    assert!(
+        out.contains("Sample ran 5 of 20 tests with seed 7 (reproduce with --sample-seed 7)"),
+        "{out}"
+    );
+    assert!(!out.contains("             generated_"), "{out}");
+}
+
+#[test]
+fn verbose_lists_sample() {
+    let (_, out) = do_run(args([
+        "--sample",
+        "5",
+        "--sample-seed",
+        "7",
+        "--verbose",
+        "--color",
+        "never",
+        "generated",
+    ]));
+    let listed: Vec<_> = out
+        .lines()
+        .skip_while(|line| !line.contains("Sample ran 5 of 20 tests"))
+        .skip(1)
+        .take_while(|line| line.starts_with("             generated_"))
+        .collect();
+    assert_eq!(listed.len(), 5, "{out}");
+}
+
+#[test]
+fn sample_percent() {
+    let (c, _) = do_run(args(["--sample-percent", "50"]));
+    assert_eq!(c.num_passed, 11);
+}
+
+#[test]
+fn same_seed_same_sample() {
+    let run = |seed: &str| {
+        let (_, out) = do_run(args([
+            "--format",
+            "json",
+            "--sample",
+            "5",
+            "--sample-seed",
+            seed,
+        ]));
+        (finished_names(&out), out)
+    };
+    let (first, out) = run("3");
+    assert_eq!(first.len(), 5);
+    assert_eq!(first, run("3").0);
+    assert_ne!(first, run("4").0);
+
+    let summary = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "run-finished")
+        .unwrap();
+    assert_eq!(summary["sampled_out"], 16);
+
+    let started: Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+    let mut sampled: Vec<_> = started["sampled"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_str().unwrap().to_owned())
+        .collect();
+    sampled.sort();
+    assert_eq!(sampled, first);
+}
@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use async_test::{Screen, Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+static FIXED: AtomicBool = AtomicBool::new(false);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("menu_opens", || async {
+            Screen::capture("stale");
+            let screen: Vec<_> = (1..=5).map(|row| format!("row {row}")).collect();
+            Screen::capture(screen.join("\n"));
+            panic!("menu did not open");
+        }));
+        tester.add(Trial::test("renders", || async {
+            Screen::capture("fine");
+        }));
+        tester.add(Trial::test("fixed_later", || async {
+            Screen::capture("broken");
+            assert!(FIXED.load(Ordering::SeqCst));
+        }));
+    }
+);
+
+#[test]
+fn saved_on_failure() {
+    let (c, out) = do_run(args([
+        "--test-threads",
+        "1",
+        "--color",
+        "never",
+        "--screen-lines",
+        "3",
+        "--skip",
+        "fixed_later",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 1), "{out}");
+    assert!(
+        out.contains("  | row 1\n  | row 2\n  | row 3\n  ... 2 more lines\n"),
+        "{out}"
+    );
+    assert!(!out.contains("fine"), "{out}");
+
+    let (_, rest) = out.split_once("screen at failure, saved to ").unwrap();
+    let (path, _) = rest.split_once(":\n").unwrap();
+    assert!(path.contains("menu_opens-"), "{path}");
+    let saved = std::fs::read_to_string(path).unwrap();
+    assert_eq!(saved, "row 1\nrow 2\nrow 3\nrow 4\nrow 5");
+}
+
+#[test]
+fn removed_once_passing() {
+    let run = || do_run(args(["--color", "never", "--", "fixed_later"]));
+    let (c, out) = run();
+    assert_eq!(c.num_failed, 1, "{out}");
+    let (_, rest) = out.split_once("screen at failure, saved to ").unwrap();
+    let (path, _) = rest.split_once(":\n").unwrap();
+    assert!(std::path::Path::new(path).exists(), "{path}");
+
+    FIXED.store(true, Ordering::SeqCst);
+    let (c, out) = run();
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert!(!std::path::Path::new(path).exists(), "{path}");
+}
@@ -0,0 +1,56 @@
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+pub struct Database {}
+
+async_test::setup!(
+    async fn database() -> Database {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        Database {}
+    }
+);
+
+async_test::test!(
+    async fn uses_database(_db: &Database) {}
+);
+
+async_test::test!(
+    async fn standalone() {}
+);
+
+#[test]
+fn shown_next_to_the_duration() {
+    let (_, out) = do_run(args(["--color", "never"]));
+    let line = |name: &str| {
+        out.lines()
+            .find(|line| line.trim_start().starts_with("PASS ") && line.ends_with(name))
+            .unwrap_or_else(|| panic!("{name}\n{out}"))
+            .to_owned()
+    };
+    let uses_database = line("] uses_database");
+    assert!(uses_database.contains("s + 0.2"), "{uses_database}");
+    assert!(
+        uses_database.contains("s setup-wait] uses_database"),
+        "{uses_database}"
+    );
+    assert!(!line("] standalone").contains("setup-wait"), "{out}");
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args(["--format", "json"]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished" && event["name"] == "uses_database")
+        .unwrap();
+    assert!(
+        finished["setup_wait_secs"].as_f64().unwrap() >= 0.19,
+        "{out}"
+    );
+    // The setup is not part of the test's own duration.
+    assert!(finished["duration_secs"].as_f64().unwrap() < 0.19, "{out}");
+}
@@ -0,0 +1,67 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("panics", || async { panic!("boom") }).with_should_panic());
+        tester.add(
+            Trial::test("panics_with_message", || async {
+                panic!("index out of bounds: 3")
+            })
+            .with_should_panic_expected("out of bounds"),
+        );
+        tester.add(
+            Trial::test("panics_with_other_message", || async { panic!("boom") })
+                .with_should_panic_expected("out of bounds"),
+        );
+        tester.add(Trial::test("completes", || async {}).with_should_panic());
+    }
+);
+
+#[test]
+fn inverts_outcome() {
+    let (c, out) = do_run(args([
+        "--test-threads",
+        "1",
+        "--color",
+        "never",
+        "--skip",
+        "attribute",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 2), "{out}");
+    assert!(
+        out.contains("PASS [") && out.contains("] panics\n"),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "panic did not contain expected string\n      panic message: `\"boom\"`,\n \
+            expected substring: `\"out of bounds\"`"
+        ),
+        "{out}"
+    );
+    assert!(out.contains("test did not panic as expected"), "{out}");
+}
+
+#[test]
+fn macro_attribute() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--", "attribute"]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 0), "{out}");
+}
+
+async_test::test!(
+    #[should_panic]
+    async fn attribute_panics() {
+        panic!("boom");
+    }
+);
+
+async_test::test!(
+    #[should_panic(expected = "boom")]
+    async fn attribute_panics_with_message() {
+        panic!("a boom");
+    }
+);
@@ -0,0 +1,73 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        for i in 0..20 {
+            tester.add(Trial::test(format!("generated_{i:02}"), || async {}));
+        }
+    }
+);
+
+fn started_names(out: &str) -> Vec<String> {
+    out.lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|event| event["type"] == "test-started")
+        .map(|event| event["name"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+fn run(shuffle_seed: Option<u64>) -> (Vec<String>, Value) {
+    let mut args = args([
+        "--format",
+        "json",
+        "--test-threads",
+        "1",
+        "--test-tasks",
+        "1",
+    ]);
+    args.shuffle_seed = shuffle_seed;
+    let (c, out) = do_run(args);
+    assert_eq!(c.num_passed, 20);
+    let started = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+    (started_names(&out), started)
+}
+
+#[test]
+fn same_seed_same_order() {
+    let (unshuffled, started) = run(None);
+    assert_eq!(started["shuffle_seed"], Value::Null);
+    let mut sorted = unshuffled.clone();
+    sorted.sort();
+    assert_eq!(unshuffled, sorted);
+
+    let (first, started) = run(Some(3));
+    assert_eq!(started["shuffle_seed"], 3);
+    assert_ne!(first, unshuffled);
+    let mut sorted = first.clone();
+    sorted.sort();
+    assert_eq!(sorted, unshuffled);
+
+    assert_eq!(first, run(Some(3)).0);
+    assert_ne!(first, run(Some(4)).0);
+}
+
+#[test]
+fn seed_is_printed() {
+    let (_, out) = do_run(args(["--shuffle", "--color", "never"]));
+    let line = out.lines().find(|line| line.contains("Shuffle")).unwrap();
+    let seed = line.rsplit_once("--shuffle-seed ").unwrap().1;
+    let seed = seed.strip_suffix(')').unwrap();
+    assert_eq!(
+        line.trim(),
+        format!(
+            "Shuffle starting tests in random order with seed {seed} \
+            (reproduce with --shuffle-seed {seed})"
+        )
+    );
+    seed.parse::<u64>().unwrap();
+}
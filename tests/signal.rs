@@ -0,0 +1,42 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use async_test::{Signal, Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_interrupts", || async {
+            let pid = std::process::id().to_string();
+            let status = std::process::Command::new("kill")
+                .args(["-INT", &pid])
+                .status()
+                .unwrap();
+            assert!(status.success());
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }));
+        tester.add(Trial::test("b_not_started", || async {}));
+    }
+);
+
+#[test]
+fn signal_is_returned_and_scoped_to_the_run() {
+    let (c, out) = do_run(args(["--test-threads", "1", "--color", "never"]));
+    assert_eq!(c.signal, Some(Signal::Interrupt), "{out}");
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert!(c.has_failed());
+    assert!(
+        out.contains("b_not_started (the run was interrupted)"),
+        "{out}"
+    );
+
+    // The signal of the first run does not reach the next one.
+    let (c, out) = do_run(args(["b_not_started", "--color", "never"]));
+    assert_eq!(c.signal, None, "{out}");
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert!(!c.has_failed());
+}
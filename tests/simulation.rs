@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+
+use async_test::{sim::Script, Tester};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+/// The script of the current run.
+static SCRIPT: Mutex<Option<Script>> = Mutex::new(None);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        if let Some(script) = &*SCRIPT.lock().unwrap() {
+            script.add_to(&tester);
+        }
+    }
+);
+
+#[test]
+fn invariants_hold() {
+    for seed in 0..20 {
+        let script = Script::random(seed);
+        *SCRIPT.lock().unwrap() = Some(script.clone());
+
+        // Vary the concurrency and the order in which tests make progress.
+        let tasks = seed as usize % 4 + 1;
+        let (c, report) = do_run(args([
+            "--format",
+            "json",
+            "--test-threads",
+            "2",
+            "--test-tasks",
+            &tasks.to_string(),
+            "--chaos",
+            &format!("seed={seed}"),
+        ]));
+        if let Err(e) = script.check(&c, tasks, &report) {
+            panic!("seed {seed}: {e}");
+        }
+    }
+}
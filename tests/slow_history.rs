@@ -0,0 +1,61 @@
+use std::{path::Path, sync::OnceLock, time::Duration};
+
+use async_test::{clock::MockClock, Tester, Trial};
+use common::{args, do_run_with_clock};
+
+#[macro_use]
+mod common;
+
+static CLOCK: OnceLock<MockClock> = OnceLock::new();
+
+fn clock() -> &'static MockClock {
+    CLOCK.get_or_init(MockClock::new)
+}
+
+const HISTORY: &str = r#"
+{"schema_version":1,"type":"test-finished","name":"regressed","status":"passed","duration_secs":0.9}
+{"schema_version":1,"type":"test-finished","name":"regressed","status":"passed","duration_secs":1.0}
+{"schema_version":1,"type":"test-finished","name":"regressed","status":"passed","duration_secs":1.2}
+"#;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("regressed", || async {
+            clock().advance(Duration::from_secs(5));
+            // Lets the harness notice the slow period has passed.
+            tokio::task::yield_now().await;
+        }));
+    }
+);
+
+#[test]
+fn slow_relative_to_median() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("slow_history.json");
+    std::fs::write(&path, HISTORY).unwrap();
+
+    let (c, out) = do_run_with_clock(
+        args([
+            "--test-threads",
+            "1",
+            "--slow-report-interval",
+            "0",
+            "--slow-history",
+            path.to_str().unwrap(),
+            "--slow-factor",
+            "2",
+        ]),
+        clock().clone(),
+    );
+    assert_eq!(c.num_passed, 1);
+    assert_log!(
+        out,
+        "
+            Starting 1 test
+                SLOW [>  2.000s] regressed
+                PASS [   5.000s] regressed
+        ------------
+             Summary [   5.000s] 1 test run: 1 passed (1 slow), 0 skipped
+                SLOW [   5.000s] regressed
+        "
+    );
+}
@@ -0,0 +1,75 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+pub struct Shared(u32);
+pub struct Database(u32);
+
+async_test::setup!(
+    async fn shared() -> Shared {
+        Shared(1)
+    }
+);
+
+async_test::setup!(
+    suite = "db",
+    async fn database() -> Database {
+        Database(2)
+    }
+);
+
+async_test::test!(
+    async fn unit() {}
+);
+
+async_test::test!(
+    suite = "db",
+    async fn query(shared: &Shared, database: &Database) {
+        assert_eq!(shared.0 + database.0, 3);
+    }
+);
+
+async_test::tests!(
+    suite = "db",
+    fn generated(tester: Tester) {
+        tester.add(Trial::test("generated", || async {}));
+    }
+);
+
+#[test]
+fn without_suite() {
+    let (c, out) = do_run(args(["--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 0), "{out}");
+    assert!(out.contains("PASS ["), "{out}");
+    assert!(out.contains("] unit\n"), "{out}");
+}
+
+#[test]
+fn with_suite() {
+    let (c, out) = do_run(args(["--suite", "db", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 0), "{out}");
+    assert!(!out.contains("] unit\n"), "{out}");
+
+    let (c, out) = do_run(args(["--suite", "ui"]));
+    assert_eq!(c.num_passed, 0, "{out}");
+}
+
+#[test]
+fn explain() {
+    let (_, out) = do_run(args(["--suite", "db", "--explain-registration"]));
+    let mut lines: Vec<_> = out.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        [
+            "async_test::test_args: setup!",
+            "suite::database: setup! in suite db",
+            "suite::generated: tests! in suite db",
+            "suite::query: tests! in suite db",
+            "suite::shared: setup!",
+            "suite::unit: tests! (ignored: not in suite db)",
+        ]
+    );
+}
@@ -0,0 +1,51 @@
+use std::{sync::OnceLock, time::Duration};
+
+use async_test::{clock::MockClock, Tester, Trial};
+use common::{args, do_run_with_clock};
+
+#[macro_use]
+mod common;
+
+static CLOCK: OnceLock<MockClock> = OnceLock::new();
+
+fn clock() -> &'static MockClock {
+    CLOCK.get_or_init(MockClock::new)
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_long", || async {
+            clock().advance(Duration::from_secs(61));
+        }));
+        tester.add(Trial::test("b_short", || async {}));
+    }
+);
+
+#[test]
+fn prints_interim_summaries() {
+    let (c, out) = do_run_with_clock(
+        args(["--test-threads", "1", "--summary-interval", "1m"]),
+        clock().clone(),
+    );
+    assert_eq!(c.num_passed, 2);
+
+    let lines: Vec<&str> = out.lines().map(str::trim).collect();
+    let progress = lines
+        .iter()
+        .position(|line| line.starts_with("Progress"))
+        .unwrap_or_else(|| panic!("no interim summary in\n{out}"));
+    // The reporter may see the clock move before or after `a_long` finishes.
+    assert!(
+        lines[progress].starts_with("Progress [  61.000s] ")
+            && lines[progress].contains("/2 tests run: "),
+        "{out}"
+    );
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.starts_with("Progress"))
+            .count(),
+        1,
+        "{out}"
+    );
+}
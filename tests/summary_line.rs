@@ -0,0 +1,36 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.warn("not shown either");
+        tester.add(Trial::test("passes", || async {}));
+        tester.add(Trial::test("fails", || async { panic!("boom") }));
+        tester.add(Trial::test("ignored", || async {}).with_ignored_flag(true));
+    }
+);
+
+fn without_time(out: &str) -> &str {
+    let (line, secs) = out.rsplit_once(" in ").unwrap();
+    assert!(secs.ends_with("s\n"), "{out}");
+    secs.trim_end_matches("s\n").parse::<f64>().unwrap();
+    line
+}
+
+#[test]
+fn critical() {
+    let (c, out) = do_run(args(["--format", "summary-line"]));
+    assert_eq!(c.num_failed, 1);
+    assert_eq!(out.lines().count(), 1, "{out}");
+    assert_eq!(without_time(&out), "CRITICAL 1 passed, 1 failed, 1 skipped");
+}
+
+#[test]
+fn ok() {
+    let (_, out) = do_run(args(["--format", "summary-line", "--skip", "fails"]));
+    assert_eq!(out.lines().count(), 1, "{out}");
+    assert_eq!(without_time(&out), "OK 1 passed, 0 failed, 2 skipped");
+}
@@ -0,0 +1,39 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("lint_fmt", || async {}).with_tags(["lint"]));
+        tester
+            .add(Trial::test("lint_docs", || async { panic!("missing docs") }).with_tags(["lint"]));
+        tester.add(Trial::test("golden_help", || async {}).with_tags(["golden", "lint"]));
+        tester.add(Trial::test("untagged", || async {}));
+    }
+);
+
+#[test]
+fn per_tag_breakdown() {
+    let (_, out) = do_run(args(["--summary-by-tag", "--color", "never"]));
+    let lines: Vec<_> = out
+        .lines()
+        .filter(|line| line.trim_start().starts_with("Tag "))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "         Tag golden: 1 passed",
+            "         Tag lint: 2 passed, 1 failed",
+            "         Tag (untagged): 1 passed",
+        ],
+        "{out}"
+    );
+}
+
+#[test]
+fn off_by_default() {
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains(" Tag "), "{out}");
+}
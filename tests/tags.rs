@@ -0,0 +1,61 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    /// Doc comments are kept on the function.
+    #[tags(smoke)]
+    async fn quick() {}
+);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("fetch", || async {}).with_tags(["smoke", "net"]));
+        tester.add(Trial::test("upload", || async {}).with_tags(["net"]));
+        tester.add(Trial::test("untagged", || async {}));
+    }
+);
+
+fn list(extra: &[&str]) -> Vec<String> {
+    let mut v = vec!["<dummy-executable>", "--list"];
+    v.extend(extra);
+    let (_, out) = do_run(v.into_iter().collect());
+    let mut names: Vec<_> = out.lines().map(str::to_owned).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn include() {
+    assert_eq!(list(&["--tag", "smoke"]), ["fetch: test", "quick: test"]);
+    assert_eq!(
+        list(&["--tag", "smoke", "--tag", "net"]),
+        ["fetch: test", "quick: test", "upload: test"]
+    );
+}
+
+#[test]
+fn exclude() {
+    assert_eq!(
+        list(&["--exclude-tag", "net"]),
+        ["quick: test", "untagged: test"]
+    );
+    assert_eq!(
+        list(&["--tag", "smoke", "--exclude-tag", "net"]),
+        ["quick: test"]
+    );
+}
+
+#[test]
+fn list_verbose() {
+    let (_, out) = do_run(args(["--list", "--verbose", "--exact", "fetch"]));
+    assert_log!(
+        out,
+        "
+            fetch: test
+                tags: smoke, net
+        "
+    );
+}
@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        let template = Trial::template().with_tags(["generated"]).with_retries(1);
+        tester.add(template.test("plain", || async {}));
+        tester.add(template.test("flaky", || async {
+            assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0, "first attempt");
+        }));
+        tester.add(template.fallible("fallible", || async { Err("broken") }));
+        // Stamped trials can still be configured on their own.
+        tester.add(template.test("extra", || async {}).with_tags(["slow"]));
+        tester.add(Trial::test("untemplated", || async {}));
+    }
+);
+
+#[test]
+fn trials_share_configuration() {
+    let (c, out) = do_run(args(["--tag", "generated", "--format", "json"]));
+    assert_eq!((c.num_passed, c.num_failed), (3, 1), "{out}");
+
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let result = |name: &str| {
+        events
+            .iter()
+            .find(|event| event["type"] == "test-finished" && event["name"] == name)
+            .unwrap()
+    };
+    assert_eq!(result("flaky")["attempts"], 2);
+    assert_eq!(result("fallible")["attempts"], 2);
+    assert_eq!(result("plain")["attempts"], 1);
+
+    let (c, _) = do_run(args(["--tag", "slow"]));
+    assert_eq!(c.num_passed, 1);
+}
@@ -0,0 +1,32 @@
+use async_test::TestArgs;
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn reads_args(args: &TestArgs) {
+        assert_eq!(args.get("url"), Some("http://staging"));
+        assert_eq!(args.get("profile"), Some("ci=readonly"));
+        assert_eq!(args.get("missing"), None);
+    }
+);
+
+#[test]
+fn passed_to_tests() {
+    let (c, out) = do_run(args([
+        "--test-arg",
+        "url=http://localhost",
+        "--test-arg",
+        "url=http://staging",
+        "--test-arg",
+        "profile=ci=readonly",
+    ]));
+    assert_eq!(c.num_passed, 1, "{out}");
+}
+
+#[test]
+fn missing_args() {
+    let (c, _) = do_run(args([]));
+    assert_eq!(c.num_failed, 1);
+}
@@ -0,0 +1,46 @@
+use async_test::TestMeta;
+use common::{args, do_run};
+use serde_json::{json, Value};
+
+#[macro_use]
+mod common;
+
+async_test::test!(
+    async fn measures() {
+        TestMeta::record("rows_verified", 1000);
+        TestMeta::record("p99_ms", 12.5);
+        TestMeta::record("p99_ms", 12.25);
+        TestMeta::record("backend", "sqlite");
+    }
+);
+
+async_test::test!(
+    async fn plain() {}
+);
+
+#[test]
+fn verbose() {
+    let (_, out) = do_run(args(["--verbose", "--color", "never"]));
+    assert!(
+        out.contains("measures (backend=sqlite, p99_ms=12.25, rows_verified=1000)\n"),
+        "{out}"
+    );
+    assert!(out.contains("plain\n"), "{out}");
+
+    let (_, out) = do_run(args(["--color", "never"]));
+    assert!(!out.contains("rows_verified"), "{out}");
+}
+
+#[test]
+fn json() {
+    let (_, out) = do_run(args(["--format", "json", "--exact", "measures"]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(
+        finished["meta"],
+        json!({ "backend": "sqlite", "p99_ms": 12.25, "rows_verified": 1000 })
+    );
+}
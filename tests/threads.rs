@@ -0,0 +1,36 @@
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("named", || async {
+            let name = std::thread::current().name().unwrap().to_owned();
+            assert!(name.starts_with("async-test-worker-"), "{name}");
+        }));
+        tester.add(Trial::test("deep", || async {
+            fn recurse(depth: u32) -> u32 {
+                let padding = std::hint::black_box([0u8; 1024]);
+                match depth {
+                    0 => padding[0] as u32,
+                    _ => recurse(depth - 1) + padding[1] as u32,
+                }
+            }
+            // Needs more than the default stack size of 2 MiB.
+            assert_eq!(recurse(8 * 1024), 0);
+        }));
+    }
+);
+
+#[test]
+fn worker_threads() {
+    let (c, out) = do_run(args([
+        "--test-threads",
+        "2",
+        "--thread-stack-size",
+        &(64 * 1024 * 1024).to_string(),
+    ]));
+    assert_eq!(c.num_passed, 2, "{out}");
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use async_test::TestMeta;
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+const THRESHOLDS: &str = r#"
+# SLOs of the staging environment.
+[metrics]
+p99_ms = { max = 50 }
+rows_verified = { min = 1000, max = 5000 }
+
+[other-tool]
+ignored = true
+"#;
+
+async_test::test!(
+    async fn fast() {
+        TestMeta::record("p99_ms", 12.3);
+        TestMeta::record("rows_verified", 1000);
+    }
+);
+
+async_test::test!(
+    async fn slow() {
+        TestMeta::record("p99_ms", 62.5);
+        TestMeta::record("rows_verified", 10);
+    }
+);
+
+async_test::test!(
+    async fn unmeasured() {}
+);
+
+#[test]
+fn breaches_fail_the_test() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("thresholds.toml");
+    std::fs::write(&path, THRESHOLDS).unwrap();
+
+    let (c, out) = do_run(args([
+        "--thresholds",
+        path.to_str().unwrap(),
+        "--color",
+        "never",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (2, 1), "{out}");
+    assert!(out.contains("FAIL ["), "{out}");
+    assert!(
+        out.contains("metric p99_ms = 62.5 is above its maximum of 50\n"),
+        "{out}"
+    );
+    assert!(
+        out.contains("metric rows_verified = 10 is below its minimum of 1000\n"),
+        "{out}"
+    );
+
+    let (c, _) = do_run(args([]));
+    assert_eq!(c.num_failed, 0);
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("a_uses_up_the_budget", || async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }));
+        tester.add(Trial::test("b_never_starts", || async {}));
+        tester.add(Trial::test("c_never_starts", || async {}));
+    }
+);
+
+#[test]
+fn stops_starting_tests() {
+    let (c, out) = do_run(args([
+        "--time-budget",
+        "50ms",
+        "--test-tasks",
+        "1",
+        "--color",
+        "never",
+    ]));
+    assert_eq!(c.num_passed, 1, "{out}");
+    assert_eq!(c.num_failed, 0, "{out}");
+    assert!(
+        out.contains("1/3 tests run: 1 passed, 2 not run (budget exceeded), 0 skipped"),
+        "{out}"
+    );
+    assert!(
+        out.contains("NOT RUN [         ] b_never_starts (time budget exceeded)"),
+        "{out}"
+    );
+}
+
+#[test]
+fn reported_as_not_run() {
+    let (_, out) = do_run(args([
+        "--time-budget",
+        "50ms",
+        "--test-tasks",
+        "1",
+        "--format",
+        "json",
+    ]));
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let not_run: Vec<_> = events
+        .iter()
+        .filter(|e| e["type"] == "test-skipped" && e["reason"] == "budget")
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(not_run, ["b_never_starts", "c_never_starts"]);
+    let summary = events.last().unwrap();
+    assert_eq!(summary["not_run"], 2);
+    assert_eq!(summary["skipped"], 0);
+}
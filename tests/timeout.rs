@@ -0,0 +1,61 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+
+#[macro_use]
+mod common;
+
+static DROPPED: AtomicBool = AtomicBool::new(false);
+
+/// Records that the future of the test holding it was dropped.
+struct DropGuard;
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED.store(true, Ordering::SeqCst);
+    }
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(
+            Trial::test("own_timeout", || async {
+                let _guard = DropGuard;
+                std::future::pending::<()>().await
+            })
+            .with_timeout(Duration::from_millis(50)),
+        );
+        tester.add(Trial::test("cli_timeout", std::future::pending::<()>));
+        tester.add(
+            Trial::test("long_timeout", || async {
+                tokio::time::sleep(Duration::from_millis(100)).await
+            })
+            .with_timeout(Duration::from_secs(60)),
+        );
+    }
+);
+
+/// Whether `out` has a status line starting with `status` for `name`.
+fn has_status(out: &str, status: &str, name: &str) -> bool {
+    out.lines()
+        .any(|line| line.trim_start().starts_with(status) && line.ends_with(&format!("] {name}")))
+}
+
+#[test]
+fn timeout() {
+    let (c, out) = do_run(args(["--timeout", "50ms", "--color", "never"]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 2), "{out}");
+
+    for name in ["own_timeout", "cli_timeout"] {
+        assert!(has_status(&out, "TERMINATING [", name), "{out}");
+        assert!(has_status(&out, "TIMEOUT [", name), "{out}");
+    }
+    // The trial's own timeout wins over `--timeout`.
+    assert!(has_status(&out, "PASS [", "long_timeout"), "{out}");
+    assert!(out.contains("2 timed out"), "{out}");
+    assert!(DROPPED.load(Ordering::SeqCst), "the future was not dropped");
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+const PATH: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/timeline.json");
+
+pub struct Database {}
+
+async_test::setup!(
+    async fn database() -> Database {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Database {}
+    }
+);
+
+async_test::test!(
+    async fn first(_db: &Database) {
+        tokio::time::sleep(Duration::from_millis(50)).await
+    }
+);
+
+async_test::test!(
+    async fn second(_db: &Database) {
+        tokio::time::sleep(Duration::from_millis(50)).await
+    }
+);
+
+async_test::test!(
+    async fn third() {
+        tokio::time::sleep(Duration::from_millis(50)).await
+    }
+);
+
+#[test]
+fn timeline() {
+    _ = std::fs::remove_file(PATH);
+    let (c, out) = do_run(args(["--test-threads", "2", "--trace-timeline", PATH]));
+    assert_eq!(c.num_passed, 3, "{out}");
+
+    let trace: Value = serde_json::from_str(&std::fs::read_to_string(PATH).unwrap()).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+    let spans: Vec<_> = events.iter().filter(|e| e["ph"] == "X").collect();
+    let mut tests: Vec<_> = spans
+        .iter()
+        .filter(|e| e["cat"] == "test")
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    tests.sort();
+    assert_eq!(tests, ["first", "second", "third"], "{trace}");
+    assert!(spans
+        .iter()
+        .filter(|e| e["cat"] == "test")
+        .all(|e| e["args"]["result"] == "passed" && e["dur"].as_u64().unwrap() >= 50_000));
+    assert_eq!(spans.iter().filter(|e| e["cat"] == "fixture").count(), 1);
+
+    // No more lanes than tests that can run at once, each with a name.
+    let lanes: Vec<_> = events
+        .iter()
+        .filter(|e| e["name"] == "thread_name")
+        .map(|e| e["tid"].as_u64().unwrap())
+        .collect();
+    assert!(!lanes.is_empty() && lanes.len() <= 2, "{trace}");
+    assert!(spans
+        .iter()
+        .all(|e| lanes.contains(&e["tid"].as_u64().unwrap())));
+}
@@ -0,0 +1,72 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+async fn grouped() {
+    let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+    MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+}
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester
+            .add(Trial::test("hangs", std::future::pending::<()>))
+            .timeout(Duration::from_millis(50));
+        tester
+            .add(Trial::test("flaky", || async {
+                assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0, "first attempt");
+            }))
+            .retries(2);
+        for name in ["grouped_a", "grouped_b", "grouped_c"] {
+            tester.add(Trial::test(name, grouped)).group("port-8080");
+        }
+        let handle = tester.add(Trial::test("debugged", || async {}));
+        assert_eq!(
+            format!("{handle:?}"),
+            r#"TrialHandle { name: "debugged", .. }"#
+        );
+    }
+);
+
+#[test]
+fn handle_configures_trials() {
+    let (c, out) = do_run(args(["--test-threads", "4", "--format", "json"]));
+    assert_eq!((c.num_passed, c.num_failed), (5, 1), "{out}");
+    assert_eq!(MAX_RUNNING.load(Ordering::SeqCst), 1);
+
+    let events: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let result = |name: &str| {
+        events
+            .iter()
+            .find(|event| event["type"] == "test-finished" && event["name"] == name)
+            .unwrap()
+    };
+    assert_eq!(result("hangs")["status"], "failed");
+    assert!(result("hangs")["output"]
+        .as_str()
+        .unwrap()
+        .contains("timed out after 50ms"));
+    assert_eq!(result("flaky")["status"], "passed");
+    assert_eq!(result("flaky")["attempts"], 2);
+
+    let summary = events.last().unwrap();
+    assert_eq!(summary["timed_out"], 1, "{summary}");
+    assert_eq!(summary["flaky"], 1, "{summary}");
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_test::{Tester, Trial};
+use common::{args, do_run};
+use serde_json::Value;
+
+#[macro_use]
+mod common;
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+async_test::tests!(
+    fn tests(tester: Tester) {
+        tester.add(Trial::test("stable", || async {}));
+        tester.add(Trial::test("broken", || async { panic!("always") }));
+        tester.add(Trial::test("racy", || async {
+            let run = RUNS.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            assert!(run % 2 == 0, "lost the race");
+        }));
+    }
+);
+
+#[test]
+fn flags_differing_outcomes() {
+    let (c, out) = do_run(args([
+        "--verify-deterministic",
+        "4",
+        "--test-threads",
+        "1",
+        "--color",
+        "never",
+    ]));
+    assert_eq!((c.num_passed, c.num_failed), (1, 2));
+    assert!(
+        out.contains("nondeterministic: 2 of 4 runs failed, the first failure follows"),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            " Determinism 1 test had different outcomes across runs:\n  2/4 failed racy\n"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn reported_in_json() {
+    let (_, out) = do_run(args([
+        "--verify-deterministic",
+        "3",
+        "--format",
+        "json",
+        "broken",
+    ]));
+    let finished = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .find(|event| event["type"] == "test-finished")
+        .unwrap();
+    assert_eq!(finished["determinism"]["runs"], 3);
+    assert_eq!(finished["determinism"]["failed"], 3);
+}